@@ -0,0 +1,104 @@
+import itertools
+import json
+import socket
+
+
+class PhysicalWorldError(Exception):
+
+  def __init__(self, code, message):
+    super().__init__("{} ({})".format(message, code))
+    self.code = code
+    self.message = message
+
+
+class RemoteActuator(object):
+  """
+  Actuator of the physical world model, usable by the controller of the runtime environment
+  """
+
+  def __init__(self, physical_world, identity):
+    self.physical_world = physical_world
+    self.identity = identity
+
+  def set_input_signal(self, input_signal):
+    self.physical_world.set_input_signal(self.identity, input_signal)
+
+  def release(self):
+    self.physical_world.release_actuator(self.identity)
+
+
+class PhysicalWorld(object):
+  """
+  Client of the physical world model, started with `physical_machine serve`
+  - Newline-delimited JSON-RPC 2.0 over TCP
+  - Quantities in SI units, vectors as [x, y, z]
+  """
+
+  def __init__(self, host = "127.0.0.1", port = 7878):
+    self.connection = socket.create_connection((host, port))
+    self.reader = self.connection.makefile("r", encoding = "utf-8")
+    self.ids = itertools.count(1)
+
+  def close(self):
+    self.reader.close()
+    self.connection.close()
+
+  def __enter__(self):
+    return self
+
+  def __exit__(self, *_):
+    self.close()
+
+  def call(self, method, **params):
+    request = { "jsonrpc" : "2.0", "id" : next(self.ids), "method" : method, "params" : params }
+    self.connection.sendall((json.dumps(request) + "\n").encode("utf-8"))
+    line = self.reader.readline()
+    if not line:
+      raise ConnectionError("Physical world closed the connection")
+    response = json.loads(line)
+    if "error" in response:
+      raise PhysicalWorldError(response["error"]["code"], response["error"]["message"])
+    return response["result"]
+
+  # Exactly one of example, path or scene (a dict in the scene file format)
+  def load_scene(self, example = None, path = None, scene = None):
+    params = { "example" : example, "path" : path, "scene" : scene }
+    return self.call("load_scene", **{ k : v for k, v in params.items() if v is not None })
+
+  def step(self, steps = 1, time_step = None):
+    if time_step is None:
+      return self.call("step", steps = steps)["time"]
+    return self.call("step", steps = steps, time_step = time_step)["time"]
+
+  def get_time(self):
+    return self.call("get_time")
+
+  def get_entities(self):
+    return self.call("get_entities")
+
+  def get_actuators(self):
+    return self.call("get_actuators")
+
+  def set_input_signal(self, actuator, signal):
+    self.call("set_input_signal", actuator = actuator, signal = signal)
+
+  def release_actuator(self, actuator):
+    self.call("release_actuator", actuator = actuator)
+
+  def get_remote_actuators(self):
+    return [RemoteActuator(self, a["identity"]) for a in self.get_actuators()]
+
+  def get_measurements(self, sensors = None):
+    if sensors is None:
+      return self.call("get_measurements")
+    return self.call("get_measurements", sensors = sensors)
+
+  # Simulates a fork of the world, actions are dicts like
+  # { "kind" : "change_velocity", "entity" : "A", "velocity_change" : [1, 0, 0], "at" : 0 }
+  def predict(self, horizon, actions = [], step_size = None):
+    if step_size is None:
+      return self.call("predict", horizon = horizon, actions = actions)
+    return self.call("predict", horizon = horizon, actions = actions, step_size = step_size)
+
+  def shutdown(self):
+    self.call("shutdown")
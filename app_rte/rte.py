@@ -14,6 +14,7 @@ class RuntimeEnvironment(object):
     )
     self.observer = Observer()
     self.controller = Controller()
+    self.physical_world = None
 
   def get_ordered_descriptions(self):
     return self.ordered_descriptions
@@ -27,6 +28,10 @@ class RuntimeEnvironment(object):
   def set_actuators(self, actuators):
     self.actuators = actuators
 
+  # Client of a running physical world model, see physical_world.py
+  def set_physical_world(self, physical_world):
+    self.physical_world = physical_world
+
   def call_behavior(self, description):
     # TODO: OS.get_sensors()
     # TODO: OS.get_actuators()
@@ -61,6 +66,12 @@ class RuntimeEnvironment(object):
     for description in self.ordered_descriptions:
       self.call_behavior(description)
 
+  # Applies the behaviors, then advances the physical world model by the given steps
+  def step(self, steps = 1):
+    self.call_behaviors()
+    if self.physical_world is not None:
+      return self.physical_world.step(steps)
+
 
 class Controller(object):
 
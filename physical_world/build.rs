@@ -0,0 +1,24 @@
+// Regenerates the header of the C interface if the header feature is enabled, other builds
+// use the header in the repository
+#[cfg(feature = "header")]
+fn main() {
+    use std::env;
+    use std::path::PathBuf;
+
+    println!("cargo:rerun-if-changed=src/ffi");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    cbindgen::Builder::new()
+        .with_src(crate_dir.join("src/ffi/types.rs"))
+        .with_src(crate_dir.join("src/ffi/system.rs"))
+        .with_config(config)
+        .generate()
+        .expect("Cannot generate the C header.")
+        .write_to_file(crate_dir.join("include/physical_machine.h"));
+}
+
+#[cfg(not(feature = "header"))]
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+}
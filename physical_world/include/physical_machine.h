@@ -0,0 +1,165 @@
+/* Generated by cbindgen from src/ffi, do not edit. */
+
+#ifndef PHYSICAL_MACHINE_H
+#define PHYSICAL_MACHINE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum PmShapeKind {
+  PM_SHAPE_NONE = 0,
+  PM_SHAPE_SPHERE = 1,
+  PM_SHAPE_CUBOID = 2,
+} PmShapeKind;
+
+// Result of every function of the C interface
+typedef enum PmStatus {
+  PM_OK = 0,
+  PM_NULL_POINTER = 1,
+  PM_INVALID_ARGUMENT = 2,
+  PM_NOT_FOUND = 3,
+  PM_DUPLICATE_IDENTITY = 4,
+  PM_SCENE_ERROR = 5,
+  // The world model failed internally, the system must not be used anymore
+  PM_PANIC = 6,
+} PmStatus;
+
+// Opaque handle of a system, created by pm_system_new* and released by pm_system_free
+typedef struct PmSystem PmSystem;
+
+typedef struct PmVector {
+  double x;
+  double y;
+  double z;
+} PmVector;
+
+// Radius is used by spheres, half extents by cuboids, both in m
+typedef struct PmShape {
+  enum PmShapeKind kind;
+  double radius;
+  struct PmVector half_extents;
+} PmShape;
+
+// State of an entity in SI units: m, m/s, N and kg
+typedef struct PmState {
+  struct PmVector location;
+  struct PmVector velocity;
+  struct PmVector net_force;
+  double mass;
+  struct PmShape shape;
+} PmState;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Static description of a status
+const char *pm_status_message(enum PmStatus status);
+
+// Creates a system without entities and with contact force, elastic collision and gravity at
+// time 0
+//
+// # Safety
+// `out` has to point to writable memory for a handle.
+enum PmStatus pm_system_new(struct PmSystem **out);
+
+// Creates a system without entities and with the named built-in interactions at time 0. Unknown
+// names are not found, the contact solver combined with the interactions it replaces is invalid.
+//
+// # Safety
+// `names` has to point to `count` NUL-terminated strings, `out` to writable memory for a handle.
+enum PmStatus pm_system_new_with_interactions(const char *const *names,
+                                              size_t count,
+                                              struct PmSystem **out);
+
+// Creates the system of a scene in the JSON scene format
+//
+// # Safety
+// `json` has to be a NUL-terminated string, `out` has to point to writable memory for a handle.
+enum PmStatus pm_system_from_scene_json(const char *json, struct PmSystem **out);
+
+// Releases a system, null is ignored
+//
+// # Safety
+// `system` has to be null or a handle that was not released yet.
+void pm_system_free(struct PmSystem *system);
+
+// Adds an entity, the identity is copied
+//
+// # Safety
+// `system` has to be a valid handle, `identity` a NUL-terminated string and `state` has to
+// point to a state.
+enum PmStatus pm_system_add_entity(struct PmSystem *system,
+                                   const char *identity,
+                                   const struct PmState *state);
+
+// Advances the system by `steps` steps of `time_step` seconds
+//
+// # Safety
+// `system` has to be a valid handle.
+enum PmStatus pm_system_step(struct PmSystem *system, double time_step, size_t steps);
+
+// Sets the input signal of an actuator of the system
+//
+// # Safety
+// `system` has to be a valid handle and `actuator` a NUL-terminated string.
+enum PmStatus pm_system_set_input_signal(struct PmSystem *system,
+                                         const char *actuator,
+                                         double signal);
+
+// Stops an actuator of the system from acting until its next input signal
+//
+// # Safety
+// `system` has to be a valid handle and `actuator` a NUL-terminated string.
+enum PmStatus pm_system_release_actuator(struct PmSystem *system, const char *actuator);
+
+// Current time in s
+//
+// # Safety
+// `system` has to be a valid handle and `out` has to point to writable memory.
+enum PmStatus pm_system_get_time(const struct PmSystem *system, double *out);
+
+// Total energy in J
+//
+// # Safety
+// `system` has to be a valid handle and `out` has to point to writable memory.
+enum PmStatus pm_system_get_energy(const struct PmSystem *system, double *out);
+
+// Total momentum in N*s
+//
+// # Safety
+// `system` has to be a valid handle and `out` has to point to writable memory.
+enum PmStatus pm_system_get_momentum(const struct PmSystem *system, struct PmVector *out);
+
+// Number of entities, indices of entities range from 0 to the count
+//
+// # Safety
+// `system` has to be a valid handle and `out` has to point to writable memory.
+enum PmStatus pm_system_get_entity_count(const struct PmSystem *system, size_t *out);
+
+// Copies the NUL-terminated identity of an entity into a buffer of `capacity` bytes
+//
+// # Safety
+// `system` has to be a valid handle and `buffer` has to point to `capacity` writable bytes.
+enum PmStatus pm_system_get_entity_identity(const struct PmSystem *system,
+                                            size_t index,
+                                            char *buffer,
+                                            size_t capacity);
+
+// Current state of an entity
+//
+// # Safety
+// `system` has to be a valid handle, `identity` a NUL-terminated string and `out` has to point
+// to writable memory.
+enum PmStatus pm_system_get_state(const struct PmSystem *system,
+                                  const char *identity,
+                                  struct PmState *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PHYSICAL_MACHINE_H */
@@ -0,0 +1,105 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::contact_forces::ContactForces;
+use crate::physics::interaction::helpers::contains_location;
+use crate::physics::interaction::interaction::Interaction;
+use crate::physics::state::state_influence::StateInfluence;
+use crate::utils::identity::Identity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mounting {
+    // Acts on the entity with the identity
+    Entity(&'static str),
+    // Acts on every entity whose shape contains the location
+    Location(Vector3d<si::Meter<f64>>),
+}
+
+impl Mounting {
+    pub fn get_targets<'a>(&self, world: &'a [Entity]) -> Vec<&'a Entity> {
+        match self {
+            Mounting::Entity(id) => world.iter().filter(|e| e.get_identity() == *id).collect(),
+            Mounting::Location(location) => world
+                .iter()
+                .filter(|e| contains_location(e, *location))
+                .collect(),
+        }
+    }
+}
+
+// Force on a target as an affine function of the input signal, valid for signals in the range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutputModel {
+    pub offset: Vector3d<si::Newton<f64>>,
+    pub gain: Vector3d<si::Newton<f64>>,
+    pub min_signal: f64,
+    pub max_signal: f64,
+}
+
+impl OutputModel {
+    pub fn get_force(&self, signal: f64) -> Vector3d<si::Newton<f64>> {
+        self.offset + self.gain * signal.clamp(self.min_signal, self.max_signal)
+    }
+}
+
+pub trait Actuator {
+    fn get_identifier(&self) -> &'static str;
+    fn get_mounting(&self) -> Mounting;
+    fn get_input_signal(&self) -> f64;
+    // The signal stays in effect until it is replaced, NaN releases the actuator
+    fn set_input_signal(&mut self, signal: f64);
+    // Stops acting on the targets until the next signal is set
+    fn release(&mut self) {
+        self.set_input_signal(0.0);
+    }
+    // Force the actuator exerts on the target during the next step
+    fn get_force(&self, target: &Entity, step_size: si::Second<f64>) -> Vector3d<si::Newton<f64>>;
+    // Forces the actuator can exert on the target during the next step
+    fn get_output_model(&self, target: &Entity, step_size: si::Second<f64>) -> OutputModel;
+    fn clone_box(&self) -> Box<dyn Actuator>;
+
+    // Forces are propagated by contact forces, with the actuator as their source
+    fn actuate(&self, world: &[Entity], step_size: si::Second<f64>) -> Vec<StateInfluence> {
+        self.get_mounting()
+            .get_targets(world)
+            .into_iter()
+            .map(|target| {
+                StateInfluence::force_influence(
+                    self.get_identifier(),
+                    self.get_identifier(),
+                    target.get_identity(),
+                    ContactForces::default().get_identifier(),
+                    self.get_force(target, step_size),
+                )
+            })
+            .filter(|influence| {
+                influence.get_state_change().get_net_force() != Vector3d::new(0.0, 0.0, 0.0) * si::N
+            })
+            .collect()
+    }
+}
+
+impl Clone for Box<dyn Actuator> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl Identity for Box<dyn Actuator> {
+    fn get_identity(&self) -> &'static str {
+        self.get_identifier()
+    }
+}
+
+impl std::fmt::Debug for dyn Actuator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({:?}, input {})",
+            self.get_identifier(),
+            self.get_mounting(),
+            self.get_input_signal()
+        )
+    }
+}
@@ -0,0 +1,92 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::{Actuator, Mounting, OutputModel};
+use crate::physics::entity::Entity;
+
+// Force against the velocity of the target, scaled by an input signal in [0, 1]. The brake
+// stops the target, but never reverses its motion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Brake {
+    identifier: &'static str,
+    mounting: Mounting,
+    max_force: si::Newton<f64>,
+    strength: f64,
+}
+
+impl Brake {
+    pub fn new(identifier: &'static str, mounting: Mounting, max_force: si::Newton<f64>) -> Brake {
+        Brake {
+            identifier,
+            mounting,
+            max_force,
+            strength: 0.0,
+        }
+    }
+}
+
+impl Actuator for Brake {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_mounting(&self) -> Mounting {
+        self.mounting
+    }
+
+    fn get_input_signal(&self) -> f64 {
+        self.strength
+    }
+
+    // NaN releases the brake
+    fn set_input_signal(&mut self, signal: f64) {
+        self.strength = if signal.is_nan() {
+            0.0
+        } else {
+            signal.clamp(0.0, 1.0)
+        };
+    }
+
+    fn get_force(&self, target: &Entity, step_size: si::Second<f64>) -> Vector3d<si::Newton<f64>> {
+        let state = target.get_state();
+        let velocity = state.get_velocity();
+        let speed = velocity.norm2().sqrt();
+        if speed == 0.0 * si::MPS {
+            return Vector3d::new(0.0, 0.0, 0.0) * si::N;
+        }
+        let stopping_force = speed * state.get_mass() / step_size;
+        let force = if stopping_force < self.max_force * self.strength {
+            stopping_force
+        } else {
+            self.max_force * self.strength
+        };
+        -(velocity / speed) * force
+    }
+
+    // Strengths beyond the one stopping the target have no further effect
+    fn get_output_model(&self, target: &Entity, step_size: si::Second<f64>) -> OutputModel {
+        let state = target.get_state();
+        let velocity = state.get_velocity();
+        let speed = velocity.norm2().sqrt();
+        if speed == 0.0 * si::MPS || self.max_force == 0.0 * si::N {
+            return OutputModel {
+                offset: Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                gain: Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                min_signal: 0.0,
+                max_signal: 0.0,
+            };
+        }
+        let stopping_strength =
+            (speed * state.get_mass() / step_size / self.max_force).value_unsafe;
+        OutputModel {
+            offset: Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            gain: -(velocity / speed) * self.max_force,
+            min_signal: 0.0,
+            max_signal: stopping_strength.min(1.0),
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Actuator> {
+        Box::new(self.clone())
+    }
+}
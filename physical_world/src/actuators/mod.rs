@@ -0,0 +1,4 @@
+pub mod actuator;
+pub mod brake;
+pub mod thruster;
+pub mod velocity_setter;
@@ -0,0 +1,77 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::{Actuator, Mounting, OutputModel};
+use crate::physics::entity::Entity;
+
+// Constant force along a fixed direction, scaled by a throttle in [-1, 1]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thruster {
+    identifier: &'static str,
+    mounting: Mounting,
+    max_force: Vector3d<si::Newton<f64>>,
+    throttle: f64,
+}
+
+impl Thruster {
+    pub fn new(
+        identifier: &'static str,
+        mounting: Mounting,
+        max_force: Vector3d<si::Newton<f64>>,
+    ) -> Thruster {
+        Thruster {
+            identifier,
+            mounting,
+            max_force,
+            throttle: 0.0,
+        }
+    }
+
+    pub fn get_max_force(&self) -> Vector3d<si::Newton<f64>> {
+        self.max_force
+    }
+}
+
+impl Actuator for Thruster {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_mounting(&self) -> Mounting {
+        self.mounting
+    }
+
+    fn get_input_signal(&self) -> f64 {
+        self.throttle
+    }
+
+    // NaN releases the thruster
+    fn set_input_signal(&mut self, signal: f64) {
+        self.throttle = if signal.is_nan() {
+            0.0
+        } else {
+            signal.clamp(-1.0, 1.0)
+        };
+    }
+
+    fn get_force(
+        &self,
+        _target: &Entity,
+        _step_size: si::Second<f64>,
+    ) -> Vector3d<si::Newton<f64>> {
+        self.max_force * self.throttle
+    }
+
+    fn get_output_model(&self, _target: &Entity, _step_size: si::Second<f64>) -> OutputModel {
+        OutputModel {
+            offset: Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            gain: self.max_force,
+            min_signal: -1.0,
+            max_signal: 1.0,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Actuator> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,90 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::{Actuator, Mounting, OutputModel};
+use crate::physics::entity::Entity;
+
+// Drives the velocity of the target along the direction to the input signal in m/s. The force
+// is computed for the mass of the target alone, contacts slow down the change.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VelocitySetter {
+    identifier: &'static str,
+    mounting: Mounting,
+    direction: Vector3d<f64>,
+    // None leaves the target alone
+    speed: Option<f64>,
+}
+
+impl VelocitySetter {
+    pub fn new(
+        identifier: &'static str,
+        mounting: Mounting,
+        direction: Vector3d<f64>,
+    ) -> VelocitySetter {
+        let norm = direction.norm2().sqrt();
+        if norm == 0.0 {
+            panic!("Direction of velocity setter {} has no length.", identifier);
+        }
+        VelocitySetter {
+            identifier,
+            mounting,
+            direction: direction / norm,
+            speed: None,
+        }
+    }
+}
+
+impl Actuator for VelocitySetter {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_mounting(&self) -> Mounting {
+        self.mounting
+    }
+
+    fn get_input_signal(&self) -> f64 {
+        self.speed.unwrap_or(f64::NAN)
+    }
+
+    // Speeds are not limited, so infinite ones release the target just like NaN
+    fn set_input_signal(&mut self, signal: f64) {
+        self.speed = if signal.is_finite() {
+            Some(signal)
+        } else {
+            None
+        };
+    }
+
+    fn release(&mut self) {
+        self.speed = None;
+    }
+
+    fn get_force(&self, target: &Entity, step_size: si::Second<f64>) -> Vector3d<si::Newton<f64>> {
+        match self.speed {
+            Some(speed) => self.get_output_model(target, step_size).get_force(speed),
+            None => Vector3d::new(0.0, 0.0, 0.0) * si::N,
+        }
+    }
+
+    // Any speed can be set, releasing the target is not part of the model
+    fn get_output_model(&self, target: &Entity, step_size: si::Second<f64>) -> OutputModel {
+        let state = target.get_state();
+        let velocity = state.get_velocity();
+        let current_speed = velocity.x.value_unsafe * self.direction.x
+            + velocity.y.value_unsafe * self.direction.y
+            + velocity.z.value_unsafe * self.direction.z;
+        let direction = Vector3d::new(self.direction.x, self.direction.y, self.direction.z);
+        let gain = direction * (state.get_mass() / step_size * si::MPS);
+        OutputModel {
+            offset: gain * -current_speed,
+            gain,
+            min_signal: f64::NEG_INFINITY,
+            max_signal: f64::INFINITY,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Actuator> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,3 @@
+// C interface of the world model, declared in include/physical_machine.h
+pub mod system;
+pub mod types;
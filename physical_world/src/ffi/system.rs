@@ -0,0 +1,318 @@
+use std::ffi::c_char;
+
+use dimensioned::si;
+use itertools::Itertools;
+
+use crate::ffi::types::{guard, to_str, PmState, PmStatus, PmVector};
+use crate::physics::entity::Entity;
+use crate::physics::system::System;
+use crate::scene::scene::{
+    check_interactions, get_builtin_interaction, get_default_interactions, Scene,
+};
+use crate::utils::identity::{intern, Identity};
+
+/// Opaque handle of a system, created by pm_system_new* and released by pm_system_free
+pub struct PmSystem {
+    system: System,
+}
+
+fn create(system: System, out: *mut *mut PmSystem) -> Result<(), PmStatus> {
+    unsafe { *out = Box::into_raw(Box::new(PmSystem { system })) };
+    Ok(())
+}
+
+unsafe fn borrow<'a>(system: *const PmSystem) -> Result<&'a System, PmStatus> {
+    system
+        .as_ref()
+        .map(|s| &s.system)
+        .ok_or(PmStatus::PmNullPointer)
+}
+
+unsafe fn borrow_mut<'a>(system: *mut PmSystem) -> Result<&'a mut System, PmStatus> {
+    system
+        .as_mut()
+        .map(|s| &mut s.system)
+        .ok_or(PmStatus::PmNullPointer)
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), PmStatus> {
+    if out.is_null() {
+        return Err(PmStatus::PmNullPointer);
+    }
+    *out = value;
+    Ok(())
+}
+
+/// Creates a system without entities and with contact force, elastic collision and gravity at
+/// time 0
+///
+/// # Safety
+/// `out` has to point to writable memory for a handle.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_new(out: *mut *mut PmSystem) -> PmStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(PmStatus::PmNullPointer);
+        }
+        create(
+            System::new(Vec::new(), get_default_interactions(), 0.0 * si::S),
+            out,
+        )
+    })
+}
+
+/// Creates a system without entities and with the named built-in interactions at time 0. Unknown
+/// names are not found, the contact solver combined with the interactions it replaces is invalid.
+///
+/// # Safety
+/// `names` has to point to `count` NUL-terminated strings, `out` to writable memory for a handle.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_new_with_interactions(
+    names: *const *const c_char,
+    count: usize,
+    out: *mut *mut PmSystem,
+) -> PmStatus {
+    guard(|| {
+        if out.is_null() || (names.is_null() && count > 0) {
+            return Err(PmStatus::PmNullPointer);
+        }
+        let mut interactions = Vec::new();
+        let mut identifiers = Vec::new();
+        for idx in 0..count {
+            let name = to_str(*names.add(idx))?;
+            interactions.push(get_builtin_interaction(name).ok_or(PmStatus::PmNotFound)?);
+            identifiers.push(name);
+        }
+        check_interactions(&identifiers).map_err(|_| PmStatus::PmInvalidArgument)?;
+        let interactions = interactions
+            .into_iter()
+            .unique_by(|i| i.get_identifier())
+            .collect();
+        create(System::new(Vec::new(), interactions, 0.0 * si::S), out)
+    })
+}
+
+/// Creates the system of a scene in the JSON scene format
+///
+/// # Safety
+/// `json` has to be a NUL-terminated string, `out` has to point to writable memory for a handle.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_from_scene_json(
+    json: *const c_char,
+    out: *mut *mut PmSystem,
+) -> PmStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(PmStatus::PmNullPointer);
+        }
+        let scene = Scene::from_json(to_str(json)?).map_err(|_| PmStatus::PmSceneError)?;
+        create(scene.to_system().map_err(|_| PmStatus::PmSceneError)?, out)
+    })
+}
+
+/// Releases a system, null is ignored
+///
+/// # Safety
+/// `system` has to be null or a handle that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_free(system: *mut PmSystem) {
+    if !system.is_null() {
+        drop(Box::from_raw(system));
+    }
+}
+
+/// Adds an entity, the identity is copied
+///
+/// # Safety
+/// `system` has to be a valid handle, `identity` a NUL-terminated string and `state` has to
+/// point to a state.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_add_entity(
+    system: *mut PmSystem,
+    identity: *const c_char,
+    state: *const PmState,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow_mut(system)?;
+        let identity = to_str(identity)?;
+        let state = state.as_ref().ok_or(PmStatus::PmNullPointer)?.to_state()?;
+        if system
+            .get_entities()
+            .iter()
+            .any(|e| e.get_identity() == identity)
+        {
+            return Err(PmStatus::PmDuplicateIdentity);
+        }
+        system.add_entity(Entity::new(intern(identity), state));
+        Ok(())
+    })
+}
+
+/// Advances the system by `steps` steps of `time_step` seconds
+///
+/// # Safety
+/// `system` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_step(
+    system: *mut PmSystem,
+    time_step: f64,
+    steps: usize,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow_mut(system)?;
+        if !time_step.is_finite() || time_step <= 0.0 {
+            return Err(PmStatus::PmInvalidArgument);
+        }
+        for _ in 0..steps {
+            system.next_state(time_step * si::S);
+        }
+        Ok(())
+    })
+}
+
+/// Sets the input signal of an actuator of the system
+///
+/// # Safety
+/// `system` has to be a valid handle and `actuator` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_set_input_signal(
+    system: *mut PmSystem,
+    actuator: *const c_char,
+    signal: f64,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow_mut(system)?;
+        let actuator = to_str(actuator)?;
+        if !signal.is_finite() {
+            return Err(PmStatus::PmInvalidArgument);
+        }
+        if !system
+            .get_actuators()
+            .iter()
+            .any(|a| a.get_identifier() == actuator)
+        {
+            return Err(PmStatus::PmNotFound);
+        }
+        system.set_input_signal(actuator, signal);
+        Ok(())
+    })
+}
+
+/// Stops an actuator of the system from acting until its next input signal
+///
+/// # Safety
+/// `system` has to be a valid handle and `actuator` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_release_actuator(
+    system: *mut PmSystem,
+    actuator: *const c_char,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow_mut(system)?;
+        let actuator = to_str(actuator)?;
+        if !system
+            .get_actuators()
+            .iter()
+            .any(|a| a.get_identifier() == actuator)
+        {
+            return Err(PmStatus::PmNotFound);
+        }
+        system.release_actuator(actuator);
+        Ok(())
+    })
+}
+
+/// Current time in s
+///
+/// # Safety
+/// `system` has to be a valid handle and `out` has to point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_time(system: *const PmSystem, out: *mut f64) -> PmStatus {
+    guard(|| write(out, borrow(system)?.get_current_time().value_unsafe))
+}
+
+/// Total energy in J
+///
+/// # Safety
+/// `system` has to be a valid handle and `out` has to point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_energy(system: *const PmSystem, out: *mut f64) -> PmStatus {
+    guard(|| write(out, borrow(system)?.get_energy().value_unsafe))
+}
+
+/// Total momentum in N*s
+///
+/// # Safety
+/// `system` has to be a valid handle and `out` has to point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_momentum(
+    system: *const PmSystem,
+    out: *mut PmVector,
+) -> PmStatus {
+    guard(|| write(out, PmVector::from_vector(borrow(system)?.get_momentum())))
+}
+
+/// Number of entities, indices of entities range from 0 to the count
+///
+/// # Safety
+/// `system` has to be a valid handle and `out` has to point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_entity_count(
+    system: *const PmSystem,
+    out: *mut usize,
+) -> PmStatus {
+    guard(|| write(out, borrow(system)?.get_entities().len()))
+}
+
+/// Copies the NUL-terminated identity of an entity into a buffer of `capacity` bytes
+///
+/// # Safety
+/// `system` has to be a valid handle and `buffer` has to point to `capacity` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_entity_identity(
+    system: *const PmSystem,
+    index: usize,
+    buffer: *mut c_char,
+    capacity: usize,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow(system)?;
+        if buffer.is_null() {
+            return Err(PmStatus::PmNullPointer);
+        }
+        let identity = system
+            .get_entities()
+            .get(index)
+            .ok_or(PmStatus::PmNotFound)?
+            .get_identity();
+        if identity.len() >= capacity {
+            return Err(PmStatus::PmInvalidArgument);
+        }
+        std::ptr::copy_nonoverlapping(identity.as_ptr(), buffer as *mut u8, identity.len());
+        *buffer.add(identity.len()) = 0;
+        Ok(())
+    })
+}
+
+/// Current state of an entity
+///
+/// # Safety
+/// `system` has to be a valid handle, `identity` a NUL-terminated string and `out` has to point
+/// to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn pm_system_get_state(
+    system: *const PmSystem,
+    identity: *const c_char,
+    out: *mut PmState,
+) -> PmStatus {
+    guard(|| {
+        let system = borrow(system)?;
+        let identity = to_str(identity)?;
+        let entity = system
+            .get_entities()
+            .iter()
+            .find(|e| e.get_identity() == identity)
+            .ok_or(PmStatus::PmNotFound)?;
+        write(out, PmState::from_state(entity.get_state()))
+    })
+}
@@ -0,0 +1,178 @@
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::state::shape::{Cuboid, Shape, Sphere};
+use crate::physics::state::state::State;
+
+/// Result of every function of the C interface
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PmStatus {
+    PmOk = 0,
+    PmNullPointer = 1,
+    PmInvalidArgument = 2,
+    PmNotFound = 3,
+    PmDuplicateIdentity = 4,
+    PmSceneError = 5,
+    /// The world model failed internally, the system must not be used anymore
+    PmPanic = 6,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PmVector {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl PmVector {
+    pub fn from_vector<U>(v: Vector3d<si::SI<f64, U>>) -> PmVector {
+        PmVector {
+            x: v.x.value_unsafe,
+            y: v.y.value_unsafe,
+            z: v.z.value_unsafe,
+        }
+    }
+
+    fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PmShapeKind {
+    PmShapeNone = 0,
+    PmShapeSphere = 1,
+    PmShapeCuboid = 2,
+}
+
+/// Radius is used by spheres, half extents by cuboids, both in m
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PmShape {
+    pub kind: PmShapeKind,
+    pub radius: f64,
+    pub half_extents: PmVector,
+}
+
+/// State of an entity in SI units: m, m/s, N and kg
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PmState {
+    pub location: PmVector,
+    pub velocity: PmVector,
+    pub net_force: PmVector,
+    pub mass: f64,
+    pub shape: PmShape,
+}
+
+impl PmState {
+    pub fn from_state(state: &State) -> PmState {
+        let none = PmShape {
+            kind: PmShapeKind::PmShapeNone,
+            radius: 0.0,
+            half_extents: PmVector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        };
+        let shape = match state.get_shape() {
+            Shape::Sphere(sphere) => PmShape {
+                kind: PmShapeKind::PmShapeSphere,
+                radius: sphere.radius.value_unsafe,
+                ..none
+            },
+            Shape::Cuboid(cuboid) => PmShape {
+                kind: PmShapeKind::PmShapeCuboid,
+                half_extents: PmVector::from_vector(cuboid.half_extents),
+                ..none
+            },
+            Shape::None => none,
+        };
+        PmState {
+            location: PmVector::from_vector(state.get_location()),
+            velocity: PmVector::from_vector(state.get_velocity()),
+            net_force: PmVector::from_vector(state.get_net_force()),
+            mass: state.get_mass().value_unsafe,
+            shape,
+        }
+    }
+
+    pub fn to_state(&self) -> Result<State, PmStatus> {
+        let finite =
+            self.location.is_finite() && self.velocity.is_finite() && self.net_force.is_finite();
+        if !finite || !self.mass.is_finite() || self.mass <= 0.0 {
+            return Err(PmStatus::PmInvalidArgument);
+        }
+        let shape = match self.shape.kind {
+            PmShapeKind::PmShapeNone => Shape::None,
+            PmShapeKind::PmShapeSphere if self.shape.radius >= 0.0 => Shape::Sphere(Sphere {
+                radius: self.shape.radius * si::M,
+            }),
+            PmShapeKind::PmShapeCuboid
+                if [
+                    self.shape.half_extents.x,
+                    self.shape.half_extents.y,
+                    self.shape.half_extents.z,
+                ]
+                .iter()
+                .all(|e| *e >= 0.0) =>
+            {
+                let PmVector { x, y, z } = self.shape.half_extents;
+                Shape::Cuboid(Cuboid {
+                    half_extents: Vector3d::new(x, y, z) * si::M,
+                })
+            }
+            _ => return Err(PmStatus::PmInvalidArgument),
+        };
+        let vector = |v: PmVector| Vector3d::new(v.x, v.y, v.z);
+        Ok(State::new(
+            vector(self.location) * si::M,
+            vector(self.velocity) * si::MPS,
+            vector(self.net_force) * si::N,
+            self.mass * si::KG,
+            shape,
+        ))
+    }
+}
+
+// Panics must not unwind into C. A system that panicked is reported by PmPanic and may be
+// left inconsistent, so asserting unwind safety is left to the caller.
+pub(crate) fn guard<F: FnOnce() -> Result<(), PmStatus>>(f: F) -> PmStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => PmStatus::PmOk,
+        Ok(Err(status)) => status,
+        Err(_) => PmStatus::PmPanic,
+    }
+}
+
+// Borrows a NUL-terminated UTF-8 string
+pub(crate) unsafe fn to_str<'a>(string: *const c_char) -> Result<&'a str, PmStatus> {
+    if string.is_null() {
+        return Err(PmStatus::PmNullPointer);
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| PmStatus::PmInvalidArgument)
+}
+
+/// Static description of a status
+#[no_mangle]
+pub extern "C" fn pm_status_message(status: PmStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        PmStatus::PmOk => c"ok",
+        PmStatus::PmNullPointer => c"null pointer",
+        PmStatus::PmInvalidArgument => c"invalid argument",
+        PmStatus::PmNotFound => c"not found",
+        PmStatus::PmDuplicateIdentity => c"identity is used more than once",
+        PmStatus::PmSceneError => c"invalid scene",
+        PmStatus::PmPanic => c"internal error",
+    };
+    message.as_ptr()
+}
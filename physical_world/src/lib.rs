@@ -1,2 +1,16 @@
+pub mod actuators;
+pub mod ffi;
+pub mod lighting;
 pub mod physics;
+pub mod plotting;
+pub mod prediction;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod recording;
+pub mod rte;
+pub mod scene;
+pub mod sensors;
+pub mod server;
+pub mod space;
 pub mod utils;
+pub mod virtualization;
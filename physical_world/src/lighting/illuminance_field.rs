@@ -0,0 +1,85 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::lighting::light_source::LightSource;
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::contains_location;
+use crate::space::raycast::{cast_segment, RayFilter};
+use crate::space::region::{Region, RegionError};
+use crate::utils::identity::Identity;
+
+// Illuminance caused by light sources in a world. Entities cast shadows, except on locations
+// inside of them and for light sources inside of them.
+pub struct IlluminanceField<'a> {
+    light_sources: &'a [LightSource],
+    world: &'a [Entity],
+}
+
+impl<'a> IlluminanceField<'a> {
+    pub fn new(light_sources: &'a [LightSource], world: &'a [Entity]) -> IlluminanceField<'a> {
+        IlluminanceField {
+            light_sources,
+            world,
+        }
+    }
+
+    pub fn get_light_sources(&self) -> &'a [LightSource] {
+        self.light_sources
+    }
+
+    pub fn get_world(&self) -> &'a [Entity] {
+        self.world
+    }
+
+    pub fn is_shadowed(
+        &self,
+        source: Vector3d<si::Meter<f64>>,
+        location: Vector3d<si::Meter<f64>>,
+    ) -> bool {
+        // Rays never hit entities containing the source, ones containing the location are excluded
+        let filter = self
+            .world
+            .iter()
+            .filter(|e| contains_location(e, location))
+            .fold(RayFilter::new(), |filter, e| {
+                filter.excluding_entity(e.get_identity())
+            });
+        cast_segment(self.world, source, location, &filter).is_some()
+    }
+
+    pub fn get_illuminance(&self, location: Vector3d<si::Meter<f64>>) -> si::Lux<f64> {
+        let mut illuminance = 0.0 * si::LX;
+        for light_source in self.light_sources {
+            let source = match light_source.get_pose().get_location(self.world) {
+                Some(source) => source,
+                None => continue,
+            };
+            if let Some(contribution) =
+                light_source.get_unobstructed_illuminance(self.world, location)
+            {
+                if contribution > 0.0 * si::LX && !self.is_shadowed(source, location) {
+                    illuminance += contribution;
+                }
+            }
+        }
+        illuminance
+    }
+
+    // Mean over the centers of cells of the given size in the region, None if there are none
+    pub fn get_average_illuminance(
+        &self,
+        region: &Region,
+        resolution: si::Meter<f64>,
+    ) -> Result<Option<si::Lux<f64>>, RegionError> {
+        let grid = region.rasterise(resolution)?;
+        let cells = grid.get_occupied_cells();
+        if cells.is_empty() {
+            return Ok(None);
+        }
+        let total = cells
+            .iter()
+            .map(|cell| self.get_illuminance(grid.get_cell_center(*cell)))
+            .fold(0.0 * si::LX, |sum, illuminance| sum + illuminance);
+        Ok(Some(total / cells.len() as f64))
+    }
+}
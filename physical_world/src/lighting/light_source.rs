@@ -0,0 +1,179 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::sensors::sensor::Pose;
+
+// How illuminance decreases with the distance to the source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Falloff {
+    InverseSquare,
+    // Illuminance is divided by the distance in meters to the power
+    Power(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Emission {
+    // Equally in all directions
+    Point,
+    // Within the angle in radians to the direction of the pose
+    Spot { half_angle: f64 },
+}
+
+// Light emitted from a location or from an entity it is mounted on, dimmed by a level in [0, 1]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightSource {
+    identifier: &'static str,
+    pose: Pose,
+    intensity: si::Candela<f64>,
+    falloff: Falloff,
+    emission: Emission,
+    // No light reaches beyond the range
+    range: Option<si::Meter<f64>>,
+    level: f64,
+}
+
+impl LightSource {
+    pub fn new(
+        identifier: &'static str,
+        pose: Pose,
+        intensity: si::Candela<f64>,
+        falloff: Falloff,
+        emission: Emission,
+    ) -> LightSource {
+        LightSource {
+            identifier,
+            pose,
+            intensity,
+            falloff,
+            emission,
+            range: None,
+            level: 0.0,
+        }
+    }
+
+    pub fn with_range(mut self, range: si::Meter<f64>) -> LightSource {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    pub fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    pub fn get_intensity(&self) -> si::Candela<f64> {
+        self.intensity
+    }
+
+    pub fn get_level(&self) -> f64 {
+        self.level
+    }
+
+    // Light sources start switched off
+    pub fn set_level(&mut self, level: f64) {
+        self.level = level.clamp(0.0, 1.0);
+    }
+
+    // Illuminance at the location without regard to shadows, None if the source is mounted on
+    // an entity that is not in the world
+    pub fn get_unobstructed_illuminance(
+        &self,
+        world: &[Entity],
+        location: Vector3d<si::Meter<f64>>,
+    ) -> Option<si::Lux<f64>> {
+        let source = self.pose.get_location(world)?;
+        let rel_location = location - source;
+        let distance = rel_location.norm2().sqrt();
+        let dark = 0.0 * si::LX;
+        if let Some(range) = self.range {
+            if distance > range {
+                return Some(dark);
+            }
+        }
+        if distance == 0.0 * si::M {
+            return Some(self.intensity * self.level / si::M / si::M);
+        }
+        if let Emission::Spot { half_angle } = self.emission {
+            let direction = self.pose.get_unit_direction();
+            let cos_angle = (rel_location.x.value_unsafe * direction.x
+                + rel_location.y.value_unsafe * direction.y
+                + rel_location.z.value_unsafe * direction.z)
+                / distance.value_unsafe;
+            if cos_angle < half_angle.cos() {
+                return Some(dark);
+            }
+        }
+        let attenuation = match self.falloff {
+            Falloff::InverseSquare => distance.value_unsafe.powi(2),
+            Falloff::Power(exponent) => distance.value_unsafe.powf(exponent),
+        };
+        Some(self.intensity * self.level / attenuation / si::M / si::M)
+    }
+}
+
+#[cfg(test)]
+mod light_source_tests {
+    use super::*;
+    use crate::actuators::actuator::Mounting;
+
+    fn illuminance(source: &LightSource, x: f64, y: f64) -> f64 {
+        source
+            .get_unobstructed_illuminance(&[], Vector3d::new(x, y, 0.0) * si::M)
+            .unwrap()
+            .value_unsafe
+    }
+
+    #[test]
+    fn falloff() {
+        let pose = Pose::new(Mounting::Location(Vector3d::new(0.0, 0.0, 0.0) * si::M));
+        let mut source = LightSource::new(
+            "lamp",
+            pose,
+            100.0 * si::CD,
+            Falloff::InverseSquare,
+            Emission::Point,
+        );
+        assert_eq!(illuminance(&source, 2.0, 0.0), 0.0);
+        source.set_level(1.0);
+        assert_eq!(illuminance(&source, 2.0, 0.0), 25.0);
+        assert_eq!(illuminance(&source, 0.0, -10.0), 1.0);
+        assert_eq!(illuminance(&source, 0.0, 0.0), 100.0);
+        source.set_level(0.5);
+        assert_eq!(illuminance(&source, 2.0, 0.0), 12.5);
+        let mut source = LightSource::new(
+            "lamp",
+            pose,
+            100.0 * si::CD,
+            Falloff::Power(0.5),
+            Emission::Point,
+        )
+        .with_range(5.0 * si::M);
+        source.set_level(1.0);
+        assert_eq!(illuminance(&source, 4.0, 0.0), 50.0);
+        assert_eq!(illuminance(&source, 6.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn spot() {
+        let mut pose = Pose::new(Mounting::Location(Vector3d::new(0.0, 0.0, 0.0) * si::M));
+        pose.direction = Vector3d::new(0.0, -1.0, 0.0);
+        let mut source = LightSource::new(
+            "spot",
+            pose,
+            100.0 * si::CD,
+            Falloff::InverseSquare,
+            Emission::Spot {
+                half_angle: std::f64::consts::FRAC_PI_4,
+            },
+        );
+        source.set_level(1.0);
+        assert_eq!(illuminance(&source, 0.0, -1.0), 100.0);
+        assert!(illuminance(&source, 0.9, -1.0) > 0.0);
+        assert_eq!(illuminance(&source, 1.1, -1.0), 0.0);
+        assert_eq!(illuminance(&source, 0.0, 1.0), 0.0);
+    }
+}
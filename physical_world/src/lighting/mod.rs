@@ -0,0 +1,2 @@
+pub mod illuminance_field;
+pub mod light_source;
@@ -0,0 +1,330 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+
+use physical_machine::physics::system::System;
+use physical_machine::plotting::animation::SceneRenderer;
+use physical_machine::plotting::chart::Chart;
+use physical_machine::plotting::series::{quantity_over_time, trajectory, Component, Projection};
+use physical_machine::recording::columns::{Columns, Quantity};
+use physical_machine::recording::csv_recorder::CsvRecorder;
+use physical_machine::recording::json_lines_recorder::JsonLinesRecorder;
+use physical_machine::recording::recorder::Recorder;
+use physical_machine::recording::sampling::Sampling;
+use physical_machine::scene::examples::{get_example, get_example_names};
+use physical_machine::scene::scene::Scene;
+use physical_machine::server::server::serve;
+
+#[derive(Parser)]
+#[command(
+    name = "physical_machine",
+    version,
+    about = "Run, inspect and export physical scenes"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the built-in example scenes
+    List,
+    /// Run a scene and print summaries of the system
+    Run {
+        /// Scene file, or the name of a built-in example
+        scene: String,
+        #[command(flatten)]
+        timing: Timing,
+        /// Print a summary every N steps, 0 only summarises the initial and final state
+        #[arg(long, default_value_t = 0)]
+        summary_every: usize,
+    },
+    /// Print a summary of the system at a point in time
+    Summary {
+        /// Scene file, or the name of a built-in example
+        scene: String,
+        /// Simulated time in seconds
+        #[arg(long, default_value_t = 0.0)]
+        at: f64,
+        /// Overrides the time step of the scene in seconds
+        #[arg(long)]
+        time_step: Option<f64>,
+    },
+    /// Run a scene and export its history
+    Export {
+        /// Scene file, or the name of a built-in example
+        scene: String,
+        #[command(flatten)]
+        timing: Timing,
+        #[command(flatten)]
+        outputs: Outputs,
+    },
+    /// Serve scenes to clients as newline-delimited JSON-RPC over TCP
+    Serve {
+        /// Address to listen on, port 0 picks a free port
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        address: String,
+    },
+}
+
+#[derive(Args)]
+struct Outputs {
+    /// CSV file with every quantity of every entity
+    #[arg(long)]
+    csv: Option<PathBuf>,
+    /// JSON Lines file with every quantity of every entity
+    #[arg(long)]
+    jsonl: Option<PathBuf>,
+    /// Chart of a quantity over time, .png or .svg
+    #[arg(long)]
+    plot: Option<PathBuf>,
+    /// Quantity of the chart
+    #[arg(long, default_value = "location", value_parser = parse_quantity)]
+    quantity: Quantity,
+    /// Component of the quantity: x, y, z or norm
+    #[arg(long, default_value = "x", value_parser = parse_component)]
+    component: Component,
+    /// Chart of the paths of the entities, .png or .svg
+    #[arg(long)]
+    trajectory: Option<PathBuf>,
+    /// Viewing plane of trajectories and animations: xy, xz or yz
+    #[arg(long, default_value = "xy", value_parser = parse_projection)]
+    projection: Projection,
+    /// Animation of the scene, a .gif file or a directory for SVG frames
+    #[arg(long)]
+    animation: Option<PathBuf>,
+    /// Frames per second of simulated time
+    #[arg(long, default_value_t = 25.0)]
+    fps: f64,
+}
+
+#[derive(Args)]
+struct Timing {
+    /// Overrides the duration of the scene in seconds
+    #[arg(long)]
+    duration: Option<f64>,
+    /// Overrides the time step of the scene in seconds
+    #[arg(long)]
+    time_step: Option<f64>,
+}
+
+fn parse_quantity(name: &str) -> Result<Quantity, String> {
+    Quantity::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Quantity::ALL.iter().map(|q| q.get_name()).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn parse_component(name: &str) -> Result<Component, String> {
+    Component::from_name(name).ok_or_else(|| "expected one of x, y, z, norm".to_string())
+}
+
+fn parse_projection(name: &str) -> Result<Projection, String> {
+    Projection::from_name(name).ok_or_else(|| "expected one of xy, xz, yz".to_string())
+}
+
+// Files take precedence over built-in examples of the same name
+fn load_scene(
+    scene: &str,
+    duration: Option<f64>,
+    time_step: Option<f64>,
+) -> Result<Scene, Box<dyn Error>> {
+    let mut scene = if std::path::Path::new(scene).exists() {
+        Scene::load(scene)?
+    } else {
+        match get_example(scene) {
+            Some(example) => example?,
+            None => return Err(format!("No scene file or example named {}.", scene).into()),
+        }
+    };
+    if let Some(duration) = duration {
+        scene.duration = duration;
+    }
+    if let Some(time_step) = time_step {
+        scene.time_step = time_step;
+    }
+    scene.validate()?;
+    Ok(scene)
+}
+
+// Calls visit with the initial state and after every step
+fn simulate<F>(scene: &Scene, mut visit: F) -> Result<System, Box<dyn Error>>
+where
+    F: FnMut(usize, &System) -> Result<(), Box<dyn Error>>,
+{
+    let mut system = scene.to_system()?;
+    visit(0, &system)?;
+    for step in 1..=scene.get_step_count() {
+        system.next_state(scene.get_time_step());
+        visit(step, &system)?;
+    }
+    Ok(system)
+}
+
+fn format_summary(system: &System) -> String {
+    let momentum = system.get_momentum();
+    let contacts = system
+        .get_contacts()
+        .iter()
+        .map(|(a, b)| format!("{}-{}", a, b))
+        .collect::<Vec<String>>();
+    format!(
+        "t = {:.3} s\n  entities: {}\n  energy: {:.6} J\n  momentum: ({:.6}, {:.6}, {:.6}) N*s\n  contacts: {}",
+        system.get_current_time().value_unsafe,
+        system.get_entities().len(),
+        system.get_energy().value_unsafe,
+        momentum.x.value_unsafe,
+        momentum.y.value_unsafe,
+        momentum.z.value_unsafe,
+        if contacts.is_empty() {
+            "none".to_string()
+        } else {
+            contacts.join(", ")
+        }
+    )
+}
+
+fn list() {
+    for name in get_example_names() {
+        match get_example(name) {
+            Some(Ok(scene)) => println!("{:<28}{}", name, scene.description),
+            _ => println!("{}", name),
+        }
+    }
+}
+
+// is_multiple_of would need Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn run(scene: &Scene, summary_every: usize) -> Result<(), Box<dyn Error>> {
+    let last_step = scene.get_step_count();
+    simulate(scene, |step, system| {
+        if step == 0 || step == last_step || (summary_every > 0 && step % summary_every == 0) {
+            println!("{}", format_summary(system));
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn export(scene: &Scene, outputs: Outputs) -> Result<(), Box<dyn Error>> {
+    if !outputs.fps.is_finite() || outputs.fps <= 0.0 {
+        return Err("Frame rate has to be positive and finite.".into());
+    }
+    let keep_history =
+        outputs.plot.is_some() || outputs.trajectory.is_some() || outputs.animation.is_some();
+    if !keep_history && outputs.csv.is_none() && outputs.jsonl.is_none() {
+        return Err("Nothing to export, choose at least one output.".into());
+    }
+    let mut recorders: Vec<Box<dyn Recorder>> = Vec::new();
+    if let Some(path) = &outputs.csv {
+        recorders.push(Box::new(CsvRecorder::create(
+            path,
+            Sampling::EveryStep,
+            Columns::all(),
+        )?));
+    }
+    if let Some(path) = &outputs.jsonl {
+        recorders.push(Box::new(JsonLinesRecorder::create(
+            path,
+            Sampling::EveryStep,
+            Columns::all(),
+        )?));
+    }
+    let mut history = Vec::new();
+    simulate(scene, |_, system| {
+        for recorder in recorders.iter_mut() {
+            recorder.record(system)?;
+        }
+        if keep_history {
+            history.push(system.clone());
+        }
+        Ok(())
+    })?;
+    for recorder in recorders.iter_mut() {
+        recorder.flush()?;
+    }
+    if let Some(path) = &outputs.plot {
+        let (quantity, component) = (outputs.quantity, outputs.component);
+        let y_label = format!(
+            "{} {} [{}]",
+            quantity.get_name(),
+            component.get_name(),
+            quantity.get_unit()
+        );
+        Chart::new(&scene.name, "Time [s]", &y_label)
+            .plot(&quantity_over_time(&history, quantity, component), path)?;
+    }
+    if let Some(path) = &outputs.trajectory {
+        let (horizontal, vertical) = outputs.projection.get_axes();
+        Chart::new(
+            &scene.name,
+            &format!("Location {} [m]", horizontal.get_name()),
+            &format!("Location {} [m]", vertical.get_name()),
+        )
+        .plot(&trajectory(&history, outputs.projection), path)?;
+    }
+    if let Some(path) = &outputs.animation {
+        let renderer = SceneRenderer::new(outputs.projection, outputs.fps);
+        if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("gif"))
+        {
+            renderer.render_gif(&history, path)?;
+        } else {
+            renderer.render_svg_frames(&history, path)?;
+        }
+    }
+    Ok(())
+}
+
+fn execute(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::List => list(),
+        Command::Run {
+            scene,
+            timing,
+            summary_every,
+        } => run(
+            &load_scene(&scene, timing.duration, timing.time_step)?,
+            summary_every,
+        )?,
+        Command::Summary {
+            scene,
+            at,
+            time_step,
+        } => {
+            let scene = load_scene(&scene, Some(at), time_step)?;
+            let system = simulate(&scene, |_, _| Ok(()))?;
+            println!("{}", format_summary(&system));
+        }
+        Command::Export {
+            scene,
+            timing,
+            outputs,
+        } => export(
+            &load_scene(&scene, timing.duration, timing.time_step)?,
+            outputs,
+        )?,
+        Command::Serve { address } => {
+            let listener = std::net::TcpListener::bind(address)?;
+            // Clients started alongside the server read the actual address from here
+            println!("listening on {}", listener.local_addr()?);
+            std::io::Write::flush(&mut std::io::stdout())?;
+            serve(listener)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match execute(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
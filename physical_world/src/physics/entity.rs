@@ -10,6 +10,7 @@ pub struct Entity {
     identifier: &'static str,
     state: State,
     influences: Vec<StateInfluence>,
+    tags: Vec<&'static str>,
 }
 
 impl Entity {
@@ -18,9 +19,24 @@ impl Entity {
             identifier,
             state,
             influences: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
+    pub fn add_tag(&mut self, tag: &'static str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    pub fn get_tags(&self) -> &Vec<&'static str> {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+
     pub fn get_state(&self) -> &State {
         &self.state
     }
@@ -48,6 +64,10 @@ impl Entity {
         self.state.evolve(elapsed_time);
     }
 
+    pub fn translate(&mut self, offset: Vector3d<si::Meter<f64>>) {
+        self.state.translate(offset);
+    }
+
     pub fn add_influence(&mut self, influence: &StateInfluence) {
         self.influences.push(influence.clone());
     }
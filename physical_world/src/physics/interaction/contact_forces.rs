@@ -2,32 +2,63 @@ use dimensioned::{si, Sqrt};
 use vector3d::Vector3d;
 
 use crate::physics::entity::Entity;
-use crate::physics::interaction::helpers::{
-    applies_force_in_direction, are_touching, get_force_in_direction,
-};
+use crate::physics::interaction::helpers::{applies_force_in_direction, are_touching, split_force};
 use crate::physics::interaction::interaction::Interaction;
 use crate::physics::state::state::State;
 use crate::physics::state::state_influence::StateInfluence;
-use crate::physics::system::PRECISION;
+use crate::physics::tolerance::Tolerances;
 use crate::utils::identity::Identity;
 
-#[derive(Clone, Copy)]
-pub struct ContactForces;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactForces {
+    pub identifier: &'static str,
+    // Owned by the system the interaction is part of
+    tolerances: Tolerances,
+}
+
+impl ContactForces {
+    pub fn new(identifier: &'static str) -> ContactForces {
+        ContactForces {
+            identifier,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ContactForces {
+    fn default() -> Self {
+        ContactForces {
+            identifier: "contact force",
+            tolerances: Tolerances::default(),
+        }
+    }
+}
 
 impl Interaction for ContactForces {
     fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool {
         are_touching(transmitter, receiver)
     }
 
-    fn init(&self, source: &Entity, neighbors: Vec<&Entity>, _step_size: si::Second<f64>) -> Vec<StateInfluence> {
+    fn init(
+        &self,
+        source: &Entity,
+        neighbors: Vec<&Entity>,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
         let mut influences = Vec::new();
         let mut forces = Vector3d::new(0.0, 0.0, 0.0) * si::N;
-        for neigh in neighbors
-            .iter()
-            .filter(|n| applies_force_in_direction(source, n))
-        {
-            let force = get_force_in_direction(source, neigh);
-            if force.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        let applied = source.get_state().get_net_force().norm2().sqrt();
+        let pushed = neighbors
+            .into_iter()
+            .filter(|n| applies_force_in_direction(source, n) && are_touching(source, n))
+            .collect::<Vec<&Entity>>();
+        let shares = split_force(source, &pushed);
+        for (neigh, force) in pushed.into_iter().zip(shares) {
+            if !self
+                .tolerances
+                .force
+                .is_negligible(force.norm2().sqrt(), applied)
+            {
                 let influence = StateInfluence::force_influence(
                     source.get_identity(),
                     source.get_identity(),
@@ -39,7 +70,11 @@ impl Interaction for ContactForces {
                 influences.push(influence);
             }
         }
-        if forces.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        if !self
+            .tolerances
+            .force
+            .is_negligible(forces.norm2().sqrt(), applied)
+        {
             let self_influence = StateInfluence::force_influence(
                 source.get_identity(),
                 source.get_identity(),
@@ -57,7 +92,7 @@ impl Interaction for ContactForces {
         reactor: &Entity,
         neighbors: Vec<&Entity>,
         influence: StateInfluence,
-        _step_size: si::Second<f64>
+        _step_size: si::Second<f64>,
     ) -> Vec<StateInfluence> {
         if reactor.get_identity() != influence.get_receiver_id() {
             panic!(
@@ -78,12 +113,18 @@ impl Interaction for ContactForces {
         );
         let mut influences = Vec::new();
         let mut forces = Vector3d::new(0.0, 0.0, 0.0) * si::N;
-        for neigh in neighbors
-            .iter()
-            .filter(|n| applies_force_in_direction(&inf_entity, n))
-        {
-            let force = get_force_in_direction(&inf_entity, neigh);
-            if force.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        let applied = influence.get_state_change().get_net_force().norm2().sqrt();
+        let pushed = neighbors
+            .into_iter()
+            .filter(|n| applies_force_in_direction(&inf_entity, n) && are_touching(&inf_entity, n))
+            .collect::<Vec<&Entity>>();
+        let shares = split_force(&inf_entity, &pushed);
+        for (neigh, force) in pushed.into_iter().zip(shares) {
+            if !self
+                .tolerances
+                .force
+                .is_negligible(force.norm2().sqrt(), applied)
+            {
                 let new_influence = StateInfluence::force_influence(
                     influence.get_source_id(),
                     reactor.get_identity(),
@@ -96,7 +137,11 @@ impl Interaction for ContactForces {
             }
         }
         let reactor_force = influence.get_state_change().get_net_force() - forces;
-        if reactor_force.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        if !self
+            .tolerances
+            .force
+            .is_negligible(reactor_force.norm2().sqrt(), applied)
+        {
             let self_influence = StateInfluence::force_influence(
                 influence.get_source_id(),
                 reactor.get_identity(),
@@ -110,11 +155,20 @@ impl Interaction for ContactForces {
     }
 
     fn get_identifier(&self) -> &'static str {
-        "contact force"
+        self.identifier
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn set_tolerances(&mut self, tolerances: Tolerances) {
+        self.tolerances = tolerances;
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod contact_forces {
     use super::*;
     use crate::physics::entity::Entity;
@@ -149,7 +203,7 @@ mod contact_forces {
             ),
         );
         let expected_res = false;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -188,7 +242,7 @@ mod contact_forces {
             ),
         );
         let expected_res = false;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -227,7 +281,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -266,7 +320,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -305,7 +359,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -344,7 +398,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -383,7 +437,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -422,7 +476,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -461,7 +515,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -500,7 +554,7 @@ mod contact_forces {
             ),
         );
         let expected_res = true;
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -527,7 +581,7 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![];
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -563,7 +617,7 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -635,7 +689,7 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0, &n1, &n2, &n3];
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let res = contact_forces.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -682,7 +736,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let neighbors = vec![&n0, &n1];
         let res = contact_forces.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
@@ -729,7 +783,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let expected_res_self = StateInfluence::force_influence(
             source.get_identity(),
             source.get_identity(),
@@ -818,7 +872,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let expected_res_self = StateInfluence::force_influence(
             source.get_identity(),
             source.get_identity(),
@@ -883,7 +937,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -899,7 +953,8 @@ mod contact_forces {
             Vector3d::new(-100.0, 0.0, 0.0) * si::N,
         );
         let neighbors = vec![];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let res_self = res.pop().unwrap();
         assert!(
             res_self == expected_res_self,
@@ -925,7 +980,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -977,7 +1032,8 @@ mod contact_forces {
             Vector3d::new(-100.0, 0.0, 0.0) * si::N,
         );
         let neighbors = vec![&n0, &n1, &n2];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let res_self = res.pop().unwrap();
         assert!(
             res_self == expected_res_self,
@@ -1003,7 +1059,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1024,7 +1080,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1059,7 +1116,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1080,7 +1137,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1129,7 +1187,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1150,7 +1208,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1199,7 +1258,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1220,7 +1279,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1255,7 +1315,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1276,7 +1336,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1311,7 +1372,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1344,7 +1405,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0, &n1];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other_0 = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1393,7 +1455,7 @@ mod contact_forces {
                 }),
             ),
         );
-        let contact_forces = ContactForces;
+        let contact_forces = ContactForces::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1426,7 +1488,8 @@ mod contact_forces {
             ),
         );
         let neighbors = vec![&n0, &n1];
-        let mut res = contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            contact_forces.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other_0 = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
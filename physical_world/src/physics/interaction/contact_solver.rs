@@ -0,0 +1,197 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::{are_touching, canonical_order, get_penetration};
+use crate::physics::interaction::interaction::{Interaction, Propagation, PropagationLimits};
+use crate::physics::state::shape::Shape;
+use crate::physics::state::state_influence::StateInfluence;
+use crate::utils::identity::Identity;
+
+// Solves all contacts of a step together instead of propagating forces along chains. Every
+// touching pair is a constraint that must not approach, solved by sequential impulses on the
+// velocities the entities would have after the step. Replaces ContactForces and ElasticCollision.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactSolver {
+    pub identifier: &'static str,
+    // Passes of projected Gauss-Seidel over all contacts per step, unless impulses settle before
+    pub max_iterations: usize,
+    // Change of impulses in N*s below which they count as settled
+    pub tolerance: f64,
+    // Share of the approaching speed that is returned after a collision, 1 keeps it elastic
+    pub restitution: f64,
+}
+
+impl Default for ContactSolver {
+    fn default() -> Self {
+        ContactSolver {
+            identifier: "contact solver",
+            max_iterations: 1000,
+            tolerance: 1e-12,
+            restitution: 1.0,
+        }
+    }
+}
+
+pub const REPLACED_INTERACTIONS: [&str; 2] = ["contact force", "elastic collision"];
+
+struct Contact {
+    idx0: usize,
+    idx1: usize,
+    // Points from the first to the second entity
+    normal: Vector3d<f64>,
+    effective_mass: f64,
+    // Separating speed the constraint aims for
+    target_speed: f64,
+    impulse: f64,
+}
+
+fn to_vector<U>(v: Vector3d<si::SI<f64, U>>) -> Vector3d<f64> {
+    Vector3d::new(v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe)
+}
+
+fn has_shape(entity: &Entity) -> bool {
+    entity.get_state().get_shape() != Shape::None
+}
+
+impl ContactSolver {
+    fn get_contacts(&self, world: &[Entity], inverse_masses: &[f64]) -> Vec<Contact> {
+        let mut contacts = Vec::new();
+        let order = canonical_order(world);
+        for (position, &idx0) in order.iter().enumerate() {
+            for &idx1 in &order[position + 1..] {
+                let (entity0, entity1) = (&world[idx0], &world[idx1]);
+                let inverse_mass = inverse_masses[idx0] + inverse_masses[idx1];
+                if inverse_mass <= 0.0 || !self.is_neighbor(entity0, entity1) {
+                    continue;
+                }
+                let Some((_, normal)) = get_penetration(entity0, entity1) else {
+                    continue;
+                };
+                // Restitution only applies to the speed the entities already approach with,
+                // not to the one the forces of this step add, so resting contacts stay at rest
+                let relative_velocity = to_vector(entity1.get_state().get_velocity())
+                    - to_vector(entity0.get_state().get_velocity());
+                let approaching_speed = -relative_velocity.dot(normal);
+                contacts.push(Contact {
+                    idx0,
+                    idx1,
+                    normal,
+                    effective_mass: 1.0 / inverse_mass,
+                    target_speed: (self.restitution * approaching_speed).max(0.0),
+                    impulse: 0.0,
+                });
+            }
+        }
+        contacts
+    }
+}
+
+impl Interaction for ContactSolver {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool {
+        has_shape(transmitter) && has_shape(receiver) && are_touching(transmitter, receiver)
+    }
+
+    // Contacts are solved for the whole world at once in get_propagation
+    fn init(
+        &self,
+        _source: &Entity,
+        _neighbors: Vec<&Entity>,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        Vec::new()
+    }
+
+    fn react(
+        &self,
+        _reactor: &Entity,
+        _neighbors: Vec<&Entity>,
+        _influence: StateInfluence,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        Vec::new()
+    }
+
+    fn get_propagation(
+        &self,
+        world: &[Entity],
+        step_size: si::Second<f64>,
+        _limits: &PropagationLimits,
+    ) -> Propagation {
+        let inverse_masses: Vec<f64> = world
+            .iter()
+            .map(|e| {
+                let mass = e.get_state().get_mass().value_unsafe;
+                if mass.is_finite() && mass > 0.0 {
+                    1.0 / mass
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let mut contacts = self.get_contacts(world, &inverse_masses);
+        if contacts.is_empty() {
+            return Propagation::default();
+        }
+        let dt = step_size.value_unsafe;
+        // Velocities after the step if only the net forces of the entities acted
+        let mut velocities: Vec<Vector3d<f64>> = world
+            .iter()
+            .zip(&inverse_masses)
+            .map(|(e, inverse_mass)| {
+                let state = e.get_state();
+                to_vector(state.get_velocity())
+                    + to_vector(state.get_net_force()) * (inverse_mass * dt)
+            })
+            .collect();
+        for _ in 0..self.max_iterations {
+            let mut largest_change: f64 = 0.0;
+            for contact in contacts.iter_mut() {
+                let separating_speed =
+                    (velocities[contact.idx1] - velocities[contact.idx0]).dot(contact.normal);
+                let impulse = (contact.impulse
+                    + contact.effective_mass * (contact.target_speed - separating_speed))
+                    .max(0.0);
+                largest_change = largest_change.max((impulse - contact.impulse).abs());
+                let change = contact.normal * (impulse - contact.impulse);
+                contact.impulse = impulse;
+                velocities[contact.idx0] =
+                    velocities[contact.idx0] - change * inverse_masses[contact.idx0];
+                velocities[contact.idx1] =
+                    velocities[contact.idx1] + change * inverse_masses[contact.idx1];
+            }
+            if largest_change < self.tolerance {
+                break;
+            }
+        }
+        let mut influences = Vec::new();
+        for contact in contacts.iter().filter(|c| c.impulse > 0.0) {
+            let force = contact.normal * (contact.impulse / dt);
+            let force = Vector3d::new(force.x, force.y, force.z) * si::N;
+            let (entity0, entity1) = (&world[contact.idx0], &world[contact.idx1]);
+            influences.push(StateInfluence::force_influence(
+                entity0.get_identity(),
+                entity0.get_identity(),
+                entity1.get_identity(),
+                self.get_identifier(),
+                force,
+            ));
+            influences.push(StateInfluence::force_influence(
+                entity1.get_identity(),
+                entity1.get_identity(),
+                entity0.get_identity(),
+                self.get_identifier(),
+                -force,
+            ));
+        }
+        influences.into()
+    }
+}
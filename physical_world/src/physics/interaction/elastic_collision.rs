@@ -3,31 +3,61 @@ use vector3d::Vector3d;
 
 use crate::physics::entity::Entity;
 use crate::physics::interaction::helpers::{
-    are_touching, get_velocity_diff_after_collision, get_force_in_direction, moves_towards
+    are_touching, get_velocity_diff_after_collision, moves_towards, split_force,
 };
 use crate::physics::interaction::interaction::Interaction;
 use crate::physics::state::state::State;
 use crate::physics::state::state_influence::StateInfluence;
-use crate::physics::system::PRECISION;
+use crate::physics::tolerance::Tolerances;
 use crate::utils::identity::Identity;
 
 use super::helpers::applies_force_in_direction;
 
-#[derive(Clone, Copy)]
-pub struct ElasticCollision;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ElasticCollision {
+    pub identifier: &'static str,
+    // Share of the approaching speed that is returned after a collision, 1 keeps it elastic
+    pub restitution: f64,
+    // Owned by the system the interaction is part of
+    tolerances: Tolerances,
+}
+
+impl ElasticCollision {
+    pub fn new(identifier: &'static str, restitution: f64) -> ElasticCollision {
+        ElasticCollision {
+            identifier,
+            restitution,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ElasticCollision {
+    fn default() -> Self {
+        ElasticCollision {
+            identifier: "elastic collision",
+            restitution: 1.0,
+            tolerances: Tolerances::default(),
+        }
+    }
+}
 
 impl Interaction for ElasticCollision {
     fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool {
         are_touching(transmitter, receiver)
     }
 
-    fn init(&self, source: &Entity, neighbors: Vec<&Entity>, step_size: si::Second<f64>) -> Vec<StateInfluence> {
+    fn init(
+        &self,
+        source: &Entity,
+        neighbors: Vec<&Entity>,
+        step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
         let mut influences = Vec::new();
         let mut forces = Vector3d::new(0.0, 0.0, 0.0) * si::N;
-        for neigh in neighbors
-            .iter()
-            .filter(|n| moves_towards(source, n))
-        {
+        // Sum of the magnitudes of the forces on the neighbors
+        let mut applied = 0.0 * si::N;
+        for neigh in neighbors.iter().filter(|n| moves_towards(source, n)) {
             let collision_vel = if moves_towards(neigh, source) {
                 let dummy = Entity::new(
                     neigh.get_identity(),
@@ -39,12 +69,20 @@ impl Interaction for ElasticCollision {
                         neigh.get_state().get_shape(),
                     ),
                 );
-                get_velocity_diff_after_collision(source, &dummy)
+                get_velocity_diff_after_collision(source, &dummy, self.restitution)
             } else {
-                get_velocity_diff_after_collision(source, neigh)
+                get_velocity_diff_after_collision(source, neigh, self.restitution)
             };
-            if collision_vel.norm2().sqrt() * PRECISION > 1.0 * si::MPS {
+            let approach = (source.get_state().get_velocity() - neigh.get_state().get_velocity())
+                .norm2()
+                .sqrt();
+            if !self
+                .tolerances
+                .velocity
+                .is_negligible(collision_vel.norm2().sqrt(), approach)
+            {
                 let force = collision_vel / step_size * neigh.get_state().get_mass();
+                applied += force.norm2().sqrt();
                 let influence = StateInfluence::force_influence(
                     source.get_identity(),
                     source.get_identity(),
@@ -56,7 +94,11 @@ impl Interaction for ElasticCollision {
                 influences.push(influence);
             }
         }
-        if forces.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        if !self
+            .tolerances
+            .force
+            .is_negligible(forces.norm2().sqrt(), applied)
+        {
             let influence = StateInfluence::force_influence(
                 source.get_identity(),
                 source.get_identity(),
@@ -74,7 +116,7 @@ impl Interaction for ElasticCollision {
         reactor: &Entity,
         neighbors: Vec<&Entity>,
         influence: StateInfluence,
-        _step_size: si::Second<f64>
+        _step_size: si::Second<f64>,
     ) -> Vec<StateInfluence> {
         if reactor.get_identity() != influence.get_receiver_id() {
             panic!(
@@ -95,12 +137,18 @@ impl Interaction for ElasticCollision {
         );
         let mut influences = Vec::new();
         let mut forces = Vector3d::new(0.0, 0.0, 0.0) * si::N;
-        for neigh in neighbors
-            .iter()
-            .filter(|n| applies_force_in_direction(&inf_entity, n))
-        {
-            let force = get_force_in_direction(&inf_entity, neigh);
-            if force.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        let applied = influence.get_state_change().get_net_force().norm2().sqrt();
+        let pushed = neighbors
+            .into_iter()
+            .filter(|n| applies_force_in_direction(&inf_entity, n) && are_touching(&inf_entity, n))
+            .collect::<Vec<&Entity>>();
+        let shares = split_force(&inf_entity, &pushed);
+        for (neigh, force) in pushed.into_iter().zip(shares) {
+            if !self
+                .tolerances
+                .force
+                .is_negligible(force.norm2().sqrt(), applied)
+            {
                 let new_influence = StateInfluence::force_influence(
                     influence.get_source_id(),
                     reactor.get_identity(),
@@ -113,7 +161,11 @@ impl Interaction for ElasticCollision {
             }
         }
         let reactor_force = influence.get_state_change().get_net_force() - forces;
-        if reactor_force.norm2().sqrt() * PRECISION > 1.0 * si::N {
+        if !self
+            .tolerances
+            .force
+            .is_negligible(reactor_force.norm2().sqrt(), applied)
+        {
             let self_influence = StateInfluence::force_influence(
                 influence.get_source_id(),
                 reactor.get_identity(),
@@ -127,11 +179,20 @@ impl Interaction for ElasticCollision {
     }
 
     fn get_identifier(&self) -> &'static str {
-        "elastic collision"
+        self.identifier
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn set_tolerances(&mut self, tolerances: Tolerances) {
+        self.tolerances = tolerances;
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod elastic_collision {
     use super::*;
     use crate::physics::entity::Entity;
@@ -166,7 +227,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = false;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -205,7 +266,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = false;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -244,7 +305,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -283,7 +344,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -322,7 +383,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -361,7 +422,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -400,7 +461,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -439,7 +500,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -478,7 +539,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -517,7 +578,7 @@ mod elastic_collision {
             ),
         );
         let expected_res = true;
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.is_neighbor(&e0, &e1);
         assert!(
             res == expected_res,
@@ -544,7 +605,7 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![];
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -580,7 +641,7 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -652,7 +713,7 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0, &n1, &n2, &n3];
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         assert!(
             res.is_empty(),
@@ -699,7 +760,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let neighbors = vec![&n0, &n1];
         let mut res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         let res_self = res.pop().unwrap();
@@ -788,7 +849,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let neighbors = vec![&n0, &n1];
         let mut res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         let res_self = res.pop().unwrap();
@@ -877,7 +938,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let neighbors = vec![&n0, &n1];
         let res = elastic_collision.init(&source, neighbors.clone(), 1.0 * si::S);
         // let res_self = res.pop().unwrap();
@@ -954,7 +1015,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         // let expected_res_self = StateInfluence::force_influence(
         //     source.get_identity(),
         //     source.get_identity(),
@@ -1005,7 +1066,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1014,7 +1075,8 @@ mod elastic_collision {
             Vector3d::new(-100.0, 0.0, 0.0) * si::N,
         );
         let neighbors = vec![];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1047,7 +1109,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1092,7 +1154,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0, &n1, &n2];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1125,7 +1188,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1146,7 +1209,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1181,7 +1245,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1202,7 +1266,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1251,7 +1316,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1272,7 +1337,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_self = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1321,7 +1387,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1342,7 +1408,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1377,7 +1444,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1398,7 +1465,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1433,7 +1501,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1466,7 +1534,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0, &n1];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other0 = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
@@ -1515,7 +1584,7 @@ mod elastic_collision {
                 }),
             ),
         );
-        let elastic_collision = ElasticCollision;
+        let elastic_collision = ElasticCollision::default();
         let influence = StateInfluence::force_influence(
             "source",
             "transmitter",
@@ -1548,7 +1617,8 @@ mod elastic_collision {
             ),
         );
         let neighbors = vec![&n0, &n1];
-        let mut res = elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
+        let mut res =
+            elastic_collision.react(&reactor, neighbors.clone(), influence.clone(), 1.0 * si::S);
         let expected_res_other_0 = StateInfluence::force_influence(
             influence.get_source_id(),
             reactor.get_identity(),
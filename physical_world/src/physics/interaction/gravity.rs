@@ -1,14 +1,45 @@
-use dimensioned::si;
+use dimensioned::{si, Sqrt};
 use vector3d::Vector3d;
 
 use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::{get_softened_gravity_pull, GRAVITATIONAL_CONSTANT};
 use crate::physics::interaction::interaction::Interaction;
 use crate::physics::state::state_influence::StateInfluence;
+use crate::physics::tolerance::Tolerances;
+use crate::utils::identity::Identity;
 
-use crate::physics::interaction::helpers::get_gravity_pull;
+// Pull of every entity on every other one, each source receives the sum of the pulls on it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity {
+    pub identifier: &'static str,
+    // In N*m^2/kg^2
+    pub constant: f64,
+    pub softening: si::Meter<f64>,
+    // Owned by the system the interaction is part of
+    tolerances: Tolerances,
+}
+
+impl Gravity {
+    pub fn new(identifier: &'static str, constant: f64, softening: si::Meter<f64>) -> Gravity {
+        Gravity {
+            identifier,
+            constant,
+            softening,
+            ..Default::default()
+        }
+    }
+}
 
-#[derive(Clone, Copy)]
-pub struct Gravity;
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity {
+            identifier: "Gravity",
+            constant: GRAVITATIONAL_CONSTANT,
+            softening: 0.0 * si::M,
+            tolerances: Tolerances::default(),
+        }
+    }
+}
 
 impl Interaction for Gravity {
     // fn is_applicable(&self, _target: &Entity, _influencer: &Entity, _env: &Vec<&Entity>) -> bool {
@@ -24,30 +55,66 @@ impl Interaction for Gravity {
     //         Shape::None,
     //         Vec::<EmitterPoint>::new(),
     //     );
-    //     // self.print(target, influencer, state_influence);
     //     self.log(target, influencer, &state_influence);
     //     state_influence
     // }
 
-    fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool {
+    fn is_neighbor(&self, _transmitter: &Entity, _receiver: &Entity) -> bool {
         true
     }
 
-    fn init(&self, source: &Entity, neighbors: Vec<&Entity>, step_size: si::Second<f64>) -> Vec<StateInfluence> {
-        Vec::new()
+    fn init(
+        &self,
+        source: &Entity,
+        neighbors: Vec<&Entity>,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        let pulls: Vec<Vector3d<si::Newton<f64>>> = neighbors
+            .iter()
+            .map(|neigh| get_softened_gravity_pull(neigh, source, self.constant, self.softening))
+            .collect();
+        let pull = pulls
+            .iter()
+            .fold(Vector3d::new(0.0, 0.0, 0.0) * si::N, |pull, p| pull + *p);
+        let applied = pulls
+            .iter()
+            .fold(0.0 * si::N, |applied, p| applied + p.norm2().sqrt());
+        if !self
+            .tolerances
+            .force
+            .is_negligible(pull.norm2().sqrt(), applied)
+        {
+            vec![StateInfluence::force_influence(
+                source.get_identity(),
+                source.get_identity(),
+                source.get_identity(),
+                self.get_identifier(),
+                pull,
+            )]
+        } else {
+            Vec::new()
+        }
     }
 
     fn react(
         &self,
-        receiver: &Entity,
-        neighbors: Vec<&Entity>,
-        influence: StateInfluence,
-        step_size: si::Second<f64>
+        _receiver: &Entity,
+        _neighbors: Vec<&Entity>,
+        _influence: StateInfluence,
+        _step_size: si::Second<f64>,
     ) -> Vec<StateInfluence> {
         Vec::new()
     }
 
     fn get_identifier(&self) -> &'static str {
-        "Gravity"
+        self.identifier
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn set_tolerances(&mut self, tolerances: Tolerances) {
+        self.tolerances = tolerances;
     }
 }
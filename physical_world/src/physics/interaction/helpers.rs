@@ -1,27 +1,212 @@
-use dimensioned::{si, Sqrt};
+use dimensioned::{si, Abs, Sqrt};
 use vector3d::Vector3d;
 
 use crate::physics::entity::Entity;
 use crate::physics::state::shape::Shape;
+use crate::utils::identity::Identity;
+
+// In N*m^2/kg^2
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.67430e-11;
+
+// Bounds of splitting a force among several entities
+const SPLIT_ITERATIONS: usize = 100;
+const SPLIT_TOLERANCE: f64 = 1e-12;
 
 pub fn are_touching(entity0: &Entity, entity1: &Entity) -> bool {
     let s0 = entity0.get_state();
     let s1 = entity1.get_state();
     let loc0 = s0.get_location();
     let loc1 = s1.get_location();
-    let dist = (loc0 - loc1).norm2().sqrt();
-    let target_radius = match s0.get_shape() {
-        Shape::Sphere(s) => s.radius,
-        _ => panic!("Shape is not a Sphere."),
-    };
-    let influencer_radius = match s1.get_shape() {
-        Shape::Sphere(s) => s.radius,
-        _ => panic!("Shape is not a Sphere."),
+    match (s0.get_shape(), s1.get_shape()) {
+        (Shape::Sphere(sphere0), Shape::Sphere(sphere1)) => {
+            let dist = (loc0 - loc1).norm2().sqrt();
+            dist <= sphere0.radius + sphere1.radius
+        }
+        (Shape::Sphere(sphere), Shape::Cuboid(cuboid)) => {
+            sphere_touches_cuboid(loc0, sphere.radius, loc1, cuboid.half_extents)
+        }
+        (Shape::Cuboid(cuboid), Shape::Sphere(sphere)) => {
+            sphere_touches_cuboid(loc1, sphere.radius, loc0, cuboid.half_extents)
+        }
+        (Shape::Cuboid(cuboid0), Shape::Cuboid(cuboid1)) => {
+            let rel_location = loc1 - loc0;
+            let extents = cuboid0.half_extents + cuboid1.half_extents;
+            rel_location.x.abs() <= extents.x
+                && rel_location.y.abs() <= extents.y
+                && rel_location.z.abs() <= extents.z
+        }
+        // Point masses never touch
+        (Shape::None, _) | (_, Shape::None) => false,
+    }
+}
+
+fn sphere_touches_cuboid(
+    sphere_location: Vector3d<si::Meter<f64>>,
+    radius: si::Meter<f64>,
+    cuboid_location: Vector3d<si::Meter<f64>>,
+    half_extents: Vector3d<si::Meter<f64>>,
+) -> bool {
+    // Distance between the center of the sphere and the closest point of the cuboid
+    let rel_location = sphere_location - cuboid_location;
+    let outside = |offset: si::Meter<f64>, extent: si::Meter<f64>| {
+        if offset.abs() > extent {
+            offset.abs() - extent
+        } else {
+            0.0 * si::M
+        }
     };
-    dist <= target_radius + influencer_radius
+    let dist = Vector3d::new(
+        outside(rel_location.x, half_extents.x),
+        outside(rel_location.y, half_extents.y),
+        outside(rel_location.z, half_extents.z),
+    );
+    dist.norm2() <= radius * radius
+}
+
+// Entities without a shape do not contain any location
+pub fn contains_location(entity: &Entity, location: Vector3d<si::Meter<f64>>) -> bool {
+    let rel_location = location - entity.get_state().get_location();
+    match entity.get_state().get_shape() {
+        Shape::Sphere(sphere) => rel_location.norm2() <= sphere.radius * sphere.radius,
+        Shape::Cuboid(cuboid) => {
+            rel_location.x.abs() <= cuboid.half_extents.x
+                && rel_location.y.abs() <= cuboid.half_extents.y
+                && rel_location.z.abs() <= cuboid.half_extents.z
+        }
+        Shape::None => false,
+    }
+}
+
+// How far the shapes of the entities overlap, positive if they overlap. Entities without a shape
+// cannot overlap.
+pub fn get_penetration_depth(entity0: &Entity, entity1: &Entity) -> Option<si::Meter<f64>> {
+    get_penetration(entity0, entity1).map(|(depth, _)| depth)
+}
+
+// Unit vector along one axis, pointing to the side of the offset
+fn axis_direction(axis: usize, offset: f64) -> Vector3d<f64> {
+    let mut direction = [0.0; 3];
+    direction[axis] = if offset < 0.0 { -1.0 } else { 1.0 };
+    Vector3d::new(direction[0], direction[1], direction[2])
+}
+
+fn min_axis(values: [f64; 3]) -> usize {
+    (0..3)
+        .min_by(|a, b| values[*a].total_cmp(&values[*b]))
+        .unwrap_or(0)
+}
+
+// Penetration depth and the normal along which entity1 has to move away from entity0 to
+// separate them. Normals of concentric shapes point along x.
+pub fn get_penetration(
+    entity0: &Entity,
+    entity1: &Entity,
+) -> Option<(si::Meter<f64>, Vector3d<f64>)> {
+    let s0 = entity0.get_state();
+    let s1 = entity1.get_state();
+    let rel = s1.get_location() - s0.get_location();
+    let rel = [rel.x.value_unsafe, rel.y.value_unsafe, rel.z.value_unsafe];
+    match (s0.get_shape(), s1.get_shape()) {
+        (Shape::Sphere(sphere0), Shape::Sphere(sphere1)) => {
+            let dist = (rel[0] * rel[0] + rel[1] * rel[1] + rel[2] * rel[2]).sqrt();
+            let normal = if dist > 0.0 {
+                Vector3d::new(rel[0] / dist, rel[1] / dist, rel[2] / dist)
+            } else {
+                Vector3d::new(1.0, 0.0, 0.0)
+            };
+            Some((sphere0.radius + sphere1.radius - dist * si::M, normal))
+        }
+        (Shape::Sphere(_), Shape::Cuboid(_)) => get_penetration(entity1, entity0)
+            .map(|(depth, normal)| (depth, Vector3d::new(-normal.x, -normal.y, -normal.z))),
+        (Shape::Cuboid(cuboid), Shape::Sphere(sphere)) => {
+            let closest = get_closest_point(entity0, s1.get_location());
+            let outside = s1.get_location() - closest;
+            let dist = outside.norm2().sqrt();
+            if dist > 0.0 * si::M {
+                let normal = outside / dist;
+                return Some((
+                    sphere.radius - dist,
+                    Vector3d::new(
+                        normal.x.value_unsafe,
+                        normal.y.value_unsafe,
+                        normal.z.value_unsafe,
+                    ),
+                ));
+            }
+            // The center of the sphere is inside of the cuboid, it leaves through the closest face
+            let half = cuboid.half_extents;
+            let inside = [
+                half.x.value_unsafe - rel[0].abs(),
+                half.y.value_unsafe - rel[1].abs(),
+                half.z.value_unsafe - rel[2].abs(),
+            ];
+            let axis = min_axis(inside);
+            Some((
+                sphere.radius + inside[axis] * si::M,
+                axis_direction(axis, rel[axis]),
+            ))
+        }
+        (Shape::Cuboid(cuboid0), Shape::Cuboid(cuboid1)) => {
+            let extents = cuboid0.half_extents + cuboid1.half_extents;
+            let overlap = [
+                extents.x.value_unsafe - rel[0].abs(),
+                extents.y.value_unsafe - rel[1].abs(),
+                extents.z.value_unsafe - rel[2].abs(),
+            ];
+            let axis = min_axis(overlap);
+            Some((overlap[axis] * si::M, axis_direction(axis, rel[axis])))
+        }
+        _ => None,
+    }
+}
+
+// Point of the shape of the entity that is closest to the location
+pub fn get_closest_point(
+    entity: &Entity,
+    location: Vector3d<si::Meter<f64>>,
+) -> Vector3d<si::Meter<f64>> {
+    let center = entity.get_state().get_location();
+    let rel_location = location - center;
+    match entity.get_state().get_shape() {
+        Shape::Sphere(sphere) => {
+            let distance = rel_location.norm2().sqrt();
+            if distance <= sphere.radius {
+                location
+            } else {
+                center + rel_location * (sphere.radius / distance)
+            }
+        }
+        Shape::Cuboid(cuboid) => {
+            let clamp = |offset: si::Meter<f64>, extent: si::Meter<f64>| {
+                if offset > extent {
+                    extent
+                } else if offset < -extent {
+                    -extent
+                } else {
+                    offset
+                }
+            };
+            center
+                + Vector3d::new(
+                    clamp(rel_location.x, cuboid.half_extents.x),
+                    clamp(rel_location.y, cuboid.half_extents.y),
+                    clamp(rel_location.z, cuboid.half_extents.z),
+                )
+        }
+        Shape::None => center,
+    }
 }
 
+// Normal of the contact between the shapes, perpendicular to the touching face of a cuboid.
+// Entities without a shape are connected center to center.
 fn get_normal_direction(from_entity: &Entity, to_entity: &Entity) -> Vector3d<si::Unitless<f64>> {
+    if let Some((_, normal)) = get_penetration(from_entity, to_entity) {
+        return Vector3d::new(
+            si::Unitless::new(normal.x),
+            si::Unitless::new(normal.y),
+            si::Unitless::new(normal.z),
+        );
+    }
     let from = from_entity.get_state();
     let to = to_entity.get_state();
     let rel_location: Vector3d<si::Meter<f64>> = to.get_location() - from.get_location();
@@ -47,9 +232,8 @@ pub fn get_force_in_direction(
     from_entity: &Entity,
     to_entity: &Entity,
 ) -> Vector3d<si::Newton<f64>> {
-    if !applies_force_in_direction(from_entity, to_entity) {
-        Vector3d::new(0.0, 0.0, 0.0) * si::N
-    } else if !are_touching(from_entity, to_entity) {
+    if !applies_force_in_direction(from_entity, to_entity) || !are_touching(from_entity, to_entity)
+    {
         Vector3d::new(0.0, 0.0, 0.0) * si::N
     } else {
         let normal_direction = get_normal_direction(from_entity, to_entity);
@@ -58,12 +242,59 @@ pub fn get_force_in_direction(
     }
 }
 
+// Indices of the entities ordered by identity. Walking the world in this order instead of
+// the order the entities were added keeps the results independent of that order.
+pub fn canonical_order(world: &[Entity]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..world.len()).collect();
+    order.sort_by_key(|&idx| world[idx].get_identity());
+    order
+}
+
+// Splits the net force of the entity among the entities it pushes along parallel paths.
+// The shares are the non-negative magnitudes along the normals whose sum is closest to the
+// force, so no more force is passed on than is applied. For a single entity or orthogonal
+// normals the shares are the forces in direction.
+pub fn split_force(
+    from_entity: &Entity,
+    to_entities: &[&Entity],
+) -> Vec<Vector3d<si::Newton<f64>>> {
+    let force = from_entity.get_state().get_net_force();
+    let normals: Vec<Vector3d<si::Unitless<f64>>> = to_entities
+        .iter()
+        .map(|to_entity| get_normal_direction(from_entity, to_entity))
+        .collect();
+    let projections: Vec<f64> = normals.iter().map(|n| n.dot(force).value_unsafe).collect();
+    let tolerance = SPLIT_TOLERANCE * force.norm2().sqrt().value_unsafe;
+    let mut magnitudes = vec![0.0; normals.len()];
+    // Projected Gauss-Seidel on the normal equations
+    for _ in 0..SPLIT_ITERATIONS {
+        let mut largest_change: f64 = 0.0;
+        for idx in 0..normals.len() {
+            let others: f64 = (0..normals.len())
+                .filter(|&other| other != idx)
+                .map(|other| normals[idx].dot(normals[other]).value_unsafe * magnitudes[other])
+                .sum();
+            let magnitude = (projections[idx] - others).max(0.0);
+            largest_change = largest_change.max((magnitude - magnitudes[idx]).abs());
+            magnitudes[idx] = magnitude;
+        }
+        if largest_change <= tolerance {
+            break;
+        }
+    }
+    normals
+        .into_iter()
+        .zip(magnitudes)
+        .map(|(normal, magnitude)| normal * (magnitude * si::N))
+        .collect()
+}
+
 fn relative_velocity(from_entity: &Entity, to_entity: &Entity) -> si::Meter2PerSecond<f64> {
     let from = from_entity.get_state();
     let to = to_entity.get_state();
     let rel_velocity = to.get_velocity() - from.get_velocity();
-    let rel_location = to.get_location() - from.get_location();
-    rel_velocity.dot(rel_location)
+    let dist = (to.get_location() - from.get_location()).norm2().sqrt();
+    rel_velocity.dot(get_normal_direction(from_entity, to_entity)) * dist
 }
 
 pub fn relatively_moves_towards(from_entity: &Entity, to_entity: &Entity) -> bool {
@@ -71,7 +302,10 @@ pub fn relatively_moves_towards(from_entity: &Entity, to_entity: &Entity) -> boo
     // < 0.0 => moving towards, > 0.0 => moving away, == 0.0 => not moving
 }
 
-fn get_velocity_val_in_direction(from_entity: &Entity, to_entity: &Entity) -> si::MeterPerSecond<f64> {
+fn get_velocity_val_in_direction(
+    from_entity: &Entity,
+    to_entity: &Entity,
+) -> si::MeterPerSecond<f64> {
     let from = from_entity.get_state();
     let velocity = from.get_velocity();
     let normal_direction = get_normal_direction(from_entity, to_entity);
@@ -84,25 +318,25 @@ pub fn moves_towards(from_entity: &Entity, to_entity: &Entity) -> bool {
     res > 0.0 * si::MPS && relatively_moves_towards(from_entity, to_entity)
 }
 
+// Change of velocity of the second entity, the restitution is the share of the approaching speed
+// that is returned
 pub fn get_velocity_diff_after_collision(
     from_entity: &Entity,
     to_entity: &Entity,
+    restitution: f64,
 ) -> Vector3d<si::MeterPerSecond<f64>> {
-    let res = if !relatively_moves_towards(from_entity, to_entity) {
-        Vector3d::new(0.0, 0.0, 0.0) * si::MPS
-    } else if !are_touching(from_entity, to_entity) {
+    let res = if !relatively_moves_towards(from_entity, to_entity)
+        || !are_touching(from_entity, to_entity)
+    {
         Vector3d::new(0.0, 0.0, 0.0) * si::MPS
     } else {
         let from = from_entity.get_state();
         let to = to_entity.get_state();
-        let rel_location = to.get_location() - from.get_location();
-        let dist = rel_location.norm2().sqrt();
-        let normal_direction = rel_location / dist;
+        let normal_direction = get_normal_direction(from_entity, to_entity);
         let reduced_system_mass = 1.0 / (1.0 / to.get_mass() + 1.0 / from.get_mass());
         let rel_velocity = to.get_velocity() - from.get_velocity();
         let impact_speed = normal_direction.dot(rel_velocity);
-        let coefficient_of_restitution = 1.0;
-        let impulse = (1.0 + coefficient_of_restitution) * reduced_system_mass * impact_speed;
+        let impulse = (1.0 + restitution) * reduced_system_mass * impact_speed;
         let velocity_diff: Vector3d<si::MeterPerSecond<f64>> =
             -normal_direction * (impulse / to.get_mass());
         velocity_diff
@@ -115,20 +349,35 @@ pub fn get_normal_force(from_entity: &Entity, to_entity: &Entity) -> Vector3d<si
 }
 
 pub fn get_gravity_pull(from_entity: &Entity, to_entity: &Entity) -> Vector3d<si::Newton<f64>> {
+    get_softened_gravity_pull(from_entity, to_entity, GRAVITATIONAL_CONSTANT, 0.0 * si::M)
+}
+
+// Pull of the first entity on the second one, the softening length keeps the pull finite for
+// entities at the same location. The constant is in N*m^2/kg^2.
+pub fn get_softened_gravity_pull(
+    from_entity: &Entity,
+    to_entity: &Entity,
+    constant: f64,
+    softening: si::Meter<f64>,
+) -> Vector3d<si::Newton<f64>> {
     let from = from_entity.get_state();
     let to = to_entity.get_state();
-    let g = 6.67430e-11 * si::N * si::M2PKG / si::KG;
     let rel_location = from.get_location() - to.get_location();
-    let force = rel_location * g * from.get_mass() * to.get_mass()
-        / (rel_location.norm2() * rel_location.norm2().sqrt());
-    force
+    let squared_distance = (rel_location.norm2() + softening * softening).value_unsafe;
+    let scale = constant * from.get_mass().value_unsafe * to.get_mass().value_unsafe
+        / (squared_distance * squared_distance.sqrt());
+    Vector3d::new(
+        rel_location.x.value_unsafe * scale,
+        rel_location.y.value_unsafe * scale,
+        rel_location.z.value_unsafe * scale,
+    ) * si::N
 }
 
 #[cfg(test)]
 mod test_helpers {
     use super::*;
     use crate::physics::entity::Entity;
-    use crate::physics::state::shape::{Shape, Sphere};
+    use crate::physics::state::shape::{Cuboid, Shape, Sphere};
     use crate::physics::state::state::State;
     use dimensioned::si;
     use vector3d::Vector3d;
@@ -144,7 +393,9 @@ mod test_helpers {
                 Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
         let e1 = Entity::new(
@@ -154,10 +405,17 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
-        assert!(moves_towards(&e0, &e1) == false, "Expected {}, got {}.", false, true);
+        assert!(
+            !moves_towards(&e0, &e1),
+            "Expected {}, got {}.",
+            false,
+            true
+        );
     }
 
     #[test]
@@ -169,7 +427,9 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
         let e1 = Entity::new(
@@ -179,10 +439,12 @@ mod test_helpers {
                 Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
-        assert!(moves_towards(&e0, &e1) == true, "Expected {}, got {}.", true, false);
+        assert!(moves_towards(&e0, &e1), "Expected {}, got {}.", true, false);
     }
 
     #[test]
@@ -194,7 +456,9 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
         let e1 = Entity::new(
@@ -204,10 +468,17 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
-        assert!(moves_towards(&e0, &e1) == false, "Expected {}, got {}.", false, true);
+        assert!(
+            !moves_towards(&e0, &e1),
+            "Expected {}, got {}.",
+            false,
+            true
+        );
     }
 
     #[test]
@@ -219,7 +490,9 @@ mod test_helpers {
                 Vector3d::new(5.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
         let e1 = Entity::new(
@@ -229,10 +502,17 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
-        assert!(moves_towards(&e0, &e1) == false, "Expected {}, got {}.", false, true);
+        assert!(
+            !moves_towards(&e0, &e1),
+            "Expected {}, got {}.",
+            false,
+            true
+        );
     }
 
     #[test]
@@ -244,7 +524,9 @@ mod test_helpers {
                 Vector3d::new(10.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
         let e1 = Entity::new(
@@ -254,10 +536,12 @@ mod test_helpers {
                 Vector3d::new(5.0, 0.0, 0.0) * si::MPS,
                 Vector3d::new(0.0, 0.0, 0.0) * si::N,
                 0.0 * si::KG,
-                Shape::Sphere( Sphere { radius: 1.0 * si::M } ),
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
             ),
         );
-        assert!(moves_towards(&e0, &e1) == true, "Expected {}, got {}.", true, false);
+        assert!(moves_towards(&e0, &e1), "Expected {}, got {}.", true, false);
     }
 
     #[test]
@@ -388,6 +672,145 @@ mod test_helpers {
         );
     }
 
+    #[test]
+    fn test_are_touching_cuboids() {
+        fn entity(id: &'static str, loc: Vector3d<si::Meter<f64>>, shape: Shape) -> Entity {
+            Entity::new(
+                id,
+                State::new(
+                    loc,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                    1.0 * si::KG,
+                    shape,
+                ),
+            )
+        }
+        let cuboid = Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(1.0, 2.0, 1.0) * si::M,
+        });
+        let sphere = Shape::Sphere(Sphere {
+            radius: 1.0 * si::M,
+        });
+        let wall = entity("wall", Vector3d::new(0.0, 0.0, 0.0) * si::M, cuboid);
+        let cases = [
+            (
+                entity("s", Vector3d::new(2.0, 0.0, 0.0) * si::M, sphere),
+                true,
+            ),
+            (
+                entity("s", Vector3d::new(2.1, 0.0, 0.0) * si::M, sphere),
+                false,
+            ),
+            (
+                entity("s", Vector3d::new(0.0, 2.9, 0.0) * si::M, sphere),
+                true,
+            ),
+            // Close to the edge, but outside of the rounded corner
+            (
+                entity("s", Vector3d::new(1.8, 2.8, 0.0) * si::M, sphere),
+                false,
+            ),
+            (
+                entity("c", Vector3d::new(2.0, 4.0, 2.0) * si::M, cuboid),
+                true,
+            ),
+            (
+                entity("c", Vector3d::new(2.0, 4.1, 0.0) * si::M, cuboid),
+                false,
+            ),
+            // Point masses never touch, not even inside of the cuboid
+            (
+                entity("p", Vector3d::new(0.0, 0.0, 0.0) * si::M, Shape::None),
+                false,
+            ),
+        ];
+        for (other, expected_result) in cases {
+            assert_eq!(are_touching(&wall, &other), expected_result, "{}", other);
+            assert_eq!(are_touching(&other, &wall), expected_result, "{}", other);
+        }
+        let point = entity("p", Vector3d::new(0.0, 0.0, 0.0) * si::M, Shape::None);
+        assert!(!are_touching(&point, &point.clone()));
+    }
+
+    #[test]
+    fn test_cuboid_contact_normal() {
+        let cuboid = |id: &'static str, x: f64, y: f64, velocity: f64, force: f64| {
+            Entity::new(
+                id,
+                State::new(
+                    Vector3d::new(x, y, 0.0) * si::M,
+                    Vector3d::new(velocity, 0.0, 0.0) * si::MPS,
+                    Vector3d::new(0.0, force, 0.0) * si::N,
+                    1.0 * si::KG,
+                    Shape::Cuboid(Cuboid {
+                        half_extents: Vector3d::new(1.0, 1.0, 1.0) * si::M,
+                    }),
+                ),
+            )
+        };
+        // Face contact with offset centers, the push is perpendicular to the face
+        let floor = cuboid("floor", 0.0, 0.0, 0.0, 0.0);
+        let pusher = cuboid("pusher", 1.5, 2.0, 0.0, -10.0);
+        assert_eq!(
+            get_force_in_direction(&pusher, &floor),
+            Vector3d::new(0.0, -10.0, 0.0) * si::N
+        );
+        // Sliding along the face does not move towards the floor
+        let slider = cuboid("slider", 1.5, 2.0, -1.0, 0.0);
+        assert!(!relatively_moves_towards(&slider, &floor));
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let cuboid = Entity::new(
+            "c",
+            State::new(
+                Vector3d::new(1.0, 0.0, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                Shape::Cuboid(Cuboid {
+                    half_extents: Vector3d::new(1.0, 1.0, 1.0) * si::M,
+                }),
+            ),
+        );
+        let sphere = Entity::new(
+            "s",
+            State::new(
+                Vector3d::new(0.0, 0.0, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                Shape::Sphere(Sphere {
+                    radius: 2.0 * si::M,
+                }),
+            ),
+        );
+        let outside = Vector3d::new(5.0, 3.0, 0.5) * si::M;
+        assert_eq!(
+            get_closest_point(&cuboid, outside),
+            Vector3d::new(2.0, 1.0, 0.5) * si::M
+        );
+        assert_eq!(
+            get_closest_point(&sphere, Vector3d::new(0.0, 4.0, 0.0) * si::M),
+            Vector3d::new(0.0, 2.0, 0.0) * si::M
+        );
+        assert!(contains_location(
+            &cuboid,
+            Vector3d::new(2.0, -1.0, 1.0) * si::M
+        ));
+        assert!(!contains_location(&cuboid, outside));
+        assert!(contains_location(
+            &sphere,
+            Vector3d::new(1.0, 1.0, 1.0) * si::M
+        ));
+        assert!(!contains_location(
+            &sphere,
+            Vector3d::new(2.0, 1.0, 0.0) * si::M
+        ));
+    }
+
     #[test]
     fn test_relatively_moves_towards() {
         fn test_move_entities(
@@ -829,6 +1252,45 @@ mod test_helpers {
         test_force_entities(loc0, force0, loc1, expected_force);
     }
 
+    #[test]
+    fn test_split_force() {
+        let sphere = |x: f64, y: f64, force: Vector3d<si::Newton<f64>>| {
+            Entity::new(
+                "test entity",
+                State::new(
+                    Vector3d::new(x, y, 0.0) * si::M,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                    force,
+                    1.0 * si::KG,
+                    Shape::Sphere(Sphere {
+                        radius: 0.5 * si::M,
+                    }),
+                ),
+            )
+        };
+        let pusher = sphere(0.0, 0.0, Vector3d::new(10.0, 10.0, 0.0) * si::N);
+        // Orthogonal normals get the forces in direction
+        let e0 = sphere(1.0, 0.0, Vector3d::new(0.0, 0.0, 0.0) * si::N);
+        let e1 = sphere(0.0, 1.0, Vector3d::new(0.0, 0.0, 0.0) * si::N);
+        let shares = split_force(&pusher, &[&e0, &e1]);
+        assert_eq!(shares[0], get_force_in_direction(&pusher, &e0));
+        assert_eq!(shares[1], get_force_in_direction(&pusher, &e1));
+        // Normals at 60 degrees pass on exactly the force
+        let e1 = sphere(
+            0.5,
+            3.0_f64.sqrt() / 2.0,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+        );
+        let shares = split_force(&pusher, &[&e0, &e1]);
+        let residual = pusher.get_state().get_net_force() - shares[0] - shares[1];
+        assert!(residual.norm2().sqrt().value_unsafe < 1e-9);
+        // A force along one normal is not passed on along the other
+        let pusher = sphere(0.0, 0.0, Vector3d::new(10.0, 0.0, 0.0) * si::N);
+        let shares = split_force(&pusher, &[&e0, &e1]);
+        assert_eq!(shares[0], Vector3d::new(10.0, 0.0, 0.0) * si::N);
+        assert_eq!(shares[1].norm2().sqrt(), 0.0 * si::N);
+    }
+
     #[test]
     fn test_get_velocity_diff_after_collision() {
         fn test_collision_entities(
@@ -864,7 +1326,7 @@ mod test_helpers {
                     }),
                 ),
             );
-            let res = get_velocity_diff_after_collision(&e0, &e1);
+            let res = get_velocity_diff_after_collision(&e0, &e1, 1.0);
             let diff = (res - expected_result).norm2().sqrt();
             let rounded_diff = (diff.value_unsafe * TEST_PRECISION as f64) as u128;
             assert!(
@@ -1,101 +1,217 @@
-use dimensioned::si;
 use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::canonical_order;
 use crate::physics::state::state_influence::StateInfluence;
+use crate::physics::tolerance::Tolerances;
 use crate::utils::identity::Identity;
+use dimensioned::si;
 
 pub trait Interaction {
     fn get_identifier(&self) -> &'static str;
+    fn clone_box(&self) -> Box<dyn Interaction>;
+    // Tolerances of the system the interaction is part of
+    fn set_tolerances(&mut self, _tolerances: Tolerances) {}
     fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool;
-    fn init(&self, source: &Entity, neighbors: Vec<&Entity>, step_size: si::Second<f64>) -> Vec<StateInfluence>;
+    fn init(
+        &self,
+        source: &Entity,
+        neighbors: Vec<&Entity>,
+        step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence>;
     fn react(
         &self,
         reactor: &Entity,
         neighbors: Vec<&Entity>,
         influence: StateInfluence,
-        step_size: si::Second<f64>
+        step_size: si::Second<f64>,
     ) -> Vec<StateInfluence>;
 
-    fn print(&self, transmitter: &Entity, receiver: &Entity, influence: &StateInfluence) {
-        println!(
-            "\n[{}]({} -> {}) -> {{\n{}}}\n",
-            self.get_identifier(),
-            transmitter.get_identity(),
-            receiver.get_identity(),
-            influence
-        );
-    }
-
     fn log(&self, transmitter: &Entity, receiver: &Entity, influence: &StateInfluence) {
         log::trace!(
-            "\n[{}]({} -> {}) -> {{\n{}}}\n",
-            self.get_identifier(),
-            transmitter.get_identity(),
-            receiver.get_identity(),
+            interaction = self.get_identifier(),
+            transmitter = transmitter.get_identity(),
+            receiver = receiver.get_identity();
+            "Influence {{\n{}}}",
             influence
         );
     }
 
+    // Propagates the influences on the stack through the world until every force ends at an
+    // entity. Each influence remembers the entities it passed, an influence that returns to one
+    // of them closes a cycle and is absorbed by its receiver instead of being passed on again.
+    // Influences beyond the limits are absorbed by their receivers as well.
     fn dfs(
         &self,
-        world: &Vec<Entity>,
+        world: &[Entity],
         influence_stack: &mut Vec<StateInfluence>,
-        step_size: si::Second<f64>
-    ) -> Vec<StateInfluence> {
-        let mut final_influences = Vec::new();
-        while let Some(influence) = influence_stack.pop() {
+        step_size: si::Second<f64>,
+        limits: &PropagationLimits,
+    ) -> Propagation {
+        let mut propagation = Propagation::default();
+        let mut stack: Vec<(StateInfluence, Vec<&'static str>)> = influence_stack
+            .drain(..)
+            .map(|influence| {
+                let path = vec![influence.get_transmitter_id()];
+                (influence, path)
+            })
+            .collect();
+        let mut reactions = 0;
+        while let Some((influence, path)) = stack.pop() {
             if influence.get_transmitter_id() == influence.get_receiver_id() {
-                final_influences.push(influence);
-            } else {
-                let receiver_idx_opt = world
-                    .iter()
-                    .position(|e| e.get_identity() == influence.get_receiver_id());
-                let receiver = match receiver_idx_opt {
-                    Some(idx) => &world[idx],
-                    None => panic!("Receiver of influence not found."),
-                };
-                let neighbors = world
-                    .iter()
-                    .filter(|&e| e != receiver && self.is_neighbor(&receiver, e))
-                    .collect::<Vec<&Entity>>();
-                influence_stack.extend(self.react(receiver, neighbors, influence, step_size));
+                propagation.influences.push(influence);
+                continue;
+            }
+            let cycle = path.contains(&influence.get_receiver_id());
+            let exhausted = path.len() > limits.max_depth || reactions >= limits.max_reactions;
+            if cycle || exhausted {
+                if cycle {
+                    propagation.cycles += 1;
+                }
+                propagation.exhausted |= exhausted;
+                propagation.influences.push(absorb(influence));
+                continue;
+            }
+            let receiver_idx_opt = world
+                .iter()
+                .position(|e| e.get_identity() == influence.get_receiver_id());
+            let receiver = match receiver_idx_opt {
+                Some(idx) => &world[idx],
+                None => {
+                    propagation.unknown_receivers += 1;
+                    propagation.influences.push(influence);
+                    continue;
+                }
+            };
+            let neighbors = canonical_order(world)
+                .into_iter()
+                .map(|idx| &world[idx])
+                .filter(|&e| e != receiver && self.is_neighbor(receiver, e))
+                .collect::<Vec<&Entity>>();
+            reactions += 1;
+            let mut receiver_path = path;
+            receiver_path.push(receiver.get_identity());
+            for new_influence in self.react(receiver, neighbors, influence, step_size) {
+                stack.push((new_influence, receiver_path.clone()));
             }
         }
-        final_influences
+        propagation
     }
 
-    fn get_influences(&self, world: &Vec<Entity>, step_size: si::Second<f64>) -> Vec<StateInfluence> {
-        let mut influences = Vec::new();
-        for source in world.clone() {
-            let neighbors = world
+    fn get_propagation(
+        &self,
+        world: &[Entity],
+        step_size: si::Second<f64>,
+        limits: &PropagationLimits,
+    ) -> Propagation {
+        let mut propagation = Propagation::default();
+        // Every source is initiated against the states at the beginning of the step
+        let order = canonical_order(world);
+        for &source_idx in &order {
+            let source = &world[source_idx];
+            let neighbors = order
                 .iter()
-                .filter(|&e| *e != source && self.is_neighbor(&source, e))
+                .map(|&idx| &world[idx])
+                .filter(|&e| e != source && self.is_neighbor(source, e))
                 .collect::<Vec<&Entity>>();
-            let mut influence_stack = self.init(&source, neighbors, step_size);
-            influences.extend(self.dfs(world, &mut influence_stack, step_size));
+            let mut influence_stack = self.init(source, neighbors, step_size);
+            propagation.merge(self.dfs(world, &mut influence_stack, step_size, limits));
+        }
+        propagation
+    }
+
+    fn get_influences(&self, world: &[Entity], step_size: si::Second<f64>) -> Vec<StateInfluence> {
+        self.get_propagation(world, step_size, &PropagationLimits::default())
+            .influences
+    }
+}
+
+// Budget of a single propagation by dfs, the depth is the number of entities an influence
+// passed, the reactions are counted over the whole propagation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PropagationLimits {
+    pub max_depth: usize,
+    pub max_reactions: usize,
+}
+
+impl Default for PropagationLimits {
+    fn default() -> Self {
+        PropagationLimits {
+            max_depth: 256,
+            max_reactions: 100_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Propagation {
+    pub influences: Vec<StateInfluence>,
+    // Influences that returned to an entity they already passed
+    pub cycles: usize,
+    // Whether the limits cut the propagation short
+    pub exhausted: bool,
+    // Influences for entities that are not part of the world, they are kept as they are
+    pub unknown_receivers: usize,
+}
+
+impl Propagation {
+    pub fn merge(&mut self, other: Propagation) {
+        self.influences.extend(other.influences);
+        self.cycles += other.cycles;
+        self.exhausted |= other.exhausted;
+        self.unknown_receivers += other.unknown_receivers;
+    }
+}
+
+impl From<Vec<StateInfluence>> for Propagation {
+    fn from(influences: Vec<StateInfluence>) -> Self {
+        Propagation {
+            influences,
+            ..Default::default()
         }
-        influences
     }
 }
 
-impl Eq for &'static dyn Interaction {}
+// The receiver keeps the influence instead of passing it on
+fn absorb(influence: StateInfluence) -> StateInfluence {
+    StateInfluence::new(
+        influence.get_source_id(),
+        influence.get_receiver_id(),
+        influence.get_receiver_id(),
+        influence.get_interaction_id(),
+        influence.get_state_change(),
+    )
+}
+
+impl Clone for Box<dyn Interaction> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl Eq for Box<dyn Interaction> {}
 
-impl PartialEq for &'static dyn Interaction {
+impl PartialEq for Box<dyn Interaction> {
     fn eq(&self, other: &Self) -> bool {
         self.get_identifier() == other.get_identifier()
     }
 }
 
-impl Identity for &'static dyn Interaction {
+impl Identity for Box<dyn Interaction> {
     fn get_identity(&self) -> &'static str {
         self.get_identifier()
     }
 }
 
+impl std::fmt::Debug for dyn Interaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.get_identifier())
+    }
+}
+
 #[cfg(test)]
 mod force_transmission_tests {
     use super::*;
-    use crate::physics::state::shape::{Shape, Sphere};
     use crate::physics::interaction::contact_forces::ContactForces;
+    use crate::physics::state::shape::{Shape, Sphere};
     use crate::physics::state::state::State;
     use dimensioned::si;
     use vector3d::Vector3d;
@@ -140,7 +256,7 @@ mod force_transmission_tests {
                 ),
             ),
         ];
-        let interaction = ContactForces;
+        let interaction = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -215,7 +331,7 @@ mod force_transmission_tests {
                 ),
             ),
         ];
-        let interaction = ContactForces;
+        let interaction = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -304,7 +420,7 @@ mod force_transmission_tests {
                 ),
             ),
         ];
-        let interaction = ContactForces;
+        let interaction = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -403,7 +519,7 @@ mod momentum_transition_tests {
                 ),
             ),
         ];
-        let interaction = ElasticCollision;
+        let interaction = ElasticCollision::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -478,7 +594,7 @@ mod momentum_transition_tests {
                 ),
             ),
         ];
-        let interaction = ElasticCollision;
+        let interaction = ElasticCollision::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -555,7 +671,7 @@ mod momentum_transition_tests {
                 ),
             ),
         ];
-        let interaction = ElasticCollision;
+        let interaction = ElasticCollision::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -632,7 +748,7 @@ mod momentum_transition_tests {
                 ),
             ),
         ];
-        let interaction = ElasticCollision;
+        let interaction = ElasticCollision::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -671,9 +787,9 @@ mod momentum_transition_tests {
 #[cfg(test)]
 mod force_momentum_transition_tests {
     use super::*;
+    use crate::physics::interaction::contact_forces::ContactForces;
     use crate::physics::interaction::elastic_collision::ElasticCollision;
     use crate::physics::state::shape::{Shape, Sphere};
-    use crate::physics::interaction::contact_forces::ContactForces;
     use crate::physics::state::state::State;
     use dimensioned::si;
     use vector3d::Vector3d;
@@ -718,8 +834,8 @@ mod force_momentum_transition_tests {
                 ),
             ),
         ];
-        let ec = ElasticCollision;
-        let cf = ContactForces;
+        let ec = ElasticCollision::default();
+        let cf = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -742,7 +858,6 @@ mod force_momentum_transition_tests {
                 ec.get_identifier(),
                 Vector3d::new(100.0, 0.0, 0.0) * si::N,
             ),
-
             StateInfluence::force_influence(
                 "e0",
                 "e2",
@@ -798,8 +913,8 @@ mod force_momentum_transition_tests {
                 ),
             ),
         ];
-        let ec = ElasticCollision;
-        let cf = ContactForces;
+        let ec = ElasticCollision::default();
+        let cf = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -877,8 +992,8 @@ mod force_momentum_transition_tests {
                 ),
             ),
         ];
-        let ec = ElasticCollision;
-        let cf = ContactForces;
+        let ec = ElasticCollision::default();
+        let cf = ContactForces::default();
         let mut expected_influences = vec![
             StateInfluence::force_influence(
                 "e0",
@@ -955,4 +1070,4 @@ mod force_momentum_transition_tests {
             "Some expected influences were not in results."
         );
     }
-}
\ No newline at end of file
+}
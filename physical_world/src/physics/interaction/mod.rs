@@ -1,5 +1,7 @@
 pub mod contact_forces;
+pub mod contact_solver;
 pub mod elastic_collision;
 pub mod gravity;
 pub mod helpers;
+#[allow(clippy::module_inception)]
 pub mod interaction;
@@ -1,4 +1,8 @@
 pub mod entity;
 pub mod interaction;
+pub mod observer;
+pub mod position_correction;
 pub mod state;
 pub mod system;
+pub mod tolerance;
+pub mod validation;
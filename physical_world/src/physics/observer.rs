@@ -0,0 +1,45 @@
+use dimensioned::si;
+
+use crate::physics::state::state_influence::StateInfluence;
+use crate::physics::system::System;
+
+// Hooks into the steps of a system, every method does nothing by default
+pub trait StepObserver {
+    // Called before anything of the step happens
+    fn before_step(&mut self, _system: &System, _step_size: si::Second<f64>) {}
+
+    // Called with all influences of the step before they are applied to the entities
+    fn influences_computed(&mut self, _system: &System, _influences: &[StateInfluence]) {}
+
+    // Called after the time of the system advanced
+    fn after_step(&mut self, _system: &System) {}
+}
+
+// Observers of a system. Forks of a system, such as the ones of predictions, are not observed, so
+// cloning leaves them behind.
+#[derive(Default)]
+pub struct Observers(Vec<Box<dyn StepObserver>>);
+
+impl Observers {
+    pub fn push(&mut self, observer: Box<dyn StepObserver>) {
+        self.0.push(observer);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Box<dyn StepObserver>> {
+        self.0.iter_mut()
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Observers::default()
+    }
+}
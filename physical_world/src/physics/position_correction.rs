@@ -0,0 +1,92 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::{canonical_order, get_penetration};
+use crate::utils::identity::Identity;
+
+// Separates overlapping shapes after integration by moving them apart along the contact normal.
+// Shifts are weighted by inverse mass, so the center of mass stays in place, and velocities are
+// not changed, so momentum is conserved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionCorrection {
+    // Overlap that is left, so that bodies at rest on each other keep touching. Like the
+    // penetration tolerance of validation it is a physical allowance rather than a rounding
+    // error, so it does not follow the length tolerance of the system.
+    pub slop: si::Meter<f64>,
+    // Share of the overlap beyond the slop removed per iteration, 1 projects the shapes apart
+    pub factor: f64,
+    // Passes over all pairs, separating one pair can push it into another
+    pub iterations: usize,
+}
+
+impl Default for PositionCorrection {
+    fn default() -> PositionCorrection {
+        PositionCorrection {
+            slop: 1e-6 * si::M,
+            factor: 1.0,
+            iterations: 4,
+        }
+    }
+}
+
+// Penetration depth removed between two entities during one step
+#[derive(Clone, Debug, PartialEq)]
+pub struct Correction {
+    pub entities: (&'static str, &'static str),
+    pub depth: si::Meter<f64>,
+}
+
+impl PositionCorrection {
+    pub fn correct(&self, entities: &mut [Entity]) -> Vec<Correction> {
+        let mut corrections: Vec<Correction> = Vec::new();
+        let order = canonical_order(entities);
+        for _ in 0..self.iterations {
+            let mut corrected = false;
+            for (position, &idx0) in order.iter().enumerate() {
+                for &idx1 in &order[position + 1..] {
+                    let (head, tail) = entities.split_at_mut(idx0.max(idx1));
+                    let (first, second) = (&mut head[idx0.min(idx1)], &mut tail[0]);
+                    let (entity0, entity1) = if idx0 < idx1 {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    let Some(depth) = self.separate(entity0, entity1) else {
+                        continue;
+                    };
+                    corrected = true;
+                    let pair = (entity0.get_identity(), entity1.get_identity());
+                    match corrections.iter_mut().find(|c| c.entities == pair) {
+                        Some(correction) => correction.depth += depth,
+                        None => corrections.push(Correction {
+                            entities: pair,
+                            depth,
+                        }),
+                    }
+                }
+            }
+            if !corrected {
+                break;
+            }
+        }
+        corrections
+    }
+
+    // Returns the removed depth, if the entities overlapped beyond the slop
+    fn separate(&self, entity0: &mut Entity, entity1: &mut Entity) -> Option<si::Meter<f64>> {
+        let (depth, normal) = get_penetration(entity0, entity1)?;
+        let inverse_mass0 = 1.0 / entity0.get_state().get_mass().value_unsafe;
+        let inverse_mass1 = 1.0 / entity1.get_state().get_mass().value_unsafe;
+        let inverse_mass = inverse_mass0 + inverse_mass1;
+        // Entities of invalid mass are left to validation
+        if depth <= self.slop || !inverse_mass.is_finite() || inverse_mass <= 0.0 {
+            return None;
+        }
+        let correction = (depth - self.slop) * self.factor;
+        let shift = Vector3d::new(normal.x, normal.y, normal.z) * correction / inverse_mass;
+        entity0.translate(-shift * inverse_mass0);
+        entity1.translate(shift * inverse_mass1);
+        Some(correction)
+    }
+}
@@ -1,3 +1,4 @@
 pub mod shape;
+#[allow(clippy::module_inception)]
 pub mod state;
 pub mod state_influence;
@@ -1,48 +1,55 @@
 use dimensioned::si;
+use vector3d::Vector3d;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Sphere {
     pub radius: si::Meter<f64>,
 }
 
+// Axis-aligned box around the location of its entity
 #[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cuboid {
+    pub half_extents: Vector3d<si::Meter<f64>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 pub enum Shape {
     Sphere(Sphere),
+    Cuboid(Cuboid),
+    #[default]
     None,
 }
 
-impl Default for Shape {
-    fn default() -> Self {
-        Shape::None
-    }
-}
-
+// Changes without a shape keep the shape, changes to another kind of shape replace it
 impl std::ops::Add<Shape> for Shape {
     type Output = Shape;
     fn add(self, rhs: Shape) -> Shape {
-        match self {
-            Shape::Sphere(me) => match rhs {
-                Shape::Sphere(other) => Shape::Sphere(Sphere {
-                    radius: me.radius + other.radius,
-                }),
-                Shape::None => self,
-            },
-            Shape::None => self,
+        match (self, rhs) {
+            (Shape::Sphere(me), Shape::Sphere(other)) => Shape::Sphere(Sphere {
+                radius: me.radius + other.radius,
+            }),
+            (Shape::Cuboid(me), Shape::Cuboid(other)) => Shape::Cuboid(Cuboid {
+                half_extents: me.half_extents + other.half_extents,
+            }),
+            (_, Shape::None) => self,
+            _ => rhs,
         }
     }
 }
 
+// Shapes of different kinds differ by the whole shape, so adding the difference replaces them
 impl std::ops::Sub<Shape> for Shape {
     type Output = Shape;
     fn sub(self, rhs: Shape) -> Shape {
-        match self {
-            Shape::Sphere(me) => match rhs {
-                Shape::Sphere(other) => Shape::Sphere(Sphere {
-                    radius: me.radius - other.radius,
-                }),
-                Shape::None => self,
-            },
-            Shape::None => rhs,
+        match (self, rhs) {
+            (Shape::Sphere(me), Shape::Sphere(other)) => Shape::Sphere(Sphere {
+                radius: me.radius - other.radius,
+            }),
+            (Shape::Cuboid(me), Shape::Cuboid(other)) => Shape::Cuboid(Cuboid {
+                half_extents: me.half_extents - other.half_extents,
+            }),
+            (Shape::None, _) => rhs,
+            _ => self,
         }
     }
 }
@@ -51,6 +58,7 @@ impl std::fmt::Display for Shape {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Shape::Sphere(_) => write!(f, "Sphere"),
+            Shape::Cuboid(_) => write!(f, "Cuboid"),
             Shape::None => write!(f, "None"),
         }
     }
@@ -2,7 +2,7 @@ use dimensioned::{si, Sqrt};
 use vector3d::Vector3d;
 
 use crate::physics::state::shape::Shape;
-use crate::physics::system::PRECISION;
+use crate::physics::tolerance::Tolerances;
 
 #[derive(Clone, Debug)]
 pub struct State {
@@ -50,10 +50,33 @@ impl State {
         self.shape
     }
 
+    // Moves the state without changing its velocity
+    pub fn translate(&mut self, offset: Vector3d<si::Meter<f64>>) {
+        self.location = self.location + offset;
+    }
+
     pub fn evolve(&mut self, elapsed_time: si::Second<f64>) {
         self.location = self.location + self.velocity * elapsed_time;
         self.velocity = self.velocity + (self.net_force / self.mass) * elapsed_time;
     }
+
+    // Equality up to the tolerances, differences of vectors are compared to the longer vector
+    pub fn approx_eq(&self, other: &State, tolerances: &Tolerances) -> bool {
+        tolerances.length.is_negligible(
+            (self.location - other.location).norm2().sqrt(),
+            larger(self.location.norm2().sqrt(), other.location.norm2().sqrt()),
+        ) && tolerances.velocity.is_negligible(
+            (self.velocity - other.velocity).norm2().sqrt(),
+            larger(self.velocity.norm2().sqrt(), other.velocity.norm2().sqrt()),
+        ) && tolerances.force.is_negligible(
+            (self.net_force - other.net_force).norm2().sqrt(),
+            larger(
+                self.net_force.norm2().sqrt(),
+                other.net_force.norm2().sqrt(),
+            ),
+        ) && tolerances.mass.are_close(self.mass, other.mass)
+            && self.shape == other.shape
+    }
 }
 
 impl std::ops::Add<&State> for &State {
@@ -92,19 +115,17 @@ impl std::fmt::Display for State {
     }
 }
 
+fn larger<T: PartialOrd>(value0: T, value1: T) -> T {
+    if value0 > value1 {
+        value0
+    } else {
+        value1
+    }
+}
+
+// Equality up to the default tolerances, System::are_close compares with those of a system
 impl std::cmp::PartialEq for State {
     fn eq(&self, other: &Self) -> bool {
-        let diff = State::new(
-            (self.location - other.location) * PRECISION,
-            (self.velocity - other.velocity) * PRECISION,
-            (self.net_force - other.net_force) * PRECISION,
-            (self.mass - other.mass) * PRECISION,
-            Shape::None,
-        );
-        diff.location.norm2().sqrt() < 1.0 * si::M
-            && diff.velocity.norm2().sqrt() < 1.0 * si::MPS
-            && diff.net_force.norm2().sqrt() < 1.0 * si::N
-            && diff.mass < 1.0 * si::KG
-            && self.shape == other.shape
+        self.approx_eq(other, &Tolerances::default())
     }
 }
@@ -1,23 +1,69 @@
 use dimensioned::si;
 use itertools::Itertools;
 use log;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use vector3d::Vector3d;
 
+use crate::actuators::actuator::Actuator;
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::lighting::light_source::LightSource;
 use crate::physics::entity::Entity;
-use crate::physics::interaction::interaction::Interaction;
+use crate::physics::interaction::helpers::are_touching;
+use crate::physics::interaction::interaction::{Interaction, Propagation, PropagationLimits};
+use crate::physics::observer::{Observers, StepObserver};
+use crate::physics::position_correction::{Correction, PositionCorrection};
+use crate::physics::state::shape::Shape;
+use crate::physics::state::state::State;
+use crate::physics::state::state_influence::StateInfluence;
+use crate::physics::tolerance::Tolerances;
+use crate::physics::validation::{
+    check_influences, check_states, Validation, ValidationMode, Violation,
+};
+use crate::recording::sampling::{Sampler, Sampling};
+use crate::sensors::error_model::{apply_error_models, ErrorModel};
+use crate::sensors::sensor::{Measurement, Sensor};
+use crate::space::raycast::{cast_ray, cast_segment, Hit, Ray, RayFilter};
 use crate::utils::identity::Identity;
 
-pub const PRECISION: f64 = 10e10;
-
 #[derive(Clone)]
 pub struct System {
     entities: Vec<Entity>,
-    interactions: Vec<&'static dyn Interaction>,
+    interactions: Vec<Box<dyn Interaction>>,
     current_time: si::Second<f64>,
+    last_influences: Vec<StateInfluence>,
+    // Influences from outside of the system, applied during the next step
+    external_influences: Vec<StateInfluence>,
+    actuators: Vec<Box<dyn Actuator>>,
+    // Time, actuator and input signal, ordered by time
+    scheduled_signals: Vec<(si::Second<f64>, &'static str, f64)>,
+    light_sources: Vec<LightSource>,
+    sensors: Vec<Box<dyn Sensor>>,
+    // Sampler and error models of every sensor, in the order of the sensors
+    samplers: Vec<Sampler>,
+    error_models: Vec<Vec<ErrorModel>>,
+    // Source of randomness of the error models, reproducible from the seed
+    seed: u64,
+    rng: ChaCha8Rng,
+    // Measurements taken at the end of the most recent step
+    last_measurements: Vec<Measurement>,
+    // Most recent measurement of every sensor, in the order of the sensors
+    latest_measurements: Vec<Option<Measurement>>,
+    observers: Observers,
+    validation: Validation,
+    // Violations of invariants found during the most recent step
+    violations: Vec<Violation>,
+    position_correction: Option<PositionCorrection>,
+    // Overlaps removed during the most recent step
+    corrections: Vec<Correction>,
+    propagation_limits: PropagationLimits,
+    // Interactions whose propagation hit the limits during the most recent step
+    exhausted_interactions: Vec<&'static str>,
+    tolerances: Tolerances,
 }
 
 impl System {
-    fn check_identities<I: Identity>(vec: &Vec<I>) -> bool {
+    fn check_identities<I: Identity>(vec: &[I]) -> bool {
         for idx0 in 0..vec.len() {
             for idx1 in idx0 + 1..vec.len() {
                 if idx0 != idx1 && vec[idx0].get_identity() == vec[idx1].get_identity() {
@@ -30,7 +76,7 @@ impl System {
 
     pub fn new(
         entities: Vec<Entity>,
-        interactions: Vec<&'static dyn Interaction>,
+        mut interactions: Vec<Box<dyn Interaction>>,
         current_time: si::Second<f64>,
     ) -> System {
         if !System::check_identities(&entities) {
@@ -39,21 +85,399 @@ impl System {
         if !System::check_identities(&interactions) {
             panic!("Identities for interactions are not unique.")
         }
+        for interaction in &mut interactions {
+            interaction.set_tolerances(Tolerances::default());
+        }
         System {
             entities,
             interactions,
             current_time,
+            last_influences: Vec::new(),
+            external_influences: Vec::new(),
+            actuators: Vec::new(),
+            scheduled_signals: Vec::new(),
+            light_sources: Vec::new(),
+            sensors: Vec::new(),
+            samplers: Vec::new(),
+            error_models: Vec::new(),
+            seed: 0,
+            rng: ChaCha8Rng::seed_from_u64(0),
+            last_measurements: Vec::new(),
+            latest_measurements: Vec::new(),
+            observers: Observers::default(),
+            validation: Validation::default(),
+            violations: Vec::new(),
+            position_correction: None,
+            corrections: Vec::new(),
+            propagation_limits: PropagationLimits::default(),
+            exhausted_interactions: Vec::new(),
+            tolerances: Tolerances::default(),
+        }
+    }
+
+    // Restarts the random number generator
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn add_light_source(&mut self, light_source: LightSource) {
+        if self
+            .light_sources
+            .iter()
+            .any(|l| l.get_identifier() == light_source.get_identifier())
+        {
+            panic!("Identities for light sources are not unique.")
+        }
+        self.light_sources.push(light_source);
+    }
+
+    pub fn get_light_sources(&self) -> &Vec<LightSource> {
+        &self.light_sources
+    }
+
+    pub fn set_light_level(&mut self, light_source_id: &str, level: f64) {
+        match self
+            .light_sources
+            .iter_mut()
+            .find(|l| l.get_identifier() == light_source_id)
+        {
+            Some(light_source) => light_source.set_level(level),
+            None => panic!("Light source {} not found.", light_source_id),
+        }
+    }
+
+    pub fn get_illuminance_field(&self) -> IlluminanceField<'_> {
+        IlluminanceField::new(&self.light_sources, &self.entities)
+    }
+
+    pub fn add_sensor(&mut self, sensor: Box<dyn Sensor>) {
+        if self
+            .sensors
+            .iter()
+            .any(|s| s.get_identifier() == sensor.get_identifier())
+        {
+            panic!("Identities for sensors are not unique.")
+        }
+        let interval = 1.0 / sensor.get_sampling_rate() * si::S;
+        // Sensors check that their sampling rates are positive and finite
+        self.samplers.push(
+            Sampler::new(Sampling::Interval(interval))
+                .expect("Sampling rate of sensor has to be positive and finite."),
+        );
+        self.error_models.push(Vec::new());
+        self.latest_measurements.push(None);
+        self.sensors.push(sensor);
+    }
+
+    // Replaces the error models of the sensor
+    pub fn set_error_models(&mut self, sensor_id: &str, error_models: Vec<ErrorModel>) {
+        match self
+            .sensors
+            .iter()
+            .position(|s| s.get_identifier() == sensor_id)
+        {
+            Some(idx) => self.error_models[idx] = error_models,
+            None => panic!("Sensor {} not found.", sensor_id),
+        }
+    }
+
+    pub fn get_error_models(&self, sensor_id: &str) -> &Vec<ErrorModel> {
+        match self
+            .sensors
+            .iter()
+            .position(|s| s.get_identifier() == sensor_id)
+        {
+            Some(idx) => &self.error_models[idx],
+            None => panic!("Sensor {} not found.", sensor_id),
+        }
+    }
+
+    pub fn get_sensors(&self) -> &Vec<Box<dyn Sensor>> {
+        &self.sensors
+    }
+
+    pub fn get_last_measurements(&self) -> &Vec<Measurement> {
+        &self.last_measurements
+    }
+
+    pub fn get_latest_measurement(&self, sensor_id: &str) -> Option<&Measurement> {
+        match self
+            .sensors
+            .iter()
+            .position(|s| s.get_identifier() == sensor_id)
+        {
+            Some(idx) => self.latest_measurements[idx].as_ref(),
+            None => panic!("Sensor {} not found.", sensor_id),
+        }
+    }
+
+    pub fn add_actuator(&mut self, actuator: Box<dyn Actuator>) {
+        if self
+            .actuators
+            .iter()
+            .any(|a| a.get_identifier() == actuator.get_identifier())
+        {
+            panic!("Identities for actuators are not unique.")
+        }
+        self.actuators.push(actuator);
+    }
+
+    pub fn get_actuators(&self) -> &Vec<Box<dyn Actuator>> {
+        &self.actuators
+    }
+
+    fn get_actuator_mut(&mut self, id: &str) -> &mut Box<dyn Actuator> {
+        match self.actuators.iter_mut().find(|a| a.get_identifier() == id) {
+            Some(actuator) => actuator,
+            None => panic!("Actuator {} not found.", id),
+        }
+    }
+
+    pub fn set_input_signal(&mut self, actuator_id: &str, signal: f64) {
+        self.get_actuator_mut(actuator_id).set_input_signal(signal);
+    }
+
+    pub fn release_actuator(&mut self, actuator_id: &str) {
+        self.get_actuator_mut(actuator_id).release();
+    }
+
+    // The signal is set by the first step that starts at or after the time
+    pub fn schedule_input_signal(
+        &mut self,
+        actuator_id: &'static str,
+        time: si::Second<f64>,
+        signal: f64,
+    ) {
+        self.get_actuator_mut(actuator_id);
+        let idx = self
+            .scheduled_signals
+            .iter()
+            .position(|(t, _, _)| *t > time)
+            .unwrap_or(self.scheduled_signals.len());
+        self.scheduled_signals
+            .insert(idx, (time, actuator_id, signal));
+    }
+
+    // Observers are not carried over to clones of the system
+    pub fn add_observer(&mut self, observer: Box<dyn StepObserver>) {
+        self.observers.push(observer);
+    }
+
+    fn notify<F: FnMut(&mut dyn StepObserver, &System)>(&mut self, mut notify: F) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut observers = std::mem::take(&mut self.observers);
+        for observer in observers.iter_mut() {
+            notify(observer.as_mut(), self);
+        }
+        self.observers = observers;
+    }
+
+    // Checks invariants after the phases of every step, off by default
+    pub fn set_validation(&mut self, validation: Validation) {
+        self.validation = validation;
+    }
+
+    pub fn get_validation(&self) -> Validation {
+        self.validation
+    }
+
+    pub fn get_violations(&self) -> &Vec<Violation> {
+        &self.violations
+    }
+
+    // Separates overlapping shapes after every step, off by default
+    pub fn set_position_correction(&mut self, position_correction: Option<PositionCorrection>) {
+        self.position_correction = position_correction;
+    }
+
+    pub fn get_position_correction(&self) -> Option<PositionCorrection> {
+        self.position_correction
+    }
+
+    pub fn get_corrections(&self) -> &Vec<Correction> {
+        &self.corrections
+    }
+
+    // Tolerances of the interactions of the system, the default ones suit scenes at the scale of
+    // metres, kilograms and newtons. The system is their only owner, every interaction works with
+    // the tolerances of the system it is part of.
+    pub fn set_tolerances(&mut self, tolerances: Tolerances) {
+        self.tolerances = tolerances;
+        for interaction in &mut self.interactions {
+            interaction.set_tolerances(tolerances);
         }
     }
 
+    pub fn get_tolerances(&self) -> Tolerances {
+        self.tolerances
+    }
+
+    // Equality of states up to the tolerances of the system, unlike == which uses the default ones
+    pub fn are_close(&self, state0: &State, state1: &State) -> bool {
+        state0.approx_eq(state1, &self.tolerances)
+    }
+
+    // Budget of every propagation of influences through the entities
+    pub fn set_propagation_limits(&mut self, propagation_limits: PropagationLimits) {
+        self.propagation_limits = propagation_limits;
+    }
+
+    pub fn get_propagation_limits(&self) -> PropagationLimits {
+        self.propagation_limits
+    }
+
+    pub fn get_exhausted_interactions(&self) -> &Vec<&'static str> {
+        &self.exhausted_interactions
+    }
+
+    fn record_propagation(&mut self, interaction: &'static str, propagation: &Propagation) {
+        // Validation reports influences for unknown entities
+        if propagation.unknown_receivers > 0 && self.validation.mode == ValidationMode::Off {
+            panic!("Receiver of influence not found.");
+        }
+        if propagation.cycles > 0 {
+            log::debug!(
+                time = self.current_time.value_unsafe,
+                interaction = interaction,
+                cycles = propagation.cycles;
+                "Absorbed influences closing cycles"
+            );
+        }
+        if propagation.exhausted {
+            log::warn!(
+                time = self.current_time.value_unsafe,
+                interaction = interaction,
+                max_depth = self.propagation_limits.max_depth,
+                max_reactions = self.propagation_limits.max_reactions;
+                "Propagation hit its limits, remaining influences were absorbed by their receivers"
+            );
+            if !self.exhausted_interactions.contains(&interaction) {
+                self.exhausted_interactions.push(interaction);
+            }
+        }
+    }
+
+    fn report(&mut self, violations: Vec<Violation>) {
+        match self.validation.mode {
+            ValidationMode::Off => {}
+            ValidationMode::Report => {
+                for violation in violations {
+                    log::error!(
+                        time = violation.time.value_unsafe,
+                        entity = violation.entity;
+                        "{}",
+                        violation
+                    );
+                    self.violations.push(violation);
+                }
+            }
+            ValidationMode::Panic => {
+                if let Some(violation) = violations.first() {
+                    panic!("{}", violation);
+                }
+            }
+        }
+    }
+
+    pub fn add_entity(&mut self, entity: Entity) {
+        if self
+            .entities
+            .iter()
+            .any(|e| e.get_identity() == entity.get_identity())
+        {
+            panic!("Identities for entities are not unique.")
+        }
+        self.entities.push(entity);
+    }
+
+    pub fn add_interaction(&mut self, mut interaction: Box<dyn Interaction>) {
+        if self
+            .interactions
+            .iter()
+            .any(|i| i.get_identifier() == interaction.get_identifier())
+        {
+            panic!("Identities for interactions are not unique.")
+        }
+        interaction.set_tolerances(self.tolerances);
+        self.interactions.push(interaction);
+    }
+
+    // The influence is applied during the next step only. If its interaction is part of the
+    // system, it is propagated by that interaction, otherwise it only affects the receiver.
+    // Unknown receivers are reported by validation if it is enabled.
+    pub fn apply_influence(&mut self, influence: StateInfluence) {
+        if self.validation.mode == ValidationMode::Off
+            && !self
+                .entities
+                .iter()
+                .any(|e| e.get_identity() == influence.get_receiver_id())
+        {
+            panic!("Receiver of influence not found.");
+        }
+        self.external_influences.push(influence);
+    }
+
+    pub fn get_external_influences(&self) -> &Vec<StateInfluence> {
+        &self.external_influences
+    }
+
     pub fn get_current_time(&self) -> si::Second<f64> {
         self.current_time
     }
 
+    pub fn get_interactions(&self) -> &Vec<Box<dyn Interaction>> {
+        &self.interactions
+    }
+
     pub fn get_entities(&self) -> &Vec<Entity> {
         &self.entities
     }
 
+    // Influences that were applied during the most recent step
+    pub fn get_last_influences(&self) -> &Vec<StateInfluence> {
+        &self.last_influences
+    }
+
+    // Pairs of touching entities, entities without a shape never touch
+    pub fn get_contacts(&self) -> Vec<(&'static str, &'static str)> {
+        self.entities
+            .iter()
+            .tuple_combinations()
+            .filter(|(e0, e1)| {
+                e0.get_state().get_shape() != Shape::None
+                    && e1.get_state().get_shape() != Shape::None
+                    && are_touching(e0, e1)
+            })
+            .map(|(e0, e1)| (e0.get_identity(), e1.get_identity()))
+            .collect()
+    }
+
+    // Closest shape the ray hits within the maximum distance
+    pub fn cast_ray(
+        &self,
+        ray: &Ray,
+        max_distance: si::Meter<f64>,
+        filter: &RayFilter,
+    ) -> Option<Hit> {
+        cast_ray(&self.entities, ray, max_distance, filter)
+    }
+
+    pub fn cast_segment(
+        &self,
+        from: Vector3d<si::Meter<f64>>,
+        to: Vector3d<si::Meter<f64>>,
+        filter: &RayFilter,
+    ) -> Option<Hit> {
+        cast_segment(&self.entities, from, to, filter)
+    }
+
     pub fn get_momentum(&self) -> Vector3d<si::NewtonSecond<f64>> {
         self.entities.iter().fold(
             Vector3d::new(0.0 * si::NS, 0.0 * si::NS, 0.0 * si::NS),
@@ -75,52 +499,169 @@ impl System {
         potential_energy + kinetic_energy
     }
 
+    // Propagates an influence from outside of the system by its interaction
+    fn propagate(
+        &mut self,
+        influence: StateInfluence,
+        elapsed_time: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        let interaction_id = influence.get_interaction_id();
+        let propagation = match self
+            .interactions
+            .iter()
+            .find(|i| i.get_identifier() == interaction_id)
+        {
+            Some(interaction) => interaction.dfs(
+                &self.entities,
+                &mut vec![influence],
+                elapsed_time,
+                &self.propagation_limits,
+            ),
+            None => return vec![influence],
+        };
+        self.record_propagation(interaction_id, &propagation);
+        propagation.influences
+    }
+
     pub fn next_state(&mut self, elapsed_time: si::Second<f64>) {
+        self.notify(|observer, system| observer.before_step(system, elapsed_time));
+        self.violations.clear();
+        self.exhausted_interactions.clear();
+        let due = self
+            .scheduled_signals
+            .iter()
+            .take_while(|(t, _, _)| *t < self.current_time + elapsed_time / 2.0)
+            .count();
+        for (_, actuator_id, signal) in self.scheduled_signals.drain(..due).collect::<Vec<_>>() {
+            self.set_input_signal(actuator_id, signal);
+        }
         let mut influences = Vec::new();
         // TODO: Look at Entities and possible neighbors, choose which interactions take place
-        for interaction in &self.interactions {
-            influences.extend(interaction.get_influences(&mut self.entities, elapsed_time));
+        let propagations: Vec<(&'static str, Propagation)> = self
+            .interactions
+            .iter()
+            .map(|interaction| {
+                let propagation = interaction.get_propagation(
+                    &self.entities,
+                    elapsed_time,
+                    &self.propagation_limits,
+                );
+                (interaction.get_identifier(), propagation)
+            })
+            .collect();
+        for (interaction, propagation) in propagations {
+            self.record_propagation(interaction, &propagation);
+            influences.extend(propagation.influences);
         }
-        // Graph = (V, E), V = Entities, E = Influences
-        if !influences.is_empty() {
-            println!(
-                "TIME {:.03}, Step size={}\n",
-                self.current_time, elapsed_time
-            );
-            for entity in &self.entities {
-                println!("{}", entity);
-            }
+        let mut external_influences = std::mem::take(&mut self.external_influences);
+        for actuator in &self.actuators {
+            external_influences.extend(actuator.actuate(&self.entities, elapsed_time));
+        }
+        for influence in external_influences {
+            influences.extend(self.propagate(influence, elapsed_time));
         }
+        log::debug!(
+            time = self.current_time.value_unsafe,
+            step_size = elapsed_time.value_unsafe,
+            influences = influences.len();
+            "Step"
+        );
+        self.notify(|observer, system| observer.influences_computed(system, &influences));
+        if self.validation.mode != ValidationMode::Off {
+            self.report(check_influences(
+                &self.entities,
+                &influences,
+                self.current_time,
+            ));
+        }
+        // Graph = (V, E), V = Entities, E = Influences
+        let time = self.current_time.value_unsafe;
         for entity in &mut self.entities {
             for influence in &influences {
                 if entity.get_identity() == influence.get_receiver_id() {
-                    println!("{}", influence);
-                    entity.add_influence(&influence);
+                    log::trace!(
+                        time = time,
+                        interaction = influence.get_interaction_id(),
+                        transmitter = influence.get_transmitter_id(),
+                        receiver = influence.get_receiver_id();
+                        "Influence {{\n{}}}",
+                        influence
+                    );
+                    entity.add_influence(influence);
                 }
             }
         }
         for entity in &mut self.entities {
             entity.evolve(elapsed_time);
         }
+        self.corrections = match &self.position_correction {
+            Some(position_correction) => position_correction.correct(&mut self.entities),
+            None => Vec::new(),
+        };
+        for correction in &self.corrections {
+            log::debug!(
+                time = self.current_time.value_unsafe,
+                entity0 = correction.entities.0,
+                entity1 = correction.entities.1,
+                depth = correction.depth.value_unsafe;
+                "Separated overlapping shapes"
+            );
+        }
+        if self.validation.mode != ValidationMode::Off {
+            self.report(check_states(
+                &self.entities,
+                &influences,
+                self.current_time,
+                self.validation.penetration_tolerance,
+            ));
+        }
+        // Sensors see the forces of the step, so they sample before the influences are removed
+        let time = self.current_time + elapsed_time;
+        let mut measurements = Vec::new();
+        let light = IlluminanceField::new(&self.light_sources, &self.entities);
+        for (idx, ((sensor, sampler), error_models)) in self
+            .sensors
+            .iter()
+            .zip(self.samplers.iter_mut())
+            .zip(self.error_models.iter())
+            .enumerate()
+        {
+            if !sampler.is_due(time) {
+                continue;
+            }
+            if let Some(reading) = sensor.measure(&self.entities, &light) {
+                if let Some(reading) =
+                    apply_error_models(error_models, reading, time, &mut self.rng)
+                {
+                    let measurement = Measurement {
+                        sensor_id: sensor.get_identifier(),
+                        time,
+                        reading,
+                    };
+                    self.latest_measurements[idx] = Some(measurement.clone());
+                    measurements.push(measurement);
+                }
+            }
+        }
+        self.last_measurements = measurements;
         for entity in &mut self.entities {
             entity.remove_influences();
         }
-        self.current_time = self.current_time + elapsed_time;
-        log::trace!("SYSTEM STATE at {}:\n{}", self.current_time, self);
+        self.current_time += elapsed_time;
+        self.last_influences = influences;
+        log::trace!(time = self.current_time.value_unsafe; "System state\n{}", self);
+        self.notify(|observer, system| observer.after_step(system));
     }
 }
 
 impl std::fmt::Display for System {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}\n", "=".repeat(80))?;
-        write!(f, "SYSTEM MOMENTUM: {}\n", self.get_momentum())?;
-        write!(f, "SYSTEM ENERGY: {}\n", self.get_energy())?;
-        write!(f, "{}\n", "=".repeat(80))?;
+        writeln!(f, "{}", "=".repeat(80))?;
+        writeln!(f, "SYSTEM MOMENTUM: {}", self.get_momentum())?;
+        writeln!(f, "SYSTEM ENERGY: {}", self.get_energy())?;
+        writeln!(f, "{}", "=".repeat(80))?;
         for entity in &self.entities {
-            match write!(f, "{}", entity) {
-                Err(e) => return Err(e),
-                Ok(_) => (),
-            }
+            write!(f, "{}", entity)?;
         }
         Ok(())
     }
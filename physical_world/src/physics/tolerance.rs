@@ -0,0 +1,60 @@
+use dimensioned::si;
+
+// Differences a quantity may have and still count as none. A magnitude is negligible if it is
+// at most the absolute tolerance, in SI units of the quantity, or at most the relative
+// tolerance times the magnitude it is compared to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    pub fn new(absolute: f64, relative: f64) -> Tolerance {
+        Tolerance { absolute, relative }
+    }
+
+    pub fn is_negligible<U>(&self, magnitude: si::SI<f64, U>, reference: si::SI<f64, U>) -> bool {
+        let magnitude = magnitude.value_unsafe.abs();
+        magnitude <= self.absolute || magnitude <= self.relative * reference.value_unsafe.abs()
+    }
+
+    pub fn are_close<U>(&self, value0: si::SI<f64, U>, value1: si::SI<f64, U>) -> bool {
+        let reference = value0.value_unsafe.abs().max(value1.value_unsafe.abs());
+        let difference = (value0.value_unsafe - value1.value_unsafe).abs();
+        difference <= self.absolute || difference <= self.relative * reference
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance {
+            absolute: 1e-11,
+            relative: 0.0,
+        }
+    }
+}
+
+// Tolerances of a system per quantity, they decide which forces interactions pass on and when
+// states are equal. Scenes far from the metre and kilogram scale need their own, together with
+// the overlaps validation and position correction allow.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Tolerances {
+    pub length: Tolerance,
+    pub velocity: Tolerance,
+    pub force: Tolerance,
+    pub mass: Tolerance,
+}
+
+impl Tolerances {
+    // The same relative tolerance for every quantity, absolute ones only catch exact zeros
+    pub fn relative(relative: f64) -> Tolerances {
+        let tolerance = Tolerance::new(0.0, relative);
+        Tolerances {
+            length: tolerance,
+            velocity: tolerance,
+            force: tolerance,
+            mass: tolerance,
+        }
+    }
+}
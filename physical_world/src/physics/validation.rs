@@ -0,0 +1,192 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::get_penetration_depth;
+use crate::physics::state::state::State;
+use crate::physics::state::state_influence::StateInfluence;
+use crate::utils::identity::Identity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    #[default]
+    Off,
+    // Violations are logged as errors and kept until the next step
+    Report,
+    // The first violation panics
+    Panic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Validation {
+    pub mode: ValidationMode,
+    // Overlap of two shapes that still counts as touching. Unlike the length tolerance of the
+    // system, which absorbs rounding errors, it allows for the overlap contacts build up during a
+    // step, so it is set on its own.
+    pub penetration_tolerance: si::Meter<f64>,
+}
+
+impl Default for Validation {
+    fn default() -> Validation {
+        Validation {
+            mode: ValidationMode::Off,
+            penetration_tolerance: 1e-6 * si::M,
+        }
+    }
+}
+
+impl Validation {
+    pub fn new(mode: ValidationMode) -> Validation {
+        Validation {
+            mode,
+            ..Validation::default()
+        }
+    }
+}
+
+// Phase of a step after which an invariant is checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Influences,
+    Evolution,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Invariant {
+    // Influence for an entity that is not part of the system
+    UnknownReceiver,
+    NonFiniteInfluence,
+    NonFiniteState,
+    NonPositiveMass(si::Kilogram<f64>),
+    Interpenetration {
+        other: &'static str,
+        depth: si::Meter<f64>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    // Start of the step
+    pub time: si::Second<f64>,
+    pub phase: Phase,
+    pub entity: &'static str,
+    // Interactions of the influences the entity received during the step
+    pub interactions: Vec<&'static str>,
+    pub invariant: Invariant,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "At {:.6} s after {:?}, entity {}",
+            self.time.value_unsafe, self.phase, self.entity
+        )?;
+        if !self.interactions.is_empty() {
+            write!(f, " (influenced by {})", self.interactions.join(", "))?;
+        }
+        match self.invariant {
+            Invariant::UnknownReceiver => write!(f, " receives an influence but does not exist."),
+            Invariant::NonFiniteInfluence => write!(f, " receives a non-finite influence."),
+            Invariant::NonFiniteState => write!(f, " has a non-finite state."),
+            Invariant::NonPositiveMass(mass) => write!(f, " has a mass of {}.", mass),
+            Invariant::Interpenetration { other, depth } => {
+                write!(f, " overlaps {} by {}.", other, depth)
+            }
+        }
+    }
+}
+
+fn is_finite<U>(v: Vector3d<si::SI<f64, U>>) -> bool {
+    v.x.value_unsafe.is_finite() && v.y.value_unsafe.is_finite() && v.z.value_unsafe.is_finite()
+}
+
+fn is_finite_state(state: &State) -> bool {
+    is_finite(state.get_location())
+        && is_finite(state.get_velocity())
+        && is_finite(state.get_net_force())
+        && state.get_mass().value_unsafe.is_finite()
+}
+
+fn get_interactions(entity: &'static str, influences: &[StateInfluence]) -> Vec<&'static str> {
+    let mut interactions: Vec<&'static str> = Vec::new();
+    for influence in influences.iter().filter(|i| i.get_receiver_id() == entity) {
+        if !interactions.contains(&influence.get_interaction_id()) {
+            interactions.push(influence.get_interaction_id());
+        }
+    }
+    interactions
+}
+
+// Influences have to reach an entity of the system and must not carry non-finite changes
+pub fn check_influences(
+    entities: &[Entity],
+    influences: &[StateInfluence],
+    time: si::Second<f64>,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for influence in influences {
+        let receiver = influence.get_receiver_id();
+        let invariant = if !entities.iter().any(|e| e.get_identity() == receiver) {
+            Invariant::UnknownReceiver
+        } else if !is_finite_state(&influence.get_state_change()) {
+            Invariant::NonFiniteInfluence
+        } else {
+            continue;
+        };
+        violations.push(Violation {
+            time,
+            phase: Phase::Influences,
+            entity: receiver,
+            interactions: vec![influence.get_interaction_id()],
+            invariant,
+        });
+    }
+    violations
+}
+
+// States have to be finite with a positive mass, shapes must not overlap beyond the tolerance
+pub fn check_states(
+    entities: &[Entity],
+    influences: &[StateInfluence],
+    time: si::Second<f64>,
+    penetration_tolerance: si::Meter<f64>,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut violate = |entity: &'static str, invariant| {
+        violations.push(Violation {
+            time,
+            phase: Phase::Evolution,
+            entity,
+            interactions: get_interactions(entity, influences),
+            invariant,
+        })
+    };
+    for entity in entities {
+        let state = entity.get_state();
+        if !is_finite_state(state) {
+            violate(entity.get_identity(), Invariant::NonFiniteState);
+        } else if state.get_mass() <= 0.0 * si::KG {
+            violate(
+                entity.get_identity(),
+                Invariant::NonPositiveMass(state.get_mass()),
+            );
+        }
+    }
+    for (idx, entity) in entities.iter().enumerate() {
+        for other in &entities[idx + 1..] {
+            if let Some(depth) = get_penetration_depth(entity, other) {
+                if depth > penetration_tolerance {
+                    violate(
+                        entity.get_identity(),
+                        Invariant::Interpenetration {
+                            other: other.get_identity(),
+                            depth,
+                        },
+                    );
+                }
+            }
+        }
+    }
+    violations
+}
@@ -0,0 +1,316 @@
+use std::path::{Path, PathBuf};
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::physics::entity::Entity;
+use crate::physics::state::shape::Shape;
+use crate::physics::system::System;
+use crate::plotting::chart::PlotError;
+use crate::plotting::series::Projection;
+use crate::recording::columns::Quantity;
+use crate::utils::identity::Identity;
+
+// Draws the scene as seen on the viewing plane of the projection. Frames are taken at a fixed
+// rate of simulated time, independent of the step size of the simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneRenderer {
+    pub projection: Projection,
+    pub size: (u32, u32),
+    // Frames per second of simulated time, checked when rendering
+    frame_rate: f64,
+    // Length of velocity arrows in seconds of travel, None hides them
+    pub velocity_scale: Option<f64>,
+    // Length of influence arrows in metres per newton, None hides them
+    pub influence_scale: Option<f64>,
+}
+
+struct Viewport {
+    min: (f64, f64),
+    scale: f64,
+    offset: (f64, f64),
+    height: f64,
+}
+
+impl Viewport {
+    fn new(bounds: ((f64, f64), (f64, f64)), size: (u32, u32)) -> Viewport {
+        let ((min_x, max_x), (min_y, max_y)) = bounds;
+        let (width, height) = (size.0 as f64, size.1 as f64);
+        // Same scale on both axes, so spheres stay round
+        let scale = (width / (max_x - min_x)).min(height / (max_y - min_y));
+        Viewport {
+            min: (min_x, min_y),
+            scale,
+            offset: (
+                (width - (max_x - min_x) * scale) / 2.0,
+                (height - (max_y - min_y) * scale) / 2.0,
+            ),
+            height,
+        }
+    }
+
+    fn to_pixel(&self, point: (f64, f64)) -> (i32, i32) {
+        let x = self.offset.0 + (point.0 - self.min.0) * self.scale;
+        let y = self.height - (self.offset.1 + (point.1 - self.min.1) * self.scale);
+        (x.round() as i32, y.round() as i32)
+    }
+
+    fn to_pixel_length(&self, length: f64) -> i32 {
+        (length * self.scale).round() as i32
+    }
+}
+
+fn get_extents(projection: Projection, entity: &Entity) -> (f64, f64) {
+    match entity.get_state().get_shape() {
+        Shape::Sphere(s) => (s.radius.value_unsafe, s.radius.value_unsafe),
+        Shape::Cuboid(c) => projection.project(&[
+            c.half_extents.x.value_unsafe,
+            c.half_extents.y.value_unsafe,
+            c.half_extents.z.value_unsafe,
+        ]),
+        Shape::None => (0.0, 0.0),
+    }
+}
+
+fn draw_arrow<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    from: (i32, i32),
+    to: (i32, i32),
+    color: RGBColor,
+) -> Result<(), PlotError>
+where
+    DB::ErrorType: 'static,
+{
+    let (dx, dy) = ((to.0 - from.0) as f64, (to.1 - from.1) as f64);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < 1.0 {
+        return Ok(());
+    }
+    let style = color.stroke_width(2);
+    root.draw(&PathElement::new(vec![from, to], style))?;
+    let head = 8.0_f64.min(length / 2.0);
+    let angle = dy.atan2(dx);
+    for side in [-0.4, 0.4] {
+        let tip = (
+            to.0 - (head * (angle + side).cos()).round() as i32,
+            to.1 - (head * (angle + side).sin()).round() as i32,
+        );
+        root.draw(&PathElement::new(vec![to, tip], style))?;
+    }
+    Ok(())
+}
+
+impl SceneRenderer {
+    pub fn new(projection: Projection, frame_rate: f64) -> SceneRenderer {
+        SceneRenderer {
+            projection,
+            size: (640, 480),
+            frame_rate,
+            velocity_scale: Some(1.0),
+            influence_scale: None,
+        }
+    }
+
+    pub fn get_frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    pub fn set_frame_rate(&mut self, frame_rate: f64) {
+        self.frame_rate = frame_rate;
+    }
+
+    // Latest state at or before every frame time
+    pub fn select_frames<'a>(&self, history: &[&'a System]) -> Result<Vec<&'a System>, PlotError> {
+        if !self.frame_rate.is_finite() || self.frame_rate <= 0.0 {
+            return Err(PlotError::InvalidFrameRate(self.frame_rate));
+        }
+        let (first, last) = match (history.first(), history.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Ok(Vec::new()),
+        };
+        let start = first.get_current_time().value_unsafe;
+        let duration = last.get_current_time().value_unsafe - start;
+        let frame_count = (duration * self.frame_rate + 1e-9).floor() as usize + 1;
+        let mut frames = Vec::with_capacity(frame_count);
+        let mut idx = 0;
+        for frame in 0..frame_count {
+            let time = start + frame as f64 / self.frame_rate + 1e-9;
+            while idx + 1 < history.len()
+                && history[idx + 1].get_current_time().value_unsafe <= time
+            {
+                idx += 1;
+            }
+            frames.push(history[idx]);
+        }
+        Ok(frames)
+    }
+
+    fn get_bounds(&self, frames: &[&System]) -> Option<((f64, f64), (f64, f64))> {
+        let mut bounds: Option<((f64, f64), (f64, f64))> = None;
+        for entity in frames.iter().flat_map(|s| s.get_entities()) {
+            let (x, y) = self
+                .projection
+                .project(&Quantity::Location.get_values(entity));
+            let (ex, ey) = get_extents(self.projection, entity);
+            let ((min_x, max_x), (min_y, max_y)) =
+                bounds.unwrap_or(((x - ex, x + ex), (y - ey, y + ey)));
+            bounds = Some((
+                (min_x.min(x - ex), max_x.max(x + ex)),
+                (min_y.min(y - ey), max_y.max(y + ey)),
+            ));
+        }
+        bounds.map(|((min_x, max_x), (min_y, max_y))| {
+            let pad_x = ((max_x - min_x) * 0.1).max(1.0);
+            let pad_y = ((max_y - min_y) * 0.1).max(1.0);
+            (
+                (min_x - pad_x, max_x + pad_x),
+                (min_y - pad_y, max_y + pad_y),
+            )
+        })
+    }
+
+    fn draw_frame<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        viewport: &Viewport,
+        identities: &[&'static str],
+        system: &System,
+    ) -> Result<(), PlotError>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+        root.draw(&Text::new(
+            format!("t = {:.3} s", system.get_current_time().value_unsafe),
+            (10, 10),
+            ("sans-serif", 18).into_font(),
+        ))?;
+        for entity in system.get_entities() {
+            let idx = identities
+                .iter()
+                .position(|id| *id == entity.get_identity())
+                .unwrap_or(0);
+            let color = Palette99::pick(idx).to_rgba();
+            let location = self
+                .projection
+                .project(&Quantity::Location.get_values(entity));
+            let center = viewport.to_pixel(location);
+            match entity.get_state().get_shape() {
+                Shape::Sphere(s) => {
+                    let radius = viewport.to_pixel_length(s.radius.value_unsafe).max(1);
+                    root.draw(&Circle::new(center, radius, color.mix(0.4).filled()))?;
+                    root.draw(&Circle::new(center, radius, color.stroke_width(2)))?;
+                }
+                Shape::Cuboid(_) => {
+                    let (ex, ey) = get_extents(self.projection, entity);
+                    let corners = [
+                        viewport.to_pixel((location.0 - ex, location.1 + ey)),
+                        viewport.to_pixel((location.0 + ex, location.1 - ey)),
+                    ];
+                    root.draw(&Rectangle::new(corners, color.mix(0.4).filled()))?;
+                    root.draw(&Rectangle::new(corners, color.stroke_width(2)))?;
+                }
+                Shape::None => {
+                    root.draw(&Circle::new(center, 3, color.filled()))?;
+                }
+            }
+            if let Some(scale) = self.velocity_scale {
+                let velocity = self
+                    .projection
+                    .project(&Quantity::Velocity.get_values(entity));
+                let tip = (
+                    location.0 + velocity.0 * scale,
+                    location.1 + velocity.1 * scale,
+                );
+                draw_arrow(root, center, viewport.to_pixel(tip), BLUE)?;
+            }
+            if let Some(scale) = self.influence_scale {
+                for influence in system
+                    .get_last_influences()
+                    .iter()
+                    .filter(|i| i.get_receiver_id() == entity.get_identity())
+                {
+                    let force = influence.get_state_change().get_net_force();
+                    let force = self.projection.project(&[
+                        force.x.value_unsafe,
+                        force.y.value_unsafe,
+                        force.z.value_unsafe,
+                    ]);
+                    let tip = (location.0 + force.0 * scale, location.1 + force.1 * scale);
+                    draw_arrow(root, center, viewport.to_pixel(tip), RED)?;
+                }
+            }
+            root.draw(&Text::new(
+                entity.get_identity(),
+                center,
+                ("sans-serif", 14).into_font(),
+            ))?;
+        }
+        root.present()?;
+        Ok(())
+    }
+
+    fn prepare<'a, H: IntoIterator<Item = &'a System>>(
+        &self,
+        history: H,
+    ) -> Result<(Vec<&'a System>, Viewport, Vec<&'static str>), PlotError> {
+        let history = history.into_iter().collect::<Vec<&System>>();
+        let frames = self.select_frames(&history)?;
+        let bounds = match self.get_bounds(&frames) {
+            Some(bounds) => bounds,
+            None => return Err(PlotError::NoData),
+        };
+        let mut identities = Vec::new();
+        for entity in frames.iter().flat_map(|s| s.get_entities()) {
+            if !identities.contains(&entity.get_identity()) {
+                identities.push(entity.get_identity());
+            }
+        }
+        Ok((frames, Viewport::new(bounds, self.size), identities))
+    }
+
+    // Returns the number of frames of the animation
+    pub fn render_gif<'a, H, P>(&self, history: H, path: P) -> Result<usize, PlotError>
+    where
+        H: IntoIterator<Item = &'a System>,
+        P: AsRef<Path>,
+    {
+        let (frames, viewport, identities) = self.prepare(history)?;
+        let delay = ((1000.0 / self.frame_rate).round() as u32).max(1);
+        let backend = match BitMapBackend::gif(path, self.size, delay) {
+            Ok(backend) => backend,
+            Err(e) => return Err(PlotError::Drawing(e.to_string())),
+        };
+        let root = backend.into_drawing_area();
+        for system in &frames {
+            self.draw_frame(&root, &viewport, &identities, system)?;
+        }
+        Ok(frames.len())
+    }
+
+    // Writes frame_00000.svg, frame_00001.svg, ... into the directory
+    pub fn render_svg_frames<'a, H, P>(
+        &self,
+        history: H,
+        directory: P,
+    ) -> Result<Vec<PathBuf>, PlotError>
+    where
+        H: IntoIterator<Item = &'a System>,
+        P: AsRef<Path>,
+    {
+        let (frames, viewport, identities) = self.prepare(history)?;
+        if let Err(e) = std::fs::create_dir_all(directory.as_ref()) {
+            return Err(PlotError::Drawing(e.to_string()));
+        }
+        let mut paths = Vec::new();
+        for (idx, system) in frames.iter().enumerate() {
+            let path = directory.as_ref().join(format!("frame_{:05}.svg", idx));
+            {
+                let root = SVGBackend::new(&path, self.size).into_drawing_area();
+                self.draw_frame(&root, &viewport, &identities, system)?;
+            }
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::plotting::series::DataSeries;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<ImageFormat> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PlotError {
+    NoData,
+    UnknownFormat(String),
+    InvalidFrameRate(f64),
+    Drawing(String),
+}
+
+impl std::fmt::Display for PlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PlotError::NoData => write!(f, "There is no data to plot."),
+            PlotError::UnknownFormat(path) => {
+                write!(f, "Cannot derive an image format from {}.", path)
+            }
+            PlotError::InvalidFrameRate(frame_rate) => {
+                write!(f, "Frame rate {} is not positive and finite.", frame_rate)
+            }
+            PlotError::Drawing(e) => write!(f, "Error while drawing: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PlotError {}
+
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for PlotError {
+    fn from(e: DrawingAreaErrorKind<E>) -> Self {
+        PlotError::Drawing(e.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub size: (u32, u32),
+    // Derived from the file extension if not set
+    pub format: Option<ImageFormat>,
+}
+
+impl Chart {
+    pub fn new(title: &str, x_label: &str, y_label: &str) -> Chart {
+        Chart {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            size: (640, 480),
+            format: None,
+        }
+    }
+
+    pub fn plot<P: AsRef<Path>>(&self, series: &[DataSeries], path: P) -> Result<(), PlotError> {
+        let path = path.as_ref();
+        let format = match self.format.or_else(|| ImageFormat::from_path(path)) {
+            Some(format) => format,
+            None => return Err(PlotError::UnknownFormat(path.display().to_string())),
+        };
+        // Backends create the file, even if nothing is drawn
+        let ranges = match get_ranges(series) {
+            Some(ranges) => ranges,
+            None => return Err(PlotError::NoData),
+        };
+        match format {
+            ImageFormat::Png => self.draw(
+                BitMapBackend::new(path, self.size).into_drawing_area(),
+                series,
+                ranges,
+            ),
+            ImageFormat::Svg => self.draw(
+                SVGBackend::new(path, self.size).into_drawing_area(),
+                series,
+                ranges,
+            ),
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        series: &[DataSeries],
+        (x_range, y_range): (std::ops::Range<f64>, std::ops::Range<f64>),
+    ) -> Result<(), PlotError>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.title, ("sans-serif", 30).into_font())
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(x_range, y_range)?;
+        chart
+            .configure_mesh()
+            .x_desc(&self.x_label)
+            .y_desc(&self.y_label)
+            .draw()?;
+        for (i, s) in series.iter().enumerate() {
+            let color = Palette99::pick(i).to_rgba();
+            chart
+                .draw_series(LineSeries::new(s.data.clone(), color))?
+                .label(s.name.clone())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        root.present()?;
+        Ok(())
+    }
+}
+
+// Flat data still gets a non-empty range
+fn padded(min: f64, max: f64) -> std::ops::Range<f64> {
+    if max > min {
+        min..max
+    } else {
+        let padding = if min == 0.0 { 1.0 } else { min.abs() * 0.05 };
+        (min - padding)..(max + padding)
+    }
+}
+
+fn get_ranges(series: &[DataSeries]) -> Option<(std::ops::Range<f64>, std::ops::Range<f64>)> {
+    let mut points = series
+        .iter()
+        .flat_map(|s| s.data.iter())
+        .filter(|(x, y)| x.is_finite() && y.is_finite());
+    let (x0, y0) = points.next()?;
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (*x0, *x0, *y0, *y0);
+    for (x, y) in points {
+        min_x = min_x.min(*x);
+        max_x = max_x.max(*x);
+        min_y = min_y.min(*y);
+        max_y = max_y.max(*y);
+    }
+    Some((padded(min_x, max_x), padded(min_y, max_y)))
+}
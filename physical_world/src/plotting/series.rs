@@ -0,0 +1,201 @@
+use dimensioned::Sqrt;
+
+use crate::physics::system::System;
+use crate::recording::columns::Quantity;
+use crate::utils::identity::Identity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataSeries {
+    pub name: String,
+    pub data: Vec<(f64, f64)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    X,
+    Y,
+    Z,
+    Norm,
+}
+
+impl Component {
+    // Scalar quantities have a single value, which is returned for every component
+    pub fn select(&self, values: &[f64]) -> f64 {
+        if values.len() == 1 {
+            return values[0];
+        }
+        match self {
+            Component::X => values[0],
+            Component::Y => values[1],
+            Component::Z => values[2],
+            Component::Norm => values.iter().map(|v| v * v).sum::<f64>().sqrt(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Component> {
+        [Component::X, Component::Y, Component::Z, Component::Norm]
+            .iter()
+            .find(|c| c.get_name() == name)
+            .copied()
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Component::X => "x",
+            Component::Y => "y",
+            Component::Z => "z",
+            Component::Norm => "norm",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Projection {
+    XY,
+    XZ,
+    YZ,
+}
+
+impl Projection {
+    pub fn from_name(name: &str) -> Option<Projection> {
+        match name.to_lowercase().as_str() {
+            "xy" => Some(Projection::XY),
+            "xz" => Some(Projection::XZ),
+            "yz" => Some(Projection::YZ),
+            _ => None,
+        }
+    }
+
+    pub fn get_axes(&self) -> (Component, Component) {
+        match self {
+            Projection::XY => (Component::X, Component::Y),
+            Projection::XZ => (Component::X, Component::Z),
+            Projection::YZ => (Component::Y, Component::Z),
+        }
+    }
+
+    pub fn project(&self, values: &[f64]) -> (f64, f64) {
+        let (horizontal, vertical) = self.get_axes();
+        (horizontal.select(values), vertical.select(values))
+    }
+}
+
+fn push_point(series: &mut Vec<DataSeries>, name: &str, point: (f64, f64)) {
+    match series.iter_mut().find(|s| s.name == name) {
+        Some(s) => s.data.push(point),
+        None => series.push(DataSeries {
+            name: name.to_string(),
+            data: vec![point],
+        }),
+    }
+}
+
+// One series per entity, in order of first appearance in the history
+fn entity_series<'a, H, F>(history: H, point: F) -> Vec<DataSeries>
+where
+    H: IntoIterator<Item = &'a System>,
+    F: Fn(&System, &crate::physics::entity::Entity) -> (f64, f64),
+{
+    let mut series = Vec::new();
+    for system in history {
+        for entity in system.get_entities() {
+            push_point(&mut series, entity.get_identity(), point(system, entity));
+        }
+    }
+    series
+}
+
+pub fn quantity_over_time<'a, H: IntoIterator<Item = &'a System>>(
+    history: H,
+    quantity: Quantity,
+    component: Component,
+) -> Vec<DataSeries> {
+    entity_series(history, |system, entity| {
+        (
+            system.get_current_time().value_unsafe,
+            component.select(&quantity.get_values(entity)),
+        )
+    })
+}
+
+pub fn trajectory<'a, H: IntoIterator<Item = &'a System>>(
+    history: H,
+    projection: Projection,
+) -> Vec<DataSeries> {
+    entity_series(history, |_, entity| {
+        projection.project(&Quantity::Location.get_values(entity))
+    })
+}
+
+// Location against velocity along a single axis
+pub fn phase_space<'a, H: IntoIterator<Item = &'a System>>(
+    history: H,
+    axis: Component,
+) -> Vec<DataSeries> {
+    entity_series(history, |_, entity| {
+        (
+            axis.select(&Quantity::Location.get_values(entity)),
+            axis.select(&Quantity::Velocity.get_values(entity)),
+        )
+    })
+}
+
+pub fn system_energy_over_time<'a, H: IntoIterator<Item = &'a System>>(history: H) -> DataSeries {
+    DataSeries {
+        name: "energy".to_string(),
+        data: history
+            .into_iter()
+            .map(|s| {
+                (
+                    s.get_current_time().value_unsafe,
+                    s.get_energy().value_unsafe,
+                )
+            })
+            .collect(),
+    }
+}
+
+pub fn system_momentum_over_time<'a, H: IntoIterator<Item = &'a System>>(
+    history: H,
+    component: Component,
+) -> DataSeries {
+    DataSeries {
+        name: format!("momentum {}", component.get_name()),
+        data: history
+            .into_iter()
+            .map(|s| {
+                let momentum = s.get_momentum();
+                let value = match component {
+                    Component::X => momentum.x.value_unsafe,
+                    Component::Y => momentum.y.value_unsafe,
+                    Component::Z => momentum.z.value_unsafe,
+                    Component::Norm => momentum.norm2().sqrt().value_unsafe,
+                };
+                (s.get_current_time().value_unsafe, value)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod series_tests {
+    use super::*;
+
+    #[test]
+    fn select_component() {
+        let values = [3.0, -4.0, 0.0];
+        assert_eq!(Component::X.select(&values), 3.0);
+        assert_eq!(Component::Y.select(&values), -4.0);
+        assert_eq!(Component::Z.select(&values), 0.0);
+        assert_eq!(Component::Norm.select(&values), 5.0);
+        assert_eq!(Component::Y.select(&[2.0]), 2.0);
+    }
+
+    #[test]
+    fn project() {
+        let values = [1.0, 2.0, 3.0];
+        assert_eq!(Projection::XY.project(&values), (1.0, 2.0));
+        assert_eq!(Projection::XZ.project(&values), (1.0, 3.0));
+        assert_eq!(Projection::YZ.project(&values), (2.0, 3.0));
+    }
+}
@@ -0,0 +1,154 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::contact_forces::ContactForces;
+use crate::physics::interaction::interaction::Interaction;
+use crate::physics::state::state_influence::StateInfluence;
+use crate::physics::system::System;
+use crate::utils::identity::Identity;
+
+// Source of the influences of hypothetical actions
+pub const ACTION_SOURCE: &str = "action";
+
+// Hypothetical action, times are relative to the start of the prediction
+#[derive(Clone, Debug)]
+pub enum Action {
+    // Constant force during [start, start + duration), propagated through contacts
+    ApplyForce {
+        entity: &'static str,
+        force: Vector3d<si::Newton<f64>>,
+        start: si::Second<f64>,
+        duration: si::Second<f64>,
+    },
+    // Instantaneous change of velocity, not propagated through contacts
+    ChangeVelocity {
+        entity: &'static str,
+        velocity_change: Vector3d<si::MeterPerSecond<f64>>,
+        at: si::Second<f64>,
+    },
+    Spawn {
+        entity: Entity,
+        at: si::Second<f64>,
+    },
+    SetInputSignal {
+        actuator: &'static str,
+        signal: f64,
+        at: si::Second<f64>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionError {
+    UnknownEntity(&'static str),
+    UnknownActuator(&'static str),
+    DuplicateIdentity(&'static str),
+    InvalidStepSize(f64),
+    InvalidHorizon(f64),
+}
+
+impl std::fmt::Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ActionError::UnknownEntity(id) => write!(f, "Entity {} not found.", id),
+            ActionError::UnknownActuator(id) => write!(f, "Actuator {} not found.", id),
+            ActionError::DuplicateIdentity(id) => write!(f, "Entity {} already exists.", id),
+            ActionError::InvalidStepSize(step_size) => {
+                write!(f, "Step size {} is not positive and finite.", step_size)
+            }
+            ActionError::InvalidHorizon(horizon) => {
+                write!(f, "Horizon {} is negative or not finite.", horizon)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+fn find_entity<'a>(system: &'a System, id: &'static str) -> Result<&'a Entity, ActionError> {
+    system
+        .get_entities()
+        .iter()
+        .find(|e| e.get_identity() == id)
+        .ok_or(ActionError::UnknownEntity(id))
+}
+
+// Half a step of tolerance, so actions are taken by the step closest to their time
+fn is_due(at: si::Second<f64>, time: si::Second<f64>, step_size: si::Second<f64>) -> bool {
+    at >= time - step_size / 2.0 && at < time + step_size / 2.0
+}
+
+impl Action {
+    // Applies the action to the system if it is due during the step starting at time. Actions
+    // on entities or actuators the system does not have when they are due fail.
+    pub fn apply(
+        &self,
+        system: &mut System,
+        time: si::Second<f64>,
+        step_size: si::Second<f64>,
+    ) -> Result<(), ActionError> {
+        match self {
+            Action::ApplyForce {
+                entity,
+                force,
+                start,
+                duration,
+            } => {
+                let end = *start + *duration;
+                if time >= *start - step_size / 2.0 && time < end - step_size / 2.0 {
+                    find_entity(system, entity)?;
+                    system.apply_influence(StateInfluence::force_influence(
+                        ACTION_SOURCE,
+                        ACTION_SOURCE,
+                        entity,
+                        ContactForces::default().get_identifier(),
+                        *force,
+                    ));
+                }
+            }
+            Action::ChangeVelocity {
+                entity,
+                velocity_change,
+                at,
+            } => {
+                if is_due(*at, time, step_size) {
+                    let mass = find_entity(system, entity)?.get_state().get_mass();
+                    // The velocity changes by the end of the step
+                    system.apply_influence(StateInfluence::force_influence(
+                        ACTION_SOURCE,
+                        entity,
+                        entity,
+                        ACTION_SOURCE,
+                        *velocity_change * mass / step_size,
+                    ));
+                }
+            }
+            Action::Spawn { entity, at } => {
+                if is_due(*at, time, step_size) {
+                    let identity = entity.get_identity();
+                    if find_entity(system, identity).is_ok() {
+                        return Err(ActionError::DuplicateIdentity(identity));
+                    }
+                    system.add_entity(entity.clone());
+                }
+            }
+            Action::SetInputSignal {
+                actuator,
+                signal,
+                at,
+            } => {
+                if is_due(*at, time, step_size) {
+                    if !system
+                        .get_actuators()
+                        .iter()
+                        .any(|a| a.get_identifier() == *actuator)
+                    {
+                        return Err(ActionError::UnknownActuator(actuator));
+                    }
+                    system.set_input_signal(actuator, *signal);
+                }
+            }
+        }
+        Ok(())
+    }
+}
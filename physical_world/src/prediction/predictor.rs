@@ -0,0 +1,232 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::state::state::State;
+use crate::physics::system::System;
+use crate::prediction::action::{Action, ActionError};
+use crate::utils::identity::Identity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    ContactStarted(&'static str, &'static str),
+    ContactEnded(&'static str, &'static str),
+    Spawned(&'static str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Event {
+    // Time of the system at the end of the step in which the event was observed
+    pub time: si::Second<f64>,
+    pub kind: EventKind,
+}
+
+// Summary of a predicted future of the system
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub end_time: si::Second<f64>,
+    pub final_states: Vec<(&'static str, State)>,
+    pub contacts: Vec<(&'static str, &'static str)>,
+    pub events: Vec<Event>,
+    pub energy: si::Joule<f64>,
+    pub momentum: Vector3d<si::NewtonSecond<f64>>,
+}
+
+impl Outcome {
+    pub fn get_state(&self, id: &str) -> Option<&State> {
+        self.final_states
+            .iter()
+            .find(|(identity, _)| *identity == id)
+            .map(|(_, state)| state)
+    }
+
+    pub fn is_in_contact(&self, id0: &str, id1: &str) -> bool {
+        self.contacts
+            .iter()
+            .any(|&(a, b)| (a == id0 && b == id1) || (a == id1 && b == id0))
+    }
+}
+
+// Simulates forks of a system, the forked system itself is never modified
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Predictor {
+    pub horizon: si::Second<f64>,
+    pub step_size: si::Second<f64>,
+}
+
+impl Predictor {
+    pub fn new(horizon: si::Second<f64>, step_size: si::Second<f64>) -> Predictor {
+        Predictor { horizon, step_size }
+    }
+
+    // The step size has to be positive and the horizon must not be negative, both finite
+    pub fn validate(&self) -> Result<(), ActionError> {
+        let step_size = self.step_size.value_unsafe;
+        if !step_size.is_finite() || step_size <= 0.0 {
+            return Err(ActionError::InvalidStepSize(step_size));
+        }
+        let horizon = self.horizon.value_unsafe;
+        if !horizon.is_finite() || horizon < 0.0 {
+            return Err(ActionError::InvalidHorizon(horizon));
+        }
+        Ok(())
+    }
+
+    pub fn get_step_count(&self) -> usize {
+        (self.horizon / self.step_size).value_unsafe.round() as usize
+    }
+
+    // Fails if the predictor is invalid or with the error of the first action that cannot be
+    // applied
+    pub fn predict(&self, system: &System, actions: &[Action]) -> Result<Outcome, ActionError> {
+        self.validate()?;
+        let mut fork = system.clone();
+        let mut events = Vec::new();
+        let mut contacts = fork.get_contacts();
+        for step in 0..self.get_step_count() {
+            let time = step as f64 * self.step_size;
+            let entity_count = fork.get_entities().len();
+            for action in actions {
+                action.apply(&mut fork, time, self.step_size)?;
+            }
+            fork.next_state(self.step_size);
+            let end_time = fork.get_current_time();
+            for entity in &fork.get_entities()[entity_count..] {
+                events.push(Event {
+                    time: end_time,
+                    kind: EventKind::Spawned(entity.get_identity()),
+                });
+            }
+            let new_contacts = fork.get_contacts();
+            for &(a, b) in new_contacts.iter().filter(|c| !contacts.contains(c)) {
+                events.push(Event {
+                    time: end_time,
+                    kind: EventKind::ContactStarted(a, b),
+                });
+            }
+            for &(a, b) in contacts.iter().filter(|c| !new_contacts.contains(c)) {
+                events.push(Event {
+                    time: end_time,
+                    kind: EventKind::ContactEnded(a, b),
+                });
+            }
+            contacts = new_contacts;
+        }
+        Ok(Outcome {
+            end_time: fork.get_current_time(),
+            final_states: fork
+                .get_entities()
+                .iter()
+                .map(|e| (e.get_identity(), e.get_state().clone()))
+                .collect(),
+            contacts,
+            events,
+            energy: fork.get_energy(),
+            momentum: fork.get_momentum(),
+        })
+    }
+
+    // One outcome per candidate set of actions, in the order of the candidates
+    pub fn predict_all(
+        &self,
+        system: &System,
+        candidates: &[Vec<Action>],
+    ) -> Result<Vec<Outcome>, ActionError> {
+        candidates
+            .iter()
+            .map(|actions| self.predict(system, actions))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod predictor_tests {
+    use super::*;
+    use crate::physics::entity::Entity;
+    use crate::physics::state::shape::{Shape, Sphere};
+
+    fn ball(id: &'static str, x: f64) -> Entity {
+        Entity::new(
+            id,
+            State::new(
+                Vector3d::new(x, 0.0, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
+            ),
+        )
+    }
+
+    #[test]
+    fn change_velocity() {
+        let system = System::new(vec![ball("A", 0.0)], Vec::new(), 0.0 * si::S);
+        let predictor = Predictor::new(1.0 * si::S, 0.1 * si::S);
+        let outcome = predictor
+            .predict(
+                &system,
+                &[Action::ChangeVelocity {
+                    entity: "A",
+                    velocity_change: Vector3d::new(1.0, 0.0, 0.0) * si::MPS,
+                    at: 0.0 * si::S,
+                }],
+            )
+            .unwrap();
+        let state = outcome.get_state("A").unwrap();
+        assert!((state.get_velocity().x - 1.0 * si::MPS).value_unsafe.abs() < 1e-9);
+        // The first step only changes the velocity
+        assert!((state.get_location().x - 0.9 * si::M).value_unsafe.abs() < 1e-9);
+        assert!((outcome.end_time - 1.0 * si::S).value_unsafe.abs() < 1e-9);
+    }
+
+    #[test]
+    fn spawn_and_contact() {
+        let system = System::new(vec![ball("A", 0.0)], Vec::new(), 0.0 * si::S);
+        let predictor = Predictor::new(1.0 * si::S, 0.1 * si::S);
+        let outcome = predictor
+            .predict(
+                &system,
+                &[Action::Spawn {
+                    entity: ball("B", 1.5),
+                    at: 0.5 * si::S,
+                }],
+            )
+            .unwrap();
+        assert_eq!(outcome.final_states.len(), 2);
+        assert!(outcome.is_in_contact("B", "A"));
+        let kinds = outcome
+            .events
+            .iter()
+            .map(|e| e.kind)
+            .collect::<Vec<EventKind>>();
+        assert_eq!(
+            kinds,
+            vec![EventKind::Spawned("B"), EventKind::ContactStarted("A", "B")]
+        );
+        assert!((outcome.events[0].time - 0.6 * si::S).value_unsafe.abs() < 1e-9);
+        assert_eq!(system.get_entities().len(), 1);
+    }
+
+    #[test]
+    fn invalid_predictors() {
+        let system = System::new(vec![ball("A", 0.0)], Vec::new(), 0.0 * si::S);
+        for step_size in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            let predictor = Predictor::new(1.0 * si::S, step_size * si::S);
+            assert!(matches!(
+                predictor.predict(&system, &[]),
+                Err(ActionError::InvalidStepSize(_))
+            ));
+        }
+        for horizon in [-1.0, f64::NAN, f64::INFINITY] {
+            let predictor = Predictor::new(horizon * si::S, 0.1 * si::S);
+            assert!(matches!(
+                predictor.predict(&system, &[]),
+                Err(ActionError::InvalidHorizon(_))
+            ));
+        }
+        assert!(Predictor::new(0.0 * si::S, 0.1 * si::S)
+            .predict(&system, &[])
+            .is_ok());
+    }
+}
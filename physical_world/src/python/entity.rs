@@ -0,0 +1,57 @@
+use pyo3::prelude::*;
+
+use crate::physics::entity::Entity;
+use crate::python::state::{to_list, PyState};
+use crate::utils::identity::{intern, Identity};
+
+#[pyclass(name = "Entity", module = "physical_machine")]
+#[derive(Clone)]
+pub struct PyEntity {
+    pub entity: Entity,
+}
+
+#[pymethods]
+impl PyEntity {
+    #[new]
+    #[pyo3(signature = (identity, state, tags = Vec::new()))]
+    fn new(identity: &str, state: PyState, tags: Vec<String>) -> PyEntity {
+        let mut entity = Entity::new(intern(identity), state.state);
+        for tag in tags {
+            entity.add_tag(intern(&tag));
+        }
+        PyEntity { entity }
+    }
+
+    #[getter]
+    fn identity(&self) -> &'static str {
+        self.entity.get_identity()
+    }
+
+    #[getter]
+    fn state(&self) -> PyState {
+        PyState {
+            state: self.entity.get_state().clone(),
+        }
+    }
+
+    #[getter]
+    fn tags(&self) -> Vec<&'static str> {
+        self.entity.get_tags().clone()
+    }
+
+    // N*s
+    #[getter]
+    fn momentum(&self) -> [f64; 3] {
+        to_list(self.entity.get_momentum())
+    }
+
+    // J
+    #[getter]
+    fn kinetic_energy(&self) -> f64 {
+        self.entity.get_kinetic_energy().value_unsafe
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Entity({:?})", self.entity.get_identity())
+    }
+}
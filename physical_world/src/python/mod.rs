@@ -0,0 +1,30 @@
+// Python extension module, see the python feature
+pub mod entity;
+pub mod state;
+pub mod system;
+
+use pyo3::prelude::*;
+
+use crate::python::entity::PyEntity;
+use crate::python::state::PyState;
+use crate::python::system::PySystem;
+use crate::scene::scene::get_builtin_interactions;
+
+// Names of the interactions a system can be created with, the contact solver replaces contact
+// force and elastic collision
+#[pyfunction]
+fn builtin_interactions() -> Vec<&'static str> {
+    get_builtin_interactions()
+        .iter()
+        .map(|i| i.get_identifier())
+        .collect()
+}
+
+#[pymodule]
+fn physical_machine(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyState>()?;
+    module.add_class::<PyEntity>()?;
+    module.add_class::<PySystem>()?;
+    module.add_function(wrap_pyfunction!(builtin_interactions, module)?)?;
+    Ok(())
+}
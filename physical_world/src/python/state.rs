@@ -0,0 +1,114 @@
+use dimensioned::si;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use vector3d::Vector3d;
+
+use crate::physics::state::shape::{Cuboid, Shape, Sphere};
+use crate::physics::state::state::State;
+
+pub fn to_list<U>(v: Vector3d<si::SI<f64, U>>) -> [f64; 3] {
+    [v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+}
+
+// State of an entity in SI units: m, m/s, N and kg
+#[pyclass(name = "State", module = "physical_machine")]
+#[derive(Clone)]
+pub struct PyState {
+    pub state: State,
+}
+
+#[pymethods]
+impl PyState {
+    // A sphere of the given radius, a box of the given half extents or a point without either
+    #[new]
+    #[pyo3(signature = (location, velocity, mass, net_force = [0.0; 3], radius = None, half_extents = None))]
+    fn new(
+        location: [f64; 3],
+        velocity: [f64; 3],
+        mass: f64,
+        net_force: [f64; 3],
+        radius: Option<f64>,
+        half_extents: Option<[f64; 3]>,
+    ) -> PyResult<PyState> {
+        if mass.is_nan() || mass <= 0.0 {
+            return Err(PyValueError::new_err(format!(
+                "Mass {} is not positive.",
+                mass
+            )));
+        }
+        let shape = match (radius, half_extents) {
+            (None, None) => Shape::None,
+            (Some(radius), None) if radius >= 0.0 => Shape::Sphere(Sphere {
+                radius: radius * si::M,
+            }),
+            (None, Some([x, y, z])) if x >= 0.0 && y >= 0.0 && z >= 0.0 => Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(x, y, z) * si::M,
+            }),
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Expected either a radius or half extents.",
+                ))
+            }
+            _ => return Err(PyValueError::new_err("Size of the shape is negative.")),
+        };
+        let [x, y, z] = location;
+        let [vx, vy, vz] = velocity;
+        let [fx, fy, fz] = net_force;
+        Ok(PyState {
+            state: State::new(
+                Vector3d::new(x, y, z) * si::M,
+                Vector3d::new(vx, vy, vz) * si::MPS,
+                Vector3d::new(fx, fy, fz) * si::N,
+                mass * si::KG,
+                shape,
+            ),
+        })
+    }
+
+    #[getter]
+    fn location(&self) -> [f64; 3] {
+        to_list(self.state.get_location())
+    }
+
+    #[getter]
+    fn velocity(&self) -> [f64; 3] {
+        to_list(self.state.get_velocity())
+    }
+
+    #[getter]
+    fn net_force(&self) -> [f64; 3] {
+        to_list(self.state.get_net_force())
+    }
+
+    #[getter]
+    fn mass(&self) -> f64 {
+        self.state.get_mass().value_unsafe
+    }
+
+    // None unless the shape is a sphere
+    #[getter]
+    fn radius(&self) -> Option<f64> {
+        match self.state.get_shape() {
+            Shape::Sphere(sphere) => Some(sphere.radius.value_unsafe),
+            _ => None,
+        }
+    }
+
+    // None unless the shape is a box
+    #[getter]
+    fn half_extents(&self) -> Option<[f64; 3]> {
+        match self.state.get_shape() {
+            Shape::Cuboid(cuboid) => Some(to_list(cuboid.half_extents)),
+            _ => None,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "State(location={:?}, velocity={:?}, mass={})",
+            self.location(),
+            self.velocity(),
+            self.mass()
+        )
+    }
+}
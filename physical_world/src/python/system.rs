@@ -0,0 +1,313 @@
+use dimensioned::si;
+use itertools::Itertools;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::physics::entity::Entity;
+use crate::physics::system::System;
+use crate::python::entity::PyEntity;
+use crate::python::state::to_list;
+use crate::recording::columns::Quantity;
+use crate::scene::examples::get_example;
+use crate::scene::scene::{
+    check_entity, check_interactions, get_builtin_interaction, get_default_interactions, Scene,
+    SceneError,
+};
+use crate::utils::identity::Identity;
+
+fn scene_error(error: SceneError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+// System together with its entities after every step, times in s
+#[pyclass(name = "System", module = "physical_machine", unsendable)]
+pub struct PySystem {
+    system: System,
+    history: Vec<(f64, Vec<Entity>)>,
+}
+
+impl PySystem {
+    fn from_system(system: System) -> PySystem {
+        let mut py_system = PySystem {
+            system,
+            history: Vec::new(),
+        };
+        py_system.record();
+        py_system
+    }
+
+    fn from_scene(scene: Scene) -> PyResult<PySystem> {
+        Ok(PySystem::from_system(
+            scene.to_system().map_err(scene_error)?,
+        ))
+    }
+
+    fn record(&mut self) {
+        self.history.push((
+            self.system.get_current_time().value_unsafe,
+            self.system.get_entities().clone(),
+        ));
+    }
+
+    fn has_entity(&self, identity: &str) -> bool {
+        self.system
+            .get_entities()
+            .iter()
+            .any(|e| e.get_identity() == identity)
+    }
+}
+
+#[pymethods]
+impl PySystem {
+    // All built-in interactions unless names of interactions are given
+    #[new]
+    #[pyo3(signature = (entities, interactions = None, time = 0.0))]
+    fn new(
+        entities: Vec<PyEntity>,
+        interactions: Option<Vec<String>>,
+        time: f64,
+    ) -> PyResult<PySystem> {
+        let interactions = match interactions {
+            None => get_default_interactions(),
+            Some(names) => {
+                check_interactions(&names).map_err(scene_error)?;
+                names
+                    .iter()
+                    .unique()
+                    .filter_map(|name| get_builtin_interaction(name))
+                    .collect()
+            }
+        };
+        let names: Vec<&'static str> = interactions.iter().map(|i| i.get_identifier()).collect();
+        let entities: Vec<Entity> = entities.into_iter().map(|e| e.entity).collect();
+        for (idx, entity) in entities.iter().enumerate() {
+            if entities[..idx]
+                .iter()
+                .any(|e| e.get_identity() == entity.get_identity())
+            {
+                return Err(PyValueError::new_err(format!(
+                    "Identity {} is used more than once.",
+                    entity.get_identity()
+                )));
+            }
+            check_entity(entity, &names).map_err(scene_error)?;
+        }
+        Ok(PySystem::from_system(System::new(
+            entities,
+            interactions,
+            time * si::S,
+        )))
+    }
+
+    #[staticmethod]
+    fn from_example(name: &str) -> PyResult<PySystem> {
+        match get_example(name) {
+            Some(scene) => PySystem::from_scene(scene.map_err(scene_error)?),
+            None => Err(PyValueError::new_err(format!("No example named {}.", name))),
+        }
+    }
+
+    #[staticmethod]
+    fn from_scene_file(path: &str) -> PyResult<PySystem> {
+        PySystem::from_scene(Scene::load(path).map_err(scene_error)?)
+    }
+
+    #[staticmethod]
+    fn from_scene_json(json: &str) -> PyResult<PySystem> {
+        PySystem::from_scene(Scene::from_json(json).map_err(scene_error)?)
+    }
+
+    // Returns the time after the last step
+    #[pyo3(signature = (time_step, steps = 1))]
+    fn step(&mut self, time_step: f64, steps: usize) -> PyResult<f64> {
+        if time_step.is_nan() || time_step <= 0.0 {
+            return Err(PyValueError::new_err(format!(
+                "Time step {} is not positive.",
+                time_step
+            )));
+        }
+        for _ in 0..steps {
+            self.system.next_state(time_step * si::S);
+            self.record();
+        }
+        Ok(self.time())
+    }
+
+    fn add_entity(&mut self, entity: PyEntity) -> PyResult<()> {
+        if self.has_entity(entity.entity.get_identity()) {
+            return Err(PyValueError::new_err(format!(
+                "Identity {} is used more than once.",
+                entity.entity.get_identity()
+            )));
+        }
+        check_entity(&entity.entity, &self.interactions()).map_err(scene_error)?;
+        self.system.add_entity(entity.entity);
+        Ok(())
+    }
+
+    #[getter]
+    fn time(&self) -> f64 {
+        self.system.get_current_time().value_unsafe
+    }
+
+    #[getter]
+    fn entities(&self) -> Vec<PyEntity> {
+        self.system
+            .get_entities()
+            .iter()
+            .map(|e| PyEntity { entity: e.clone() })
+            .collect()
+    }
+
+    fn get_entity(&self, identity: &str) -> Option<PyEntity> {
+        self.system
+            .get_entities()
+            .iter()
+            .find(|e| e.get_identity() == identity)
+            .map(|e| PyEntity { entity: e.clone() })
+    }
+
+    #[getter]
+    fn interactions(&self) -> Vec<&'static str> {
+        self.system
+            .get_interactions()
+            .iter()
+            .map(|i| i.get_identifier())
+            .collect()
+    }
+
+    // J
+    #[getter]
+    fn energy(&self) -> f64 {
+        self.system.get_energy().value_unsafe
+    }
+
+    // N*s
+    #[getter]
+    fn momentum(&self) -> [f64; 3] {
+        to_list(self.system.get_momentum())
+    }
+
+    #[getter]
+    fn contacts(&self) -> Vec<(&'static str, &'static str)> {
+        self.system.get_contacts()
+    }
+
+    // Times of the initial state and of every step since
+    #[getter]
+    fn history_times(&self) -> Vec<f64> {
+        self.history.iter().map(|(time, _)| *time).collect()
+    }
+
+    // One row [t, values...] per recorded state containing the entity. Units and names of the
+    // quantities are those of the recorders: location, velocity, net_force, momentum, mass,
+    // radius and kinetic_energy.
+    #[pyo3(signature = (identity, quantity = "location"))]
+    fn get_history(&self, identity: &str, quantity: &str) -> PyResult<Vec<Vec<f64>>> {
+        let quantity = Quantity::from_name(quantity).ok_or_else(|| {
+            let names: Vec<&str> = Quantity::ALL.iter().map(|q| q.get_name()).collect();
+            PyValueError::new_err(format!("Expected one of {}.", names.join(", ")))
+        })?;
+        if !self
+            .history
+            .iter()
+            .any(|(_, entities)| entities.iter().any(|e| e.get_identity() == identity))
+        {
+            return Err(PyValueError::new_err(format!(
+                "Entity {} not found.",
+                identity
+            )));
+        }
+        Ok(self
+            .history
+            .iter()
+            .filter_map(|(time, entities)| {
+                let entity = entities.iter().find(|e| e.get_identity() == identity)?;
+                let mut row = vec![*time];
+                row.extend(quantity.get_values(entity));
+                Some(row)
+            })
+            .collect())
+    }
+
+    // Keeps only the current state
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.record();
+    }
+}
+
+#[cfg(test)]
+mod python_system_tests {
+    use vector3d::Vector3d;
+
+    use super::*;
+    use crate::physics::state::shape::{Shape, Sphere};
+    use crate::physics::state::state::State;
+
+    fn ball(identity: &'static str, x: f64, velocity: f64, shape: Shape) -> PyEntity {
+        PyEntity {
+            entity: Entity::new(
+                identity,
+                State::new(
+                    Vector3d::new(x, 0.0, 0.0) * si::M,
+                    Vector3d::new(velocity, 0.0, 0.0) * si::MPS,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                    1.0 * si::KG,
+                    shape,
+                ),
+            ),
+        }
+    }
+
+    fn sphere() -> Shape {
+        Shape::Sphere(Sphere {
+            radius: 0.5 * si::M,
+        })
+    }
+
+    #[test]
+    fn step_and_history() {
+        let mut system = PySystem::new(
+            vec![ball("A", 0.0, 1.0, sphere()), ball("B", 5.0, 0.0, sphere())],
+            Some(vec!["elastic collision".to_string()]),
+            0.0,
+        )
+        .unwrap();
+        assert_eq!(system.interactions(), vec!["elastic collision"]);
+        assert!((system.step(0.5, 4).unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(system.history_times().len(), 5);
+        let history = system.get_history("A", "location").unwrap();
+        assert_eq!(history.len(), 5);
+        assert!((history[4][0] - 2.0).abs() < 1e-9 && (history[4][1] - 2.0).abs() < 1e-9);
+        system.clear_history();
+        assert_eq!(system.history_times(), vec![system.time()]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut system = PySystem::new(vec![ball("A", 0.0, 0.0, sphere())], None, 0.0).unwrap();
+        assert!(system.step(0.0, 1).is_err());
+        assert!(system.step(f64::NAN, 1).is_err());
+        assert!(system.get_history("missing", "location").is_err());
+        assert!(system.get_history("A", "colour").is_err());
+        assert!(system.add_entity(ball("A", 2.0, 0.0, sphere())).is_err());
+        assert!(PySystem::new(
+            vec![ball("A", 0.0, 0.0, sphere())],
+            Some(vec!["magnetism".to_string()]),
+            0.0
+        )
+        .is_err());
+        assert!(PySystem::from_example("missing").is_err());
+    }
+
+    #[test]
+    fn shapeless_entities_need_shapeless_interactions() {
+        let point = || ball("P", 0.0, 0.0, Shape::None);
+        assert!(PySystem::new(vec![point()], None, 0.0).is_err());
+        assert!(PySystem::new(vec![point()], Some(vec!["Gravity".to_string()]), 0.0).is_ok());
+        let mut system = PySystem::new(Vec::new(), None, 0.0).unwrap();
+        assert!(system.add_entity(point()).is_err());
+        assert!(system.add_entity(ball("A", 0.0, 0.0, sphere())).is_ok());
+    }
+}
@@ -0,0 +1,155 @@
+use dimensioned::Sqrt;
+
+use crate::physics::entity::Entity;
+use crate::physics::state::shape::Shape;
+use crate::physics::system::System;
+use crate::recording::recorder::RecorderError;
+use crate::utils::identity::Identity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quantity {
+    Location,
+    Velocity,
+    NetForce,
+    Momentum,
+    Mass,
+    Radius,
+    KineticEnergy,
+}
+
+impl Quantity {
+    pub const ALL: [Quantity; 7] = [
+        Quantity::Location,
+        Quantity::Velocity,
+        Quantity::NetForce,
+        Quantity::Momentum,
+        Quantity::Mass,
+        Quantity::Radius,
+        Quantity::KineticEnergy,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Quantity::Location => "location",
+            Quantity::Velocity => "velocity",
+            Quantity::NetForce => "net_force",
+            Quantity::Momentum => "momentum",
+            Quantity::Mass => "mass",
+            Quantity::Radius => "radius",
+            Quantity::KineticEnergy => "kinetic_energy",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Quantity> {
+        Quantity::ALL.iter().find(|q| q.get_name() == name).copied()
+    }
+
+    pub fn get_unit(&self) -> &'static str {
+        match self {
+            Quantity::Location | Quantity::Radius => "m",
+            Quantity::Velocity => "m/s",
+            Quantity::NetForce => "N",
+            Quantity::Momentum => "N*s",
+            Quantity::Mass => "kg",
+            Quantity::KineticEnergy => "J",
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        matches!(
+            self,
+            Quantity::Location | Quantity::Velocity | Quantity::NetForce | Quantity::Momentum
+        )
+    }
+
+    // SI values of the quantity, three components for vectors and a single one for scalars
+    pub fn get_values(&self, entity: &Entity) -> Vec<f64> {
+        let state = entity.get_state();
+        match self {
+            Quantity::Location => {
+                let v = state.get_location();
+                vec![v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+            }
+            Quantity::Velocity => {
+                let v = state.get_velocity();
+                vec![v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+            }
+            Quantity::NetForce => {
+                let v = state.get_net_force();
+                vec![v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+            }
+            Quantity::Momentum => {
+                let v = entity.get_momentum();
+                vec![v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+            }
+            Quantity::Mass => vec![state.get_mass().value_unsafe],
+            // Cuboids report the radius of their bounding sphere, entities without a shape are
+            // treated as point masses
+            Quantity::Radius => match state.get_shape() {
+                Shape::Sphere(s) => vec![s.radius.value_unsafe],
+                Shape::Cuboid(c) => vec![c.half_extents.norm2().sqrt().value_unsafe],
+                Shape::None => vec![0.0],
+            },
+            Quantity::KineticEnergy => vec![entity.get_kinetic_energy().value_unsafe],
+        }
+    }
+
+    pub fn get_column_names(&self, prefix: &str) -> Vec<String> {
+        if self.is_vector() {
+            ["x", "y", "z"]
+                .iter()
+                .map(|axis| format!("{}_{}_{}", prefix, self.get_name(), axis))
+                .collect()
+        } else {
+            vec![format!("{}_{}", prefix, self.get_name())]
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Columns {
+    // None selects every entity of the system at the time of the first record
+    entities: Option<Vec<&'static str>>,
+    quantities: Vec<Quantity>,
+}
+
+impl Columns {
+    pub fn new(entities: Option<Vec<&'static str>>, quantities: Vec<Quantity>) -> Columns {
+        Columns {
+            entities,
+            quantities,
+        }
+    }
+
+    pub fn all() -> Columns {
+        Columns::new(None, Quantity::ALL.to_vec())
+    }
+
+    pub fn get_quantities(&self) -> &[Quantity] {
+        &self.quantities
+    }
+
+    pub fn resolve_entities(&self, system: &System) -> Result<Vec<&'static str>, RecorderError> {
+        match &self.entities {
+            None => Ok(system
+                .get_entities()
+                .iter()
+                .map(|e| e.get_identity())
+                .collect()),
+            Some(ids) => {
+                for id in ids {
+                    find_entity(system, id)?;
+                }
+                Ok(ids.clone())
+            }
+        }
+    }
+}
+
+pub fn find_entity<'a>(system: &'a System, id: &'static str) -> Result<&'a Entity, RecorderError> {
+    system
+        .get_entities()
+        .iter()
+        .find(|e| e.get_identity() == id)
+        .ok_or(RecorderError::UnknownEntity(id))
+}
@@ -0,0 +1,98 @@
+use std::io::Write;
+
+use crate::physics::system::System;
+use crate::recording::columns::{find_entity, Columns};
+use crate::recording::recorder::{Recorder, RecorderError};
+use crate::recording::sampling::{Sampler, Sampling};
+
+pub struct CsvRecorder<W: Write> {
+    writer: csv::Writer<W>,
+    sampler: Sampler,
+    columns: Columns,
+    // Entities are fixed by the header written with the first record
+    entities: Option<Vec<&'static str>>,
+}
+
+impl CsvRecorder<std::fs::File> {
+    pub fn create<P: AsRef<std::path::Path>>(
+        path: P,
+        sampling: Sampling,
+        columns: Columns,
+    ) -> Result<CsvRecorder<std::fs::File>, RecorderError> {
+        // No file is left behind for an invalid sampling
+        let sampler = Sampler::new(sampling)?;
+        let file = std::fs::File::create(path)?;
+        Ok(CsvRecorder::with_sampler(file, sampler, columns))
+    }
+}
+
+impl<W: Write> CsvRecorder<W> {
+    pub fn new(
+        writer: W,
+        sampling: Sampling,
+        columns: Columns,
+    ) -> Result<CsvRecorder<W>, RecorderError> {
+        Ok(CsvRecorder::with_sampler(
+            writer,
+            Sampler::new(sampling)?,
+            columns,
+        ))
+    }
+
+    fn with_sampler(writer: W, sampler: Sampler, columns: Columns) -> CsvRecorder<W> {
+        CsvRecorder {
+            writer: csv::Writer::from_writer(writer),
+            sampler,
+            columns,
+            entities: None,
+        }
+    }
+
+    pub fn into_inner(self) -> Result<W, RecorderError> {
+        self.writer
+            .into_inner()
+            .map_err(|e| RecorderError::Io(e.into_error()))
+    }
+
+    fn write_header(&mut self, entities: &[&'static str]) -> Result<(), RecorderError> {
+        let mut header = vec!["time".to_string()];
+        for id in entities {
+            for quantity in self.columns.get_quantities() {
+                header.extend(quantity.get_column_names(id));
+            }
+        }
+        self.writer.write_record(&header)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Recorder for CsvRecorder<W> {
+    fn record(&mut self, system: &System) -> Result<(), RecorderError> {
+        if !self.sampler.is_due(system.get_current_time()) {
+            return Ok(());
+        }
+        let entities = match &self.entities {
+            Some(entities) => entities.clone(),
+            None => {
+                let entities = self.columns.resolve_entities(system)?;
+                self.write_header(&entities)?;
+                self.entities = Some(entities.clone());
+                entities
+            }
+        };
+        let mut row = vec![system.get_current_time().value_unsafe.to_string()];
+        for id in entities {
+            let entity = find_entity(system, id)?;
+            for quantity in self.columns.get_quantities() {
+                row.extend(quantity.get_values(entity).iter().map(|v| v.to_string()));
+            }
+        }
+        self.writer.write_record(&row)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), RecorderError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::Write;
+
+use serde_json::{json, Map, Value};
+
+use crate::physics::system::System;
+use crate::recording::columns::{find_entity, Columns};
+use crate::recording::recorder::{Recorder, RecorderError};
+use crate::recording::sampling::{Sampler, Sampling};
+
+// One JSON object per line:
+// {"time": 0.1, "entities": [{"identity": "A", "location": [x, y, z], "mass": 1.0, ...}, ...]}
+pub struct JsonLinesRecorder<W: Write> {
+    writer: W,
+    sampler: Sampler,
+    columns: Columns,
+    entities: Option<Vec<&'static str>>,
+}
+
+impl JsonLinesRecorder<std::io::BufWriter<std::fs::File>> {
+    pub fn create<P: AsRef<std::path::Path>>(
+        path: P,
+        sampling: Sampling,
+        columns: Columns,
+    ) -> Result<JsonLinesRecorder<std::io::BufWriter<std::fs::File>>, RecorderError> {
+        // No file is left behind for an invalid sampling
+        let sampler = Sampler::new(sampling)?;
+        let file = std::fs::File::create(path)?;
+        Ok(JsonLinesRecorder::with_sampler(
+            std::io::BufWriter::new(file),
+            sampler,
+            columns,
+        ))
+    }
+}
+
+impl<W: Write> JsonLinesRecorder<W> {
+    pub fn new(
+        writer: W,
+        sampling: Sampling,
+        columns: Columns,
+    ) -> Result<JsonLinesRecorder<W>, RecorderError> {
+        Ok(JsonLinesRecorder::with_sampler(
+            writer,
+            Sampler::new(sampling)?,
+            columns,
+        ))
+    }
+
+    fn with_sampler(writer: W, sampler: Sampler, columns: Columns) -> JsonLinesRecorder<W> {
+        JsonLinesRecorder {
+            writer,
+            sampler,
+            columns,
+            entities: None,
+        }
+    }
+
+    pub fn into_inner(mut self) -> Result<W, RecorderError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Recorder for JsonLinesRecorder<W> {
+    fn record(&mut self, system: &System) -> Result<(), RecorderError> {
+        if !self.sampler.is_due(system.get_current_time()) {
+            return Ok(());
+        }
+        if self.entities.is_none() {
+            self.entities = Some(self.columns.resolve_entities(system)?);
+        }
+        let mut entities = Vec::new();
+        for id in self.entities.iter().flatten() {
+            let entity = find_entity(system, id)?;
+            let mut object = Map::new();
+            object.insert("identity".to_string(), json!(id));
+            for quantity in self.columns.get_quantities() {
+                let values = quantity.get_values(entity);
+                let value = if quantity.is_vector() {
+                    json!(values)
+                } else {
+                    json!(values[0])
+                };
+                object.insert(quantity.get_name().to_string(), value);
+            }
+            entities.push(Value::Object(object));
+        }
+        let line = json!({
+            "time": system.get_current_time().value_unsafe,
+            "entities": entities,
+        });
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), RecorderError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod columns;
+pub mod csv_recorder;
+pub mod json_lines_recorder;
+pub mod recorder;
+pub mod sampling;
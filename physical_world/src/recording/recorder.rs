@@ -0,0 +1,65 @@
+use crate::physics::system::System;
+use crate::recording::sampling::Sampling;
+
+/// Receives the state of a `System` after every step and persists the rows
+/// selected by its sampling and column configuration.
+pub trait Recorder {
+    fn record(&mut self, system: &System) -> Result<(), RecorderError>;
+    fn flush(&mut self) -> Result<(), RecorderError>;
+}
+
+#[derive(Debug)]
+pub enum RecorderError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    UnknownEntity(&'static str),
+    InvalidSampling(Sampling),
+}
+
+impl std::fmt::Display for RecorderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RecorderError::Io(e) => write!(f, "I/O error while recording: {}", e),
+            RecorderError::Csv(e) => write!(f, "CSV error while recording: {}", e),
+            RecorderError::Json(e) => write!(f, "JSON error while recording: {}", e),
+            RecorderError::UnknownEntity(id) => {
+                write!(f, "Entity {} is not part of the recorded system.", id)
+            }
+            RecorderError::InvalidSampling(sampling) => write!(
+                f,
+                "Sampling {:?} needs a positive factor or a positive and finite interval.",
+                sampling
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecorderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecorderError::Io(e) => Some(e),
+            RecorderError::Csv(e) => Some(e),
+            RecorderError::Json(e) => Some(e),
+            RecorderError::UnknownEntity(_) | RecorderError::InvalidSampling(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RecorderError {
+    fn from(e: std::io::Error) -> Self {
+        RecorderError::Io(e)
+    }
+}
+
+impl From<csv::Error> for RecorderError {
+    fn from(e: csv::Error) -> Self {
+        RecorderError::Csv(e)
+    }
+}
+
+impl From<serde_json::Error> for RecorderError {
+    fn from(e: serde_json::Error) -> Self {
+        RecorderError::Json(e)
+    }
+}
@@ -0,0 +1,124 @@
+use dimensioned::si;
+
+use crate::recording::recorder::RecorderError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sampling {
+    EveryStep,
+    // Keep every n-th step, starting with the first one
+    Decimation(usize),
+    // Keep the first step at or after every multiple of the interval in simulated time
+    Interval(si::Second<f64>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    sampling: Sampling,
+    steps: usize,
+    start_time: Option<si::Second<f64>>,
+    next_sample: usize,
+}
+
+impl Sampler {
+    pub fn new(sampling: Sampling) -> Result<Sampler, RecorderError> {
+        match sampling {
+            Sampling::Decimation(0) => return Err(RecorderError::InvalidSampling(sampling)),
+            Sampling::Interval(interval)
+                if !interval.value_unsafe.is_finite() || interval <= 0.0 * si::S =>
+            {
+                return Err(RecorderError::InvalidSampling(sampling))
+            }
+            _ => (),
+        }
+        Ok(Sampler {
+            sampling,
+            steps: 0,
+            start_time: None,
+            next_sample: 0,
+        })
+    }
+
+    pub fn get_sampling(&self) -> Sampling {
+        self.sampling
+    }
+
+    // is_multiple_of would need Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn is_due(&mut self, time: si::Second<f64>) -> bool {
+        let step = self.steps;
+        self.steps += 1;
+        match self.sampling {
+            Sampling::EveryStep => true,
+            Sampling::Decimation(factor) => step % factor == 0,
+            Sampling::Interval(interval) => {
+                let start_time = *self.start_time.get_or_insert(time);
+                // Tolerate accumulated rounding errors of the simulated time
+                let elapsed = ((time - start_time) / interval).value_unsafe + 1e-9;
+                if elapsed < self.next_sample as f64 {
+                    return false;
+                }
+                self.next_sample = elapsed.floor() as usize + 1;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod sampling_tests {
+    use super::*;
+
+    fn due_steps(sampling: Sampling, step_size: f64, steps: usize) -> Vec<usize> {
+        let mut sampler = Sampler::new(sampling).unwrap();
+        let mut time = 0.0 * si::S;
+        let mut due = Vec::new();
+        for step in 0..steps {
+            if sampler.is_due(time) {
+                due.push(step);
+            }
+            time += step_size * si::S;
+        }
+        due
+    }
+
+    #[test]
+    fn every_step() {
+        assert_eq!(due_steps(Sampling::EveryStep, 0.1, 4), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn decimation() {
+        assert_eq!(due_steps(Sampling::Decimation(3), 0.1, 8), vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn interval() {
+        assert_eq!(
+            due_steps(Sampling::Interval(0.5 * si::S), 0.1, 16),
+            vec![0, 5, 10, 15]
+        );
+    }
+
+    #[test]
+    fn interval_shorter_than_step() {
+        assert_eq!(
+            due_steps(Sampling::Interval(0.1 * si::S), 0.25, 4),
+            vec![0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn invalid_sampling() {
+        for sampling in [
+            Sampling::Decimation(0),
+            Sampling::Interval(0.0 * si::S),
+            Sampling::Interval(-1.0 * si::S),
+            Sampling::Interval(f64::NAN * si::S),
+        ] {
+            assert!(matches!(
+                Sampler::new(sampling),
+                Err(RecorderError::InvalidSampling(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use dimensioned::si;
+
+use crate::physics::system::System;
+use crate::virtualization::virtual_actuator::{
+    ActuationError, DesiredChange, Plan, VirtualActuator,
+};
+
+// Applies changes of physical properties through virtual actuators
+pub struct Controller {
+    virtual_actuators: Vec<Box<dyn VirtualActuator>>,
+}
+
+impl Controller {
+    pub fn new(virtual_actuators: Vec<Box<dyn VirtualActuator>>) -> Controller {
+        Controller { virtual_actuators }
+    }
+
+    pub fn get_virtual_actuators(&self) -> &Vec<Box<dyn VirtualActuator>> {
+        &self.virtual_actuators
+    }
+
+    // Sets the input signals of the plan of the first virtual actuator for the property
+    pub fn set_change(
+        &self,
+        system: &mut System,
+        change: &DesiredChange,
+        step_size: si::Second<f64>,
+    ) -> Result<Plan, ActuationError> {
+        let property = change.value.get_property();
+        let actuator = self
+            .virtual_actuators
+            .iter()
+            .find(|a| a.get_property() == property)
+            .ok_or(ActuationError::UnsupportedProperty(property))?;
+        let plan = actuator.plan(system, change, step_size)?;
+        plan.apply(system);
+        Ok(plan)
+    }
+}
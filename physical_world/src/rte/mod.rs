@@ -0,0 +1,4 @@
+pub mod controller;
+pub mod observer;
+pub mod phenomenon;
+pub mod runtime_environment;
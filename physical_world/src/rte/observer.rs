@@ -0,0 +1,48 @@
+use crate::physics::system::System;
+use crate::rte::phenomenon::{PhenomenonDescription, StateInstance};
+use crate::space::region::Region;
+use crate::virtualization::virtual_sensor::VirtualSensor;
+
+// Observes the state of phenomena through virtual sensors
+pub struct Observer {
+    virtual_sensors: Vec<Box<dyn VirtualSensor>>,
+}
+
+impl Observer {
+    pub fn new(virtual_sensors: Vec<Box<dyn VirtualSensor>>) -> Observer {
+        Observer { virtual_sensors }
+    }
+
+    pub fn get_virtual_sensors(&self) -> &Vec<Box<dyn VirtualSensor>> {
+        &self.virtual_sensors
+    }
+
+    // None if a property cannot be observed, a sensor of a condition is unknown or a rule is
+    // not met
+    pub fn get_state_instance(
+        &self,
+        description: &dyn PhenomenonDescription,
+        system: &System,
+    ) -> Option<StateInstance> {
+        let mut observations = Vec::new();
+        for condition in description.state() {
+            let sensor = self
+                .virtual_sensors
+                .iter()
+                .find(|s| s.get_identifier() == condition.sensor)?;
+            let observation = sensor.observe(system)?;
+            if !(condition.rule)(&observation) {
+                return None;
+            }
+            observations.push(observation);
+        }
+        let region = observations
+            .iter()
+            .map(|o| Region::from(o.region))
+            .reduce(Region::intersection)?;
+        Some(StateInstance {
+            observations,
+            region,
+        })
+    }
+}
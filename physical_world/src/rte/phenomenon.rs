@@ -0,0 +1,27 @@
+use crate::space::region::Region;
+use crate::virtualization::virtual_actuator::DesiredChange;
+use crate::virtualization::virtual_sensor::Observation;
+
+// Rule on the observation of a virtual sensor
+#[derive(Clone, Copy, Debug)]
+pub struct Condition {
+    pub sensor: &'static str,
+    pub rule: fn(&Observation) -> bool,
+}
+
+// Observations that satisfy the state of a phenomenon, in the order of its conditions, and the
+// region where all of them apply
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateInstance {
+    pub observations: Vec<Observation>,
+    pub region: Region,
+}
+
+pub trait PhenomenonDescription {
+    fn get_identifier(&self) -> &'static str;
+    // No conditions describe the default phenomenon, which applies to the whole system
+    fn state(&self) -> Vec<Condition>;
+    // Changes of higher priorities replace the ones of lower priorities
+    fn priority(&self) -> i32;
+    fn behavior(&self, state: &StateInstance) -> Vec<DesiredChange>;
+}
@@ -0,0 +1,169 @@
+use dimensioned::si;
+
+use crate::physics::system::System;
+use crate::rte::controller::Controller;
+use crate::rte::observer::Observer;
+use crate::rte::phenomenon::{PhenomenonDescription, StateInstance};
+use crate::space::region::Region;
+use crate::virtualization::virtual_actuator::ActuationError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RteError {
+    DuplicateSensor(&'static str),
+    DuplicateActuator(&'static str),
+    UnknownSensor {
+        description: &'static str,
+        sensor: &'static str,
+    },
+}
+
+impl std::fmt::Display for RteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RteError::DuplicateSensor(id) => write!(f, "Virtual sensor {} is not unique.", id),
+            RteError::DuplicateActuator(id) => {
+                write!(f, "Virtual actuator {} is not unique.", id)
+            }
+            RteError::UnknownSensor {
+                description,
+                sensor,
+            } => write!(
+                f,
+                "Virtual sensor {} of phenomenon {} not found.",
+                sensor, description
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RteError {}
+
+// Identifier that occurs a second time, if any
+fn find_duplicate(identifiers: &[&'static str]) -> Option<&'static str> {
+    identifiers
+        .iter()
+        .enumerate()
+        .find(|&(idx, id)| identifiers[..idx].contains(id))
+        .map(|(_, &id)| id)
+}
+
+// Runs the behaviors of phenomenon descriptions against a system. Applications only address
+// properties and regions, the observer and the controller pick the physical devices.
+pub struct RuntimeEnvironment {
+    // Ascending priorities, so changes of lower priorities are replaced later
+    descriptions: Vec<Box<dyn PhenomenonDescription>>,
+    observer: Observer,
+    controller: Controller,
+    system_region: Region,
+}
+
+impl RuntimeEnvironment {
+    // Virtual sensors and actuators need unique identities and the conditions of the
+    // descriptions may only refer to virtual sensors of the observer
+    pub fn new(
+        mut descriptions: Vec<Box<dyn PhenomenonDescription>>,
+        observer: Observer,
+        controller: Controller,
+        system_region: Region,
+    ) -> Result<RuntimeEnvironment, RteError> {
+        let sensors: Vec<&'static str> = observer
+            .get_virtual_sensors()
+            .iter()
+            .map(|s| s.get_identifier())
+            .collect();
+        if let Some(id) = find_duplicate(&sensors) {
+            return Err(RteError::DuplicateSensor(id));
+        }
+        let actuators: Vec<&'static str> = controller
+            .get_virtual_actuators()
+            .iter()
+            .map(|a| a.get_identifier())
+            .collect();
+        if let Some(id) = find_duplicate(&actuators) {
+            return Err(RteError::DuplicateActuator(id));
+        }
+        for description in &descriptions {
+            for condition in description.state() {
+                if !sensors.contains(&condition.sensor) {
+                    return Err(RteError::UnknownSensor {
+                        description: description.get_identifier(),
+                        sensor: condition.sensor,
+                    });
+                }
+            }
+        }
+        descriptions.sort_by_key(|d| d.priority());
+        Ok(RuntimeEnvironment {
+            descriptions,
+            observer,
+            controller,
+            system_region,
+        })
+    }
+
+    pub fn get_ordered_descriptions(&self) -> &Vec<Box<dyn PhenomenonDescription>> {
+        &self.descriptions
+    }
+
+    pub fn get_observer(&self) -> &Observer {
+        &self.observer
+    }
+
+    pub fn get_controller(&self) -> &Controller {
+        &self.controller
+    }
+
+    // Changes that could not be applied, with the identifier of the description asking for them
+    pub fn call_behavior(
+        &self,
+        description: &dyn PhenomenonDescription,
+        system: &mut System,
+        step_size: si::Second<f64>,
+    ) -> Vec<(&'static str, ActuationError)> {
+        let state_instance = if description.state().is_empty() {
+            StateInstance {
+                observations: Vec::new(),
+                region: self.system_region.clone(),
+            }
+        } else {
+            match self.observer.get_state_instance(description, system) {
+                Some(state_instance) => state_instance,
+                None => return Vec::new(),
+            }
+        };
+        let mut errors = Vec::new();
+        for change in description.behavior(&state_instance) {
+            if let Err(e) = self.controller.set_change(system, &change, step_size) {
+                errors.push((description.get_identifier(), e));
+            }
+        }
+        errors
+    }
+
+    pub fn call_behaviors(
+        &self,
+        system: &mut System,
+        step_size: si::Second<f64>,
+    ) -> Vec<(&'static str, ActuationError)> {
+        let mut errors = Vec::new();
+        for description in &self.descriptions {
+            errors.extend(self.call_behavior(description.as_ref(), system, step_size));
+        }
+        errors
+    }
+
+    // Calls the behaviors before every step
+    pub fn run(
+        &self,
+        system: &mut System,
+        step_size: si::Second<f64>,
+        steps: usize,
+    ) -> Vec<(&'static str, ActuationError)> {
+        let mut errors = Vec::new();
+        for _ in 0..steps {
+            errors.extend(self.call_behaviors(system, step_size));
+            system.next_state(step_size);
+        }
+        errors
+    }
+}
@@ -0,0 +1,204 @@
+use dimensioned::si;
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::{Actuator, Mounting};
+use crate::actuators::brake::Brake;
+use crate::actuators::thruster::Thruster;
+use crate::actuators::velocity_setter::VelocitySetter;
+use crate::scene::scene::SceneError;
+use crate::sensors::accelerometer::Accelerometer;
+use crate::sensors::contact_sensor::ContactSensor;
+use crate::sensors::light_sensor::LightSensor;
+use crate::sensors::position_sensor::PositionSensor;
+use crate::sensors::proximity_sensor::ProximitySensor;
+use crate::sensors::sensor::{is_valid_sampling_rate, Pose, Sensor};
+use crate::sensors::velocity_sensor::VelocitySensor;
+use crate::utils::identity::intern;
+
+// {"entity": "Ball"} or {"location": [x, y, z]}
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MountingDescription {
+    Entity(String),
+    Location([f64; 3]),
+}
+
+impl MountingDescription {
+    pub fn to_mounting(&self) -> Mounting {
+        match self {
+            MountingDescription::Entity(id) => Mounting::Entity(intern(id)),
+            MountingDescription::Location([x, y, z]) => {
+                Mounting::Location(Vector3d::new(*x, *y, *z) * si::M)
+            }
+        }
+    }
+
+    fn validate(&self, device: &str, entities: &[&str]) -> Result<(), SceneError> {
+        match self {
+            MountingDescription::Entity(id) if !entities.contains(&id.as_str()) => {
+                Err(SceneError::InvalidValue(format!(
+                    "{} is mounted on unknown entity {}.",
+                    device, id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_zero(direction: &[f64; 3]) -> bool {
+    direction.iter().all(|d| *d == 0.0) || direction.iter().any(|d| d.is_nan())
+}
+
+fn default_direction() -> [f64; 3] {
+    [1.0, 0.0, 0.0]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SensorModel {
+    Position,
+    Velocity,
+    Accelerometer,
+    Contact,
+    Light,
+    // Range in meters and half angle of the field of view in radians
+    Proximity { range: f64, half_angle: f64 },
+}
+
+// Sensor in SI units, the offset is relative to the mounting
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SensorDescription {
+    pub identity: String,
+    #[serde(flatten)]
+    pub model: SensorModel,
+    pub mounting: MountingDescription,
+    #[serde(default)]
+    pub offset: [f64; 3],
+    #[serde(default = "default_direction")]
+    pub direction: [f64; 3],
+    // Measurements per second
+    pub sampling_rate: f64,
+}
+
+impl SensorDescription {
+    pub fn validate(&self, entities: &[&str]) -> Result<(), SceneError> {
+        if !is_valid_sampling_rate(self.sampling_rate) {
+            return Err(SceneError::InvalidValue(format!(
+                "sampling rate of {} is not positive.",
+                self.identity
+            )));
+        }
+        if is_zero(&self.direction) {
+            return Err(SceneError::InvalidValue(format!(
+                "direction of {} has no length.",
+                self.identity
+            )));
+        }
+        if let SensorModel::Proximity { range, half_angle } = self.model {
+            if range.is_nan() || range < 0.0 || half_angle.is_nan() || half_angle < 0.0 {
+                return Err(SceneError::InvalidValue(format!(
+                    "range or angle of {} is negative.",
+                    self.identity
+                )));
+            }
+        }
+        self.mounting.validate(&self.identity, entities)
+    }
+
+    pub fn to_sensor(&self) -> Box<dyn Sensor> {
+        let identifier = intern(&self.identity);
+        let [x, y, z] = self.offset;
+        let [dx, dy, dz] = self.direction;
+        let pose = Pose {
+            offset: Vector3d::new(x, y, z) * si::M,
+            direction: Vector3d::new(dx, dy, dz),
+            ..Pose::new(self.mounting.to_mounting())
+        };
+        let rate = self.sampling_rate;
+        match self.model {
+            SensorModel::Position => Box::new(PositionSensor::new(identifier, pose, rate)),
+            SensorModel::Velocity => Box::new(VelocitySensor::new(identifier, pose, rate)),
+            SensorModel::Accelerometer => Box::new(Accelerometer::new(identifier, pose, rate)),
+            SensorModel::Contact => Box::new(ContactSensor::new(identifier, pose, rate)),
+            SensorModel::Light => Box::new(LightSensor::new(identifier, pose, rate)),
+            SensorModel::Proximity { range, half_angle } => Box::new(ProximitySensor::new(
+                identifier,
+                pose,
+                rate,
+                range * si::M,
+                half_angle,
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActuatorModel {
+    // Force at full signal in newtons
+    Thruster { max_force: [f64; 3] },
+    Brake { max_force: f64 },
+    VelocitySetter { direction: [f64; 3] },
+}
+
+// Actuator in SI units
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActuatorDescription {
+    pub identity: String,
+    #[serde(flatten)]
+    pub model: ActuatorModel,
+    pub mounting: MountingDescription,
+}
+
+impl ActuatorDescription {
+    pub fn validate(&self, entities: &[&str]) -> Result<(), SceneError> {
+        match self.model {
+            ActuatorModel::Thruster { max_force } if max_force.iter().any(|f| f.is_nan()) => {
+                return Err(SceneError::InvalidValue(format!(
+                    "force of {} is not a number.",
+                    self.identity
+                )))
+            }
+            ActuatorModel::Brake { max_force } if max_force.is_nan() || max_force < 0.0 => {
+                return Err(SceneError::InvalidValue(format!(
+                    "force of {} is negative.",
+                    self.identity
+                )))
+            }
+            ActuatorModel::VelocitySetter { direction } if is_zero(&direction) => {
+                return Err(SceneError::InvalidValue(format!(
+                    "direction of {} has no length.",
+                    self.identity
+                )))
+            }
+            _ => {}
+        }
+        self.mounting.validate(&self.identity, entities)
+    }
+
+    pub fn to_actuator(&self) -> Box<dyn Actuator> {
+        let identifier = intern(&self.identity);
+        let mounting = self.mounting.to_mounting();
+        match self.model {
+            ActuatorModel::Thruster {
+                max_force: [x, y, z],
+            } => Box::new(Thruster::new(
+                identifier,
+                mounting,
+                Vector3d::new(x, y, z) * si::N,
+            )),
+            ActuatorModel::Brake { max_force } => {
+                Box::new(Brake::new(identifier, mounting, max_force * si::N))
+            }
+            ActuatorModel::VelocitySetter {
+                direction: [x, y, z],
+            } => Box::new(VelocitySetter::new(
+                identifier,
+                mounting,
+                Vector3d::new(x, y, z),
+            )),
+        }
+    }
+}
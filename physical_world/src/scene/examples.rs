@@ -0,0 +1,36 @@
+use crate::scene::scene::{Scene, SceneError};
+
+// Scenarios of the integration tests, shipped with the crate as scene files
+pub const EXAMPLES: [(&str, &str); 5] = [
+    (
+        "force_propagation_1",
+        include_str!("../../scenes/force_propagation_1.json"),
+    ),
+    (
+        "multi_body_collisions",
+        include_str!("../../scenes/multi_body_collisions.json"),
+    ),
+    (
+        "newton_pendulum_1",
+        include_str!("../../scenes/newton_pendulum_1.json"),
+    ),
+    (
+        "simultaneous_collisions",
+        include_str!("../../scenes/simultaneous_collisions.json"),
+    ),
+    (
+        "wall_collisions",
+        include_str!("../../scenes/wall_collisions.json"),
+    ),
+];
+
+pub fn get_example_names() -> Vec<&'static str> {
+    EXAMPLES.iter().map(|(name, _)| *name).collect()
+}
+
+pub fn get_example(name: &str) -> Option<Result<Scene, SceneError>> {
+    EXAMPLES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, json)| Scene::from_json(json))
+}
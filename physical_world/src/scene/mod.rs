@@ -0,0 +1,4 @@
+pub mod devices;
+pub mod examples;
+#[allow(clippy::module_inception)]
+pub mod scene;
@@ -0,0 +1,454 @@
+use std::path::Path;
+
+use dimensioned::si;
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::interaction::contact_forces::ContactForces;
+use crate::physics::interaction::contact_solver::{ContactSolver, REPLACED_INTERACTIONS};
+use crate::physics::interaction::elastic_collision::ElasticCollision;
+use crate::physics::interaction::gravity::Gravity;
+use crate::physics::interaction::interaction::Interaction;
+use crate::physics::state::shape::{Cuboid, Shape, Sphere};
+use crate::physics::state::state::State;
+use crate::physics::system::System;
+use crate::scene::devices::{ActuatorDescription, SensorDescription};
+use crate::utils::identity::{intern, Identity};
+
+// Built in interactions with their default parameters
+pub fn get_builtin_interactions() -> Vec<Box<dyn Interaction>> {
+    vec![
+        Box::new(ContactForces::default()),
+        Box::new(ElasticCollision::default()),
+        Box::new(Gravity::default()),
+        Box::new(ContactSolver::default()),
+    ]
+}
+
+// Interactions of systems that do not name theirs
+pub fn get_default_interactions() -> Vec<Box<dyn Interaction>> {
+    vec![
+        Box::new(ContactForces::default()),
+        Box::new(ElasticCollision::default()),
+        Box::new(Gravity::default()),
+    ]
+}
+
+pub fn get_builtin_interaction(identifier: &str) -> Option<Box<dyn Interaction>> {
+    get_builtin_interactions()
+        .into_iter()
+        .find(|i| i.get_identifier() == identifier)
+}
+
+// Interactions have to be built in, and the contact solver replaces the interactions that
+// propagate contact forces
+pub fn check_interactions<S: AsRef<str>>(names: &[S]) -> Result<(), SceneError> {
+    for name in names {
+        if get_builtin_interaction(name.as_ref()).is_none() {
+            return Err(SceneError::UnknownInteraction(name.as_ref().to_string()));
+        }
+    }
+    let solver = ContactSolver::default().get_identifier();
+    if names.iter().any(|n| n.as_ref() == solver) {
+        if let Some(replaced) = names
+            .iter()
+            .find(|n| REPLACED_INTERACTIONS.contains(&n.as_ref()))
+        {
+            return Err(SceneError::InvalidValue(format!(
+                "{} cannot be combined with {}.",
+                solver,
+                replaced.as_ref()
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Contacts are detected with the shapes of the entities
+pub fn needs_shapes<S: AsRef<str>>(names: &[S]) -> bool {
+    let solver = ContactSolver::default().get_identifier();
+    names
+        .iter()
+        .any(|n| n.as_ref() == solver || REPLACED_INTERACTIONS.contains(&n.as_ref()))
+}
+
+// Mass and shape of an entity have to suit the interactions it is simulated with
+pub fn check_entity<S: AsRef<str>>(entity: &Entity, interactions: &[S]) -> Result<(), SceneError> {
+    let state = entity.get_state();
+    check_mass_and_shape(
+        entity.get_identity(),
+        state.get_mass().value_unsafe,
+        state.get_shape() != Shape::None,
+        interactions,
+    )
+}
+
+fn check_mass_and_shape<S: AsRef<str>>(
+    identity: &str,
+    mass: f64,
+    has_shape: bool,
+    interactions: &[S],
+) -> Result<(), SceneError> {
+    if !mass.is_finite() || mass <= 0.0 {
+        return Err(SceneError::InvalidValue(format!(
+            "mass of {} is not positive.",
+            identity
+        )));
+    }
+    if !has_shape && needs_shapes(interactions) {
+        return Err(SceneError::InvalidValue(format!(
+            "{} has no shape to detect contacts with.",
+            identity
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnknownInteraction(String),
+    DuplicateIdentity(String),
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "Cannot read scene: {}", e),
+            SceneError::Parse(e) => write!(f, "Cannot parse scene: {}", e),
+            SceneError::UnknownInteraction(name) => write!(f, "Unknown interaction {}.", name),
+            SceneError::DuplicateIdentity(id) => {
+                write!(f, "Identity {} is used more than once.", id)
+            }
+            SceneError::InvalidValue(msg) => write!(f, "Invalid scene: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(e: std::io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneError::Parse(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeDescription {
+    Sphere {
+        radius: f64,
+    },
+    Cuboid {
+        half_extents: [f64; 3],
+    },
+    #[default]
+    None,
+}
+
+// Entity in SI units
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntityDescription {
+    pub identity: String,
+    pub location: [f64; 3],
+    #[serde(default)]
+    pub velocity: [f64; 3],
+    #[serde(default)]
+    pub net_force: [f64; 3],
+    pub mass: f64,
+    #[serde(default)]
+    pub shape: ShapeDescription,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl EntityDescription {
+    // Mass and shape of the entity have to suit the interactions it is simulated with
+    pub fn validate<S: AsRef<str>>(&self, interactions: &[S]) -> Result<(), SceneError> {
+        check_mass_and_shape(
+            &self.identity,
+            self.mass,
+            self.shape != ShapeDescription::None,
+            interactions,
+        )
+    }
+
+    pub fn to_entity(&self) -> Entity {
+        let [x, y, z] = self.location;
+        let [vx, vy, vz] = self.velocity;
+        let [fx, fy, fz] = self.net_force;
+        let shape = match self.shape {
+            ShapeDescription::Sphere { radius } => Shape::Sphere(Sphere {
+                radius: radius * si::M,
+            }),
+            ShapeDescription::Cuboid { half_extents } => Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(half_extents[0], half_extents[1], half_extents[2])
+                    * si::M,
+            }),
+            ShapeDescription::None => Shape::None,
+        };
+        let mut entity = Entity::new(
+            intern(&self.identity),
+            State::new(
+                Vector3d::new(x, y, z) * si::M,
+                Vector3d::new(vx, vy, vz) * si::MPS,
+                Vector3d::new(fx, fy, fz) * si::N,
+                self.mass * si::KG,
+                shape,
+            ),
+        );
+        for tag in &self.tags {
+            entity.add_tag(intern(tag));
+        }
+        entity
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // Simulated time in seconds
+    pub duration: f64,
+    pub time_step: f64,
+    pub interactions: Vec<String>,
+    pub entities: Vec<EntityDescription>,
+    #[serde(default)]
+    pub sensors: Vec<SensorDescription>,
+    #[serde(default)]
+    pub actuators: Vec<ActuatorDescription>,
+}
+
+impl Scene {
+    pub fn from_json(json: &str) -> Result<Scene, SceneError> {
+        let scene: Scene = serde_json::from_str(json)?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Scene, SceneError> {
+        Scene::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn validate(&self) -> Result<(), SceneError> {
+        if !self.time_step.is_finite() || self.time_step <= 0.0 {
+            return Err(SceneError::InvalidValue(format!(
+                "time step {} is not positive and finite.",
+                self.time_step
+            )));
+        }
+        if !self.duration.is_finite() || self.duration < 0.0 {
+            return Err(SceneError::InvalidValue(format!(
+                "duration {} is negative or not finite.",
+                self.duration
+            )));
+        }
+        check_interactions(&self.interactions)?;
+        for (idx, entity) in self.entities.iter().enumerate() {
+            if self.entities[..idx]
+                .iter()
+                .any(|e| e.identity == entity.identity)
+            {
+                return Err(SceneError::DuplicateIdentity(entity.identity.clone()));
+            }
+            entity.validate(&self.interactions)?;
+        }
+        let entities: Vec<&str> = self.entities.iter().map(|e| e.identity.as_str()).collect();
+        for (idx, sensor) in self.sensors.iter().enumerate() {
+            if self.sensors[..idx]
+                .iter()
+                .any(|s| s.identity == sensor.identity)
+            {
+                return Err(SceneError::DuplicateIdentity(sensor.identity.clone()));
+            }
+            sensor.validate(&entities)?;
+        }
+        for (idx, actuator) in self.actuators.iter().enumerate() {
+            if self.actuators[..idx]
+                .iter()
+                .any(|a| a.identity == actuator.identity)
+            {
+                return Err(SceneError::DuplicateIdentity(actuator.identity.clone()));
+            }
+            actuator.validate(&entities)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_time_step(&self) -> si::Second<f64> {
+        self.time_step * si::S
+    }
+
+    pub fn get_duration(&self) -> si::Second<f64> {
+        self.duration * si::S
+    }
+
+    // Number of steps needed to cover the duration
+    pub fn get_step_count(&self) -> usize {
+        (self.duration / self.time_step).round() as usize
+    }
+
+    pub fn to_system(&self) -> Result<System, SceneError> {
+        self.validate()?;
+        let interactions = self
+            .interactions
+            .iter()
+            .filter_map(|name| get_builtin_interaction(name))
+            .collect();
+        let entities = self.entities.iter().map(|e| e.to_entity()).collect();
+        let mut system = System::new(entities, interactions, 0.0 * si::S);
+        for sensor in &self.sensors {
+            system.add_sensor(sensor.to_sensor());
+        }
+        for actuator in &self.actuators {
+            system.add_actuator(actuator.to_actuator());
+        }
+        Ok(system)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod scene {
+    use super::*;
+    use crate::utils::identity::Identity;
+
+    const SCENE: &str = r#"{
+        "name": "test",
+        "duration": 1.0,
+        "time_step": 0.1,
+        "interactions": ["contact force", "elastic collision"],
+        "entities": [
+            {"identity": "A", "location": [1.0, 2.0, 3.0], "mass": 2.0,
+             "shape": {"sphere": {"radius": 0.5}}},
+            {"identity": "B", "location": [0.0, 0.0, 0.0], "velocity": [1.0, 0.0, 0.0], "mass": 1.0,
+             "shape": {"cuboid": {"half_extents": [1.0, 2.0, 3.0]}}, "tags": ["obstacle"]}
+        ],
+        "sensors": [
+            {"identity": "gps", "kind": "position", "mounting": {"entity": "A"}, "sampling_rate": 10.0},
+            {"identity": "radar", "kind": "proximity", "range": 5.0, "half_angle": 0.5,
+             "mounting": {"location": [0.0, 0.0, 0.0]}, "direction": [0.0, 1.0, 0.0],
+             "sampling_rate": 10.0}
+        ],
+        "actuators": [
+            {"identity": "thruster", "kind": "thruster", "max_force": [1.0, 0.0, 0.0],
+             "mounting": {"entity": "B"}}
+        ]
+    }"#;
+
+    #[test]
+    fn parse_scene() {
+        let scene = Scene::from_json(SCENE).unwrap();
+        let system = scene.to_system().unwrap();
+        let entities = system.get_entities();
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].get_identity(), "A");
+        assert_eq!(
+            entities[0].get_state().get_shape(),
+            Shape::Sphere(Sphere {
+                radius: 0.5 * si::M
+            })
+        );
+        assert_eq!(
+            entities[1].get_state().get_shape(),
+            Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(1.0, 2.0, 3.0) * si::M
+            })
+        );
+        assert!(entities[0].get_tags().is_empty());
+        assert!(entities[1].has_tag("obstacle"));
+        let sensors = system.get_sensors();
+        assert_eq!(sensors.len(), 2);
+        assert_eq!(sensors[1].get_identifier(), "radar");
+        assert_eq!(
+            sensors[1].get_pose().direction,
+            Vector3d::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(system.get_actuators()[0].get_identifier(), "thruster");
+        assert_eq!(
+            entities[1].get_state().get_velocity(),
+            Vector3d::new(1.0, 0.0, 0.0) * si::MPS
+        );
+    }
+
+    #[test]
+    fn invalid_scenes() {
+        let unknown = SCENE.replace("\"elastic collision\"", "\"magnetism\"");
+        assert!(matches!(
+            Scene::from_json(&unknown),
+            Err(SceneError::UnknownInteraction(_))
+        ));
+        let duplicate = SCENE.replace("\"identity\": \"B\"", "\"identity\": \"A\"");
+        assert!(matches!(
+            Scene::from_json(&duplicate),
+            Err(SceneError::DuplicateIdentity(_))
+        ));
+        let massless = SCENE.replace("\"mass\": 1.0", "\"mass\": 0.0");
+        assert!(matches!(
+            Scene::from_json(&massless),
+            Err(SceneError::InvalidValue(_))
+        ));
+        // Point masses cannot be in contact, so contact interactions reject them
+        let shapeless = SCENE.replace(
+            "\"shape\": {\"cuboid\": {\"half_extents\": [1.0, 2.0, 3.0]}}, ",
+            "",
+        );
+        assert!(matches!(
+            Scene::from_json(&shapeless),
+            Err(SceneError::InvalidValue(_))
+        ));
+        let solver = shapeless.replace(
+            "\"contact force\", \"elastic collision\"",
+            "\"contact solver\"",
+        );
+        assert!(matches!(
+            Scene::from_json(&solver),
+            Err(SceneError::InvalidValue(_))
+        ));
+        let gravity = shapeless.replace("\"contact force\", \"elastic collision\"", "\"Gravity\"");
+        let system = Scene::from_json(&gravity).unwrap().to_system().unwrap();
+        assert_eq!(
+            system.get_entities()[1].get_state().get_shape(),
+            Shape::None
+        );
+        let unmounted = SCENE.replace("{\"entity\": \"B\"}", "{\"entity\": \"C\"}");
+        assert!(matches!(
+            Scene::from_json(&unmounted),
+            Err(SceneError::InvalidValue(_))
+        ));
+        let duplicate = SCENE.replace("\"radar\"", "\"gps\"");
+        assert!(matches!(
+            Scene::from_json(&duplicate),
+            Err(SceneError::DuplicateIdentity(_))
+        ));
+        let unknown = SCENE.replace("\"proximity\"", "\"camera\"");
+        assert!(matches!(
+            Scene::from_json(&unknown),
+            Err(SceneError::Parse(_))
+        ));
+        assert!(matches!(Scene::from_json("{"), Err(SceneError::Parse(_))));
+        // Scenes that would never end
+        let mut endless = Scene::from_json(SCENE).unwrap();
+        endless.duration = f64::INFINITY;
+        assert!(matches!(
+            endless.validate(),
+            Err(SceneError::InvalidValue(_))
+        ));
+        let mut frozen = Scene::from_json(SCENE).unwrap();
+        frozen.time_step = f64::INFINITY;
+        assert!(matches!(
+            frozen.validate(),
+            Err(SceneError::InvalidValue(_))
+        ));
+    }
+}
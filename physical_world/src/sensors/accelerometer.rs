@@ -0,0 +1,57 @@
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+
+// Acceleration of the entity the sensor is mounted on, from its net force and mass
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accelerometer {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+}
+
+impl Accelerometer {
+    pub fn new(identifier: &'static str, pose: Pose, sampling_rate: f64) -> Accelerometer {
+        check_sampling_rate(identifier, sampling_rate);
+        Accelerometer {
+            identifier,
+            pose,
+            sampling_rate,
+        }
+    }
+}
+
+impl Sensor for Accelerometer {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Acceleration
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        self.pose.get_location(world).map(MeasurementRegion::Point)
+    }
+
+    fn measure(&self, world: &[Entity], _light: &IlluminanceField) -> Option<Reading> {
+        self.pose.get_target(world).map(|target| {
+            let state = target.get_state();
+            Reading::Acceleration(state.get_net_force() / state.get_mass())
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::actuators::actuator::Mounting;
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::{are_touching, contains_location};
+use crate::physics::state::shape::Shape;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+use crate::utils::identity::Identity;
+
+// Entities touching the entity the sensor is mounted on. Mounted at a location, the sensor
+// reports the entities containing the location, like a pressure plate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactSensor {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+}
+
+impl ContactSensor {
+    pub fn new(identifier: &'static str, pose: Pose, sampling_rate: f64) -> ContactSensor {
+        check_sampling_rate(identifier, sampling_rate);
+        ContactSensor {
+            identifier,
+            pose,
+            sampling_rate,
+        }
+    }
+}
+
+impl Sensor for ContactSensor {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Contact
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        match self.pose.mounting {
+            Mounting::Entity(_) => self
+                .pose
+                .get_target(world)
+                .map(MeasurementRegion::of_entity),
+            Mounting::Location(_) => self.pose.get_location(world).map(MeasurementRegion::Point),
+        }
+    }
+
+    fn measure(&self, world: &[Entity], _light: &IlluminanceField) -> Option<Reading> {
+        let contacts = match self.pose.mounting {
+            Mounting::Entity(_) => {
+                let target = self.pose.get_target(world)?;
+                if target.get_state().get_shape() == Shape::None {
+                    Vec::new()
+                } else {
+                    world
+                        .iter()
+                        .filter(|e| {
+                            *e != target
+                                && e.get_state().get_shape() != Shape::None
+                                && are_touching(target, e)
+                        })
+                        .map(|e| e.get_identity())
+                        .collect()
+                }
+            }
+            Mounting::Location(_) => {
+                let location = self.pose.get_location(world)?;
+                world
+                    .iter()
+                    .filter(|e| contains_location(e, location))
+                    .map(|e| e.get_identity())
+                    .collect()
+            }
+        };
+        Some(Reading::Contacts(contacts))
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,216 @@
+use dimensioned::si;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use vector3d::Vector3d;
+
+use crate::sensors::sensor::Reading;
+
+// Deviation of simulated readings from the true values. Models are applied to every numeric
+// component of a reading, in the order in which they are listed for a sensor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorModel {
+    Gaussian { standard_deviation: f64 },
+    Bias(f64),
+    // Offset growing with the simulated time, per second
+    Drift { rate: f64 },
+    Quantisation { resolution: f64 },
+    Saturation { min: f64, max: f64 },
+    // Probability that a reading is lost
+    Dropout { probability: f64 },
+    // Probability that a component is off by the magnitude, in either direction
+    Outlier { probability: f64, magnitude: f64 },
+}
+
+// Standard normal distribution by the Box-Muller transform
+fn standard_normal(rng: &mut ChaCha8Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn map_values<F: FnMut(f64) -> f64>(reading: Reading, mut f: F) -> Reading {
+    let mut map_vector = |v: [f64; 3]| Vector3d::new(f(v[0]), f(v[1]), f(v[2]));
+    match reading {
+        Reading::Position(v) => Reading::Position(
+            map_vector([v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]) * si::M,
+        ),
+        Reading::Velocity(v) => Reading::Velocity(
+            map_vector([v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]) * si::MPS,
+        ),
+        Reading::Acceleration(v) => Reading::Acceleration(
+            map_vector([v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]) * si::MPS2,
+        ),
+        Reading::Distance(d) => Reading::Distance(d.map(|d| f(d.value_unsafe) * si::M)),
+        Reading::Contacts(contacts) => Reading::Contacts(contacts),
+        Reading::Illuminance(e) => Reading::Illuminance(f(e.value_unsafe) * si::LX),
+    }
+}
+
+impl ErrorModel {
+    // None if the reading is lost
+    pub fn apply(
+        &self,
+        reading: Reading,
+        time: si::Second<f64>,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<Reading> {
+        let reading = match *self {
+            ErrorModel::Gaussian { standard_deviation } => {
+                map_values(reading, |v| v + standard_deviation * standard_normal(rng))
+            }
+            ErrorModel::Bias(bias) => map_values(reading, |v| v + bias),
+            ErrorModel::Drift { rate } => map_values(reading, |v| v + rate * time.value_unsafe),
+            ErrorModel::Quantisation { resolution } => {
+                map_values(reading, |v| (v / resolution).round() * resolution)
+            }
+            ErrorModel::Saturation { min, max } => map_values(reading, |v| v.clamp(min, max)),
+            ErrorModel::Dropout { probability } => {
+                if rng.gen::<f64>() < probability {
+                    return None;
+                }
+                reading
+            }
+            ErrorModel::Outlier {
+                probability,
+                magnitude,
+            } => map_values(reading, |v| {
+                if rng.gen::<f64>() < probability {
+                    if rng.gen::<bool>() {
+                        v + magnitude
+                    } else {
+                        v - magnitude
+                    }
+                } else {
+                    v
+                }
+            }),
+        };
+        Some(reading)
+    }
+}
+
+pub fn apply_error_models(
+    models: &[ErrorModel],
+    reading: Reading,
+    time: si::Second<f64>,
+    rng: &mut ChaCha8Rng,
+) -> Option<Reading> {
+    models
+        .iter()
+        .try_fold(reading, |reading, model| model.apply(reading, time, rng))
+}
+
+// Expected deviation of a component from its true value at the time. Dropouts do not change
+// values, outliers and saturation are not taken into account.
+pub fn get_standard_deviation(models: &[ErrorModel], time: si::Second<f64>) -> f64 {
+    models
+        .iter()
+        .map(|model| match *model {
+            ErrorModel::Gaussian { standard_deviation } => standard_deviation.powi(2),
+            ErrorModel::Bias(bias) => bias.powi(2),
+            ErrorModel::Drift { rate } => (rate * time.value_unsafe).powi(2),
+            ErrorModel::Quantisation { resolution } => resolution.powi(2) / 12.0,
+            ErrorModel::Saturation { .. }
+            | ErrorModel::Dropout { .. }
+            | ErrorModel::Outlier { .. } => 0.0,
+        })
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod error_model_tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn distance(reading: Option<Reading>) -> f64 {
+        match reading {
+            Some(Reading::Distance(Some(d))) => d.value_unsafe,
+            reading => panic!("Unexpected reading {:?}", reading),
+        }
+    }
+
+    #[test]
+    fn deterministic_models() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let reading = Reading::Distance(Some(1.26 * si::M));
+        let time = 10.0 * si::S;
+        let models = [
+            ErrorModel::Bias(0.5),
+            ErrorModel::Drift { rate: 0.01 },
+            ErrorModel::Quantisation { resolution: 0.25 },
+        ];
+        // 1.26 + 0.5 + 0.1 = 1.86, quantised to 1.75
+        let result = apply_error_models(&models, reading.clone(), time, &mut rng);
+        assert!((distance(result) - 1.75).abs() < 1e-12);
+        assert!(
+            (get_standard_deviation(&models, time) - (0.25f64 + 0.01 + 0.0625 / 12.0).sqrt()).abs()
+                < 1e-12
+        );
+        let saturation = [ErrorModel::Saturation { min: 0.0, max: 1.0 }];
+        let result = apply_error_models(&saturation, reading, time, &mut rng);
+        assert_eq!(distance(result), 1.0);
+    }
+
+    #[test]
+    fn gaussian_noise() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let model = ErrorModel::Gaussian {
+            standard_deviation: 2.0,
+        };
+        let samples = (0..10000)
+            .map(|_| {
+                distance(model.apply(Reading::Distance(Some(5.0 * si::M)), 0.0 * si::S, &mut rng))
+            })
+            .collect::<Vec<f64>>();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 5.0).abs() < 0.1, "{}", mean);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1, "{}", variance.sqrt());
+    }
+
+    #[test]
+    fn dropouts_and_outliers() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let models = [
+            ErrorModel::Dropout { probability: 0.25 },
+            ErrorModel::Outlier {
+                probability: 0.1,
+                magnitude: 100.0,
+            },
+        ];
+        let results = (0..10000)
+            .map(|_| {
+                apply_error_models(
+                    &models,
+                    Reading::Distance(Some(0.0 * si::M)),
+                    0.0 * si::S,
+                    &mut rng,
+                )
+            })
+            .collect::<Vec<Option<Reading>>>();
+        let lost = results.iter().filter(|r| r.is_none()).count();
+        let outliers = results
+            .iter()
+            .flatten()
+            .filter(|r| **r != Reading::Distance(Some(0.0 * si::M)))
+            .count();
+        assert!((lost as f64 / 10000.0 - 0.25).abs() < 0.02, "{}", lost);
+        assert!(
+            (outliers as f64 / (10000 - lost) as f64 - 0.1).abs() < 0.02,
+            "{}",
+            outliers
+        );
+        // Contacts have no numeric components
+        let contacts = Reading::Contacts(vec!["A"]);
+        let outlier = [ErrorModel::Outlier {
+            probability: 1.0,
+            magnitude: 1.0,
+        }];
+        assert_eq!(
+            apply_error_models(&outlier, contacts.clone(), 0.0 * si::S, &mut rng),
+            Some(contacts)
+        );
+    }
+}
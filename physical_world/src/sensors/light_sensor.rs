@@ -0,0 +1,56 @@
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+
+// Illuminance at the mounting point, like a photodiode
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightSensor {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+}
+
+impl LightSensor {
+    pub fn new(identifier: &'static str, pose: Pose, sampling_rate: f64) -> LightSensor {
+        check_sampling_rate(identifier, sampling_rate);
+        LightSensor {
+            identifier,
+            pose,
+            sampling_rate,
+        }
+    }
+}
+
+impl Sensor for LightSensor {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Light
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        self.pose.get_location(world).map(MeasurementRegion::Point)
+    }
+
+    fn measure(&self, world: &[Entity], light: &IlluminanceField) -> Option<Reading> {
+        self.pose
+            .get_location(world)
+            .map(|location| Reading::Illuminance(light.get_illuminance(location)))
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,8 @@
+pub mod accelerometer;
+pub mod contact_sensor;
+pub mod error_model;
+pub mod light_sensor;
+pub mod position_sensor;
+pub mod proximity_sensor;
+pub mod sensor;
+pub mod velocity_sensor;
@@ -0,0 +1,54 @@
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+
+// Location of the mounting point, like a satellite navigation receiver
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionSensor {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+}
+
+impl PositionSensor {
+    pub fn new(identifier: &'static str, pose: Pose, sampling_rate: f64) -> PositionSensor {
+        check_sampling_rate(identifier, sampling_rate);
+        PositionSensor {
+            identifier,
+            pose,
+            sampling_rate,
+        }
+    }
+}
+
+impl Sensor for PositionSensor {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Position
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        self.pose.get_location(world).map(MeasurementRegion::Point)
+    }
+
+    fn measure(&self, world: &[Entity], _light: &IlluminanceField) -> Option<Reading> {
+        self.pose.get_location(world).map(Reading::Position)
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
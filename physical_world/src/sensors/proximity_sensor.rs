@@ -0,0 +1,144 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::Mounting;
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::physics::interaction::helpers::get_closest_point;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+use crate::space::raycast::{cast_ray, cast_segment, Ray, RayFilter};
+use crate::utils::identity::Identity;
+
+// Rays cast across the field of view, in rings around the facing direction
+const RINGS: usize = 8;
+const RAYS_PER_RING: usize = 16;
+
+// Distance to the closest surface within a cone in front of the sensor, entities only partly
+// inside of the cone count as well. The entity the sensor is mounted on is not detected, and
+// surfaces hidden behind other entities are not either.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProximitySensor {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+    range: si::Meter<f64>,
+    // Angle in radians between the facing direction and the edge of the field of view
+    half_angle: f64,
+}
+
+impl ProximitySensor {
+    pub fn new(
+        identifier: &'static str,
+        pose: Pose,
+        sampling_rate: f64,
+        range: si::Meter<f64>,
+        half_angle: f64,
+    ) -> ProximitySensor {
+        check_sampling_rate(identifier, sampling_rate);
+        ProximitySensor {
+            identifier,
+            pose,
+            sampling_rate,
+            range,
+            half_angle,
+        }
+    }
+
+    pub fn get_range(&self) -> si::Meter<f64> {
+        self.range
+    }
+
+    // Facing direction and rings of directions up to the edge of the field of view
+    fn get_view_directions(&self) -> Vec<Vector3d<f64>> {
+        let direction = self.pose.get_unit_direction();
+        let helper = if direction.x.abs() < 0.9 {
+            Vector3d::new(1.0, 0.0, 0.0)
+        } else {
+            Vector3d::new(0.0, 1.0, 0.0)
+        };
+        let u = helper.cross(direction);
+        let u = u / u.norm2().sqrt();
+        let v = direction.cross(u);
+        let mut directions = vec![direction];
+        for ring in 1..=RINGS {
+            let polar = self.half_angle * ring as f64 / RINGS as f64;
+            for ray in 0..RAYS_PER_RING {
+                let azimuth = 2.0 * std::f64::consts::PI * ray as f64 / RAYS_PER_RING as f64;
+                let sideways = u * azimuth.cos() + v * azimuth.sin();
+                directions.push(direction * polar.cos() + sideways * polar.sin());
+            }
+        }
+        directions
+    }
+}
+
+impl Sensor for ProximitySensor {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Proximity
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        self.pose
+            .get_location(world)
+            .map(|apex| MeasurementRegion::Cone {
+                apex,
+                direction: self.pose.get_unit_direction(),
+                range: self.range,
+                half_angle: self.half_angle,
+            })
+    }
+
+    fn measure(&self, world: &[Entity], _light: &IlluminanceField) -> Option<Reading> {
+        let region = self.get_measurement_region(world)?;
+        let apex = self.pose.get_location(world)?;
+        let filter = match self.pose.mounting {
+            Mounting::Entity(id) => RayFilter::new().excluding_entity(id),
+            Mounting::Location(_) => RayFilter::new(),
+        };
+        // Closest points are exact if they are visible, rays find entities that are only partly
+        // in view or partly hidden
+        let closest_points = world
+            .iter()
+            .filter(|e| filter.accepts(e))
+            .map(|e| (e, get_closest_point(e, apex)))
+            .filter(|(_, point)| region.contains(*point))
+            .filter(|(e, point)| {
+                let filter = filter.clone().excluding_entity(e.get_identity());
+                cast_segment(world, apex, *point, &filter).is_none()
+            })
+            .map(|(_, point)| (point - apex).norm2().sqrt());
+        let hits = self
+            .get_view_directions()
+            .into_iter()
+            .filter_map(|direction| {
+                cast_ray(world, &Ray::new(apex, direction), self.range, &filter)
+            })
+            .map(|hit| hit.distance);
+        let distance = closest_points.chain(hits).fold(
+            None,
+            |closest: Option<si::Meter<f64>>, d| match closest {
+                Some(c) if c <= d => Some(c),
+                _ => Some(d),
+            },
+        );
+        Some(Reading::Distance(distance))
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
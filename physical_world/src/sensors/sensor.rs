@@ -0,0 +1,228 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::actuators::actuator::Mounting;
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::physics::state::shape::Shape;
+
+// Where a sensor is mounted and where it faces. The offset is relative to the mounting, the
+// direction does not need to be normalised.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub mounting: Mounting,
+    pub offset: Vector3d<si::Meter<f64>>,
+    pub direction: Vector3d<f64>,
+}
+
+impl Pose {
+    pub fn new(mounting: Mounting) -> Pose {
+        Pose {
+            mounting,
+            offset: Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            direction: Vector3d::new(1.0, 0.0, 0.0),
+        }
+    }
+
+    // Sensors mounted at a location measure the first entity containing the location
+    pub fn get_target<'a>(&self, world: &'a [Entity]) -> Option<&'a Entity> {
+        self.mounting.get_targets(world).into_iter().next()
+    }
+
+    pub fn get_location(&self, world: &[Entity]) -> Option<Vector3d<si::Meter<f64>>> {
+        match self.mounting {
+            Mounting::Entity(_) => self
+                .get_target(world)
+                .map(|target| target.get_state().get_location() + self.offset),
+            Mounting::Location(location) => Some(location + self.offset),
+        }
+    }
+
+    pub fn get_unit_direction(&self) -> Vector3d<f64> {
+        let norm = self.direction.norm2().sqrt();
+        if norm == 0.0 {
+            panic!("Direction of pose has no length.");
+        }
+        self.direction / norm
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeasurementRegion {
+    Point(Vector3d<si::Meter<f64>>),
+    Sphere {
+        center: Vector3d<si::Meter<f64>>,
+        radius: si::Meter<f64>,
+    },
+    Cuboid {
+        center: Vector3d<si::Meter<f64>>,
+        half_extents: Vector3d<si::Meter<f64>>,
+    },
+    // Angle in radians between the axis and the mantle
+    Cone {
+        apex: Vector3d<si::Meter<f64>>,
+        direction: Vector3d<f64>,
+        range: si::Meter<f64>,
+        half_angle: f64,
+    },
+}
+
+impl MeasurementRegion {
+    // Region covered by the shape of the entity
+    pub fn of_entity(entity: &Entity) -> MeasurementRegion {
+        let center = entity.get_state().get_location();
+        match entity.get_state().get_shape() {
+            Shape::Sphere(s) => MeasurementRegion::Sphere {
+                center,
+                radius: s.radius,
+            },
+            Shape::Cuboid(c) => MeasurementRegion::Cuboid {
+                center,
+                half_extents: c.half_extents,
+            },
+            Shape::None => MeasurementRegion::Point(center),
+        }
+    }
+
+    pub fn contains(&self, location: Vector3d<si::Meter<f64>>) -> bool {
+        match *self {
+            MeasurementRegion::Point(point) => point == location,
+            MeasurementRegion::Sphere { center, radius } => {
+                (location - center).norm2() <= radius * radius
+            }
+            MeasurementRegion::Cuboid {
+                center,
+                half_extents,
+            } => {
+                let rel_location = location - center;
+                rel_location.x.value_unsafe.abs() <= half_extents.x.value_unsafe
+                    && rel_location.y.value_unsafe.abs() <= half_extents.y.value_unsafe
+                    && rel_location.z.value_unsafe.abs() <= half_extents.z.value_unsafe
+            }
+            MeasurementRegion::Cone {
+                apex,
+                direction,
+                range,
+                half_angle,
+            } => {
+                let rel_location = location - apex;
+                let distance = rel_location.norm2().sqrt();
+                if distance > range {
+                    return false;
+                }
+                if distance == 0.0 * si::M {
+                    return true;
+                }
+                let cos_angle = (rel_location.x.value_unsafe * direction.x
+                    + rel_location.y.value_unsafe * direction.y
+                    + rel_location.z.value_unsafe * direction.z)
+                    / (distance.value_unsafe * direction.norm2().sqrt());
+                cos_angle >= half_angle.cos()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reading {
+    Position(Vector3d<si::Meter<f64>>),
+    Velocity(Vector3d<si::MeterPerSecond<f64>>),
+    Acceleration(Vector3d<si::MeterPerSecond2<f64>>),
+    // None if nothing is within range
+    Distance(Option<si::Meter<f64>>),
+    Contacts(Vec<&'static str>),
+    Illuminance(si::Lux<f64>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub sensor_id: &'static str,
+    pub time: si::Second<f64>,
+    pub reading: Reading,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SensorKind {
+    Position,
+    Velocity,
+    Acceleration,
+    Proximity,
+    Contact,
+    Light,
+}
+
+pub trait Sensor {
+    fn get_identifier(&self) -> &'static str;
+    fn get_kind(&self) -> SensorKind;
+    fn get_pose(&self) -> Pose;
+    // Samples per second of simulated time
+    fn get_sampling_rate(&self) -> f64;
+    // None if the sensor is not mounted on an entity of the world
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion>;
+    // Called at the end of a step, while the influences of the step are still applied
+    fn measure(&self, world: &[Entity], light: &IlluminanceField) -> Option<Reading>;
+    fn clone_box(&self) -> Box<dyn Sensor>;
+}
+
+// Sampling rates are finite and positive
+pub fn is_valid_sampling_rate(sampling_rate: f64) -> bool {
+    sampling_rate.is_finite() && sampling_rate > 0.0
+}
+
+pub fn check_sampling_rate(identifier: &str, sampling_rate: f64) {
+    if !is_valid_sampling_rate(sampling_rate) {
+        panic!(
+            "Sampling rate of sensor {} has to be positive and finite.",
+            identifier
+        );
+    }
+}
+
+impl Clone for Box<dyn Sensor> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl std::fmt::Debug for dyn Sensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({:?}, {:?}, {} Hz)",
+            self.get_identifier(),
+            self.get_kind(),
+            self.get_pose(),
+            self.get_sampling_rate()
+        )
+    }
+}
+
+#[cfg(test)]
+mod sensor_tests {
+    use super::*;
+
+    #[test]
+    fn cone_contains() {
+        let cone = MeasurementRegion::Cone {
+            apex: Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            direction: Vector3d::new(2.0, 0.0, 0.0),
+            range: 10.0 * si::M,
+            half_angle: std::f64::consts::FRAC_PI_4,
+        };
+        assert!(cone.contains(Vector3d::new(0.0, 0.0, 0.0) * si::M));
+        assert!(cone.contains(Vector3d::new(5.0, 4.9, 0.0) * si::M));
+        assert!(!cone.contains(Vector3d::new(5.0, 5.1, 0.0) * si::M));
+        assert!(!cone.contains(Vector3d::new(-1.0, 0.0, 0.0) * si::M));
+        assert!(!cone.contains(Vector3d::new(10.1, 0.0, 0.0) * si::M));
+    }
+
+    #[test]
+    fn cuboid_contains() {
+        let cuboid = MeasurementRegion::Cuboid {
+            center: Vector3d::new(1.0, 1.0, 1.0) * si::M,
+            half_extents: Vector3d::new(1.0, 2.0, 3.0) * si::M,
+        };
+        assert!(cuboid.contains(Vector3d::new(2.0, -1.0, 4.0) * si::M));
+        assert!(!cuboid.contains(Vector3d::new(2.0, -1.1, 4.0) * si::M));
+    }
+}
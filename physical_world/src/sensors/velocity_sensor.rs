@@ -0,0 +1,56 @@
+use crate::lighting::illuminance_field::IlluminanceField;
+use crate::physics::entity::Entity;
+use crate::sensors::sensor::{
+    check_sampling_rate, MeasurementRegion, Pose, Reading, Sensor, SensorKind,
+};
+
+// Velocity of the entity the sensor is mounted on
+#[derive(Clone, Debug, PartialEq)]
+pub struct VelocitySensor {
+    identifier: &'static str,
+    pose: Pose,
+    sampling_rate: f64,
+}
+
+impl VelocitySensor {
+    pub fn new(identifier: &'static str, pose: Pose, sampling_rate: f64) -> VelocitySensor {
+        check_sampling_rate(identifier, sampling_rate);
+        VelocitySensor {
+            identifier,
+            pose,
+            sampling_rate,
+        }
+    }
+}
+
+impl Sensor for VelocitySensor {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_kind(&self) -> SensorKind {
+        SensorKind::Velocity
+    }
+
+    fn get_pose(&self) -> Pose {
+        self.pose
+    }
+
+    fn get_sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    fn get_measurement_region(&self, world: &[Entity]) -> Option<MeasurementRegion> {
+        self.pose.get_location(world).map(MeasurementRegion::Point)
+    }
+
+    fn measure(&self, world: &[Entity], _light: &IlluminanceField) -> Option<Reading> {
+        self.pose
+            .get_target(world)
+            .map(|target| Reading::Velocity(target.get_state().get_velocity()))
+    }
+
+    fn clone_box(&self) -> Box<dyn Sensor> {
+        Box::new(self.clone())
+    }
+}
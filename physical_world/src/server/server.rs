@@ -0,0 +1,89 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+
+use log::{info, warn};
+use serde_json::{json, Value};
+
+use crate::server::session::{RpcError, Session, INVALID_REQUEST, PARSE_ERROR};
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+// Answers one JSON-RPC 2.0 request. Notifications, requests without an id, get no response.
+pub fn handle_request(session: &mut Session, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            ))
+        }
+    };
+    let id = request.get("id").cloned();
+    let method = match request.get("method").and_then(|m| m.as_str()) {
+        Some(method) if request.get("jsonrpc") == Some(&json!("2.0")) => method,
+        _ => {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(
+                    INVALID_REQUEST,
+                    "Expected a JSON-RPC 2.0 request.".to_string(),
+                ),
+            ))
+        }
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+    let result = session.call(method, &params);
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+// Newline-delimited JSON: one request per line, one response per line. Returns when the input
+// ends or the session is shut down.
+pub fn serve_connection<R: BufRead, W: Write>(
+    session: &mut Session,
+    reader: R,
+    mut writer: W,
+) -> std::io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_request(session, &line) {
+            writeln!(writer, "{}", response)?;
+            writer.flush()?;
+        }
+        if session.is_shut_down() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Serves one client at a time, every connection starts a new session
+pub fn serve(listener: TcpListener) -> std::io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        info!("Client {} connected.", stream.peer_addr()?);
+        let mut session = Session::new();
+        if let Err(e) = serve_connection(&mut session, BufReader::new(stream.try_clone()?), stream)
+        {
+            warn!("Connection failed: {}", e);
+        }
+        if session.is_shut_down() {
+            info!("Shutting down.");
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,554 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use dimensioned::si;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::system::System;
+use crate::prediction::action::Action;
+use crate::prediction::predictor::{EventKind, Predictor};
+use crate::scene::examples::get_example;
+use crate::scene::scene::{EntityDescription, Scene};
+use crate::sensors::sensor::{Measurement, Reading};
+use crate::utils::identity::{intern, Identity};
+
+// Error codes of JSON-RPC 2.0
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+// Valid requests the current state of the session cannot serve
+pub const SESSION_ERROR: i64 = -32000;
+
+// Steps a single request may simulate, the server serves one request at a time
+pub const MAX_STEPS: usize = 100_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: String) -> RpcError {
+        RpcError { code, message }
+    }
+
+    fn invalid_params(message: String) -> RpcError {
+        RpcError::new(INVALID_PARAMS, message)
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+// A panic of the simulation is reported to the client instead of ending the server
+fn guard<T, F: FnOnce() -> T>(f: F) -> Result<T, RpcError> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(message), _) => message.to_string(),
+            (None, Some(message)) => message.clone(),
+            (None, None) => "unknown panic".to_string(),
+        };
+        RpcError::new(SESSION_ERROR, format!("Simulation failed: {}", message))
+    })
+}
+
+fn parse_params<'de, T: Deserialize<'de>>(params: &'de Value) -> Result<T, RpcError> {
+    T::deserialize(params).map_err(|e| RpcError::invalid_params(e.to_string()))
+}
+
+fn vector<U>(v: Vector3d<si::SI<f64, U>>) -> Value {
+    json!([v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe])
+}
+
+fn entity_to_json(entity: &Entity) -> Value {
+    let state = entity.get_state();
+    json!({
+        "identity": entity.get_identity(),
+        "location": vector(state.get_location()),
+        "velocity": vector(state.get_velocity()),
+        "mass": state.get_mass().value_unsafe,
+        "tags": entity.get_tags(),
+    })
+}
+
+// {"position": [x, y, z]}, {"distance": null}, {"contacts": ["A"]}, ...
+pub fn reading_to_json(reading: &Reading) -> Value {
+    match reading {
+        Reading::Position(p) => json!({ "position": vector(*p) }),
+        Reading::Velocity(v) => json!({ "velocity": vector(*v) }),
+        Reading::Acceleration(a) => json!({ "acceleration": vector(*a) }),
+        Reading::Distance(d) => json!({ "distance": d.map(|d| d.value_unsafe) }),
+        Reading::Contacts(contacts) => json!({ "contacts": contacts }),
+        Reading::Illuminance(e) => json!({ "illuminance": e.value_unsafe }),
+    }
+}
+
+fn measurement_to_json(measurement: &Measurement) -> Value {
+    json!({
+        "sensor": measurement.sensor_id,
+        "time": measurement.time.value_unsafe,
+        "reading": reading_to_json(&measurement.reading),
+    })
+}
+
+// Exactly one of the fields selects the scene
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoadSceneParams {
+    example: Option<String>,
+    path: Option<String>,
+    scene: Option<Scene>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StepParams {
+    #[serde(default = "one")]
+    steps: usize,
+    time_step: Option<f64>,
+}
+
+fn one() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InputSignalParams {
+    actuator: String,
+    signal: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ActuatorParams {
+    actuator: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MeasurementParams {
+    sensors: Option<Vec<String>>,
+}
+
+// Hypothetical actions in SI units, times are relative to the start of the prediction
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum ActionDescription {
+    ApplyForce {
+        entity: String,
+        force: [f64; 3],
+        start: f64,
+        duration: f64,
+    },
+    ChangeVelocity {
+        entity: String,
+        velocity_change: [f64; 3],
+        at: f64,
+    },
+    Spawn {
+        entity: EntityDescription,
+        at: f64,
+    },
+    SetInputSignal {
+        actuator: String,
+        signal: f64,
+        at: f64,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PredictParams {
+    horizon: f64,
+    step_size: Option<f64>,
+    #[serde(default)]
+    actions: Vec<ActionDescription>,
+}
+
+// State of one client: the loaded scene and the system simulating it
+#[derive(Default)]
+pub struct Session {
+    scene: Option<Scene>,
+    system: Option<System>,
+    shut_down: bool,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    pub fn get_system(&self) -> Option<&System> {
+        self.system.as_ref()
+    }
+
+    // Whether a client asked the server to stop
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    pub fn call(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "load_scene" => self.load_scene(parse_params(params)?),
+            "step" => self.step(parse_params(params)?),
+            "get_time" => Ok(json!(self.system()?.get_current_time().value_unsafe)),
+            "get_entities" => self.get_entities(),
+            "get_actuators" => self.get_actuators(),
+            "set_input_signal" => self.set_input_signal(parse_params(params)?),
+            "release_actuator" => self.release_actuator(parse_params(params)?),
+            "get_measurements" => self.get_measurements(parse_params(params)?),
+            "predict" => self.predict(parse_params(params)?),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method {}.", method),
+            )),
+        }
+    }
+
+    fn system(&self) -> Result<&System, RpcError> {
+        self.system
+            .as_ref()
+            .ok_or_else(|| RpcError::new(SESSION_ERROR, "No scene is loaded.".to_string()))
+    }
+
+    fn system_mut(&mut self) -> Result<&mut System, RpcError> {
+        self.system
+            .as_mut()
+            .ok_or_else(|| RpcError::new(SESSION_ERROR, "No scene is loaded.".to_string()))
+    }
+
+    fn has_actuator(&self, id: &str) -> Result<bool, RpcError> {
+        Ok(self
+            .system()?
+            .get_actuators()
+            .iter()
+            .any(|a| a.get_identifier() == id))
+    }
+
+    fn load_scene(&mut self, params: LoadSceneParams) -> Result<Value, RpcError> {
+        let scene = match (params.example, params.path, params.scene) {
+            (Some(name), None, None) => match get_example(&name) {
+                Some(example) => example,
+                None => {
+                    return Err(RpcError::invalid_params(format!(
+                        "No example named {}.",
+                        name
+                    )))
+                }
+            },
+            (None, Some(path), None) => Scene::load(path),
+            (None, None, Some(scene)) => scene.validate().map(|_| scene),
+            _ => {
+                return Err(RpcError::invalid_params(
+                    "Expected one of example, path or scene.".to_string(),
+                ))
+            }
+        }
+        .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+        let system = scene
+            .to_system()
+            .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+        let result = json!({
+            "name": scene.name,
+            "time_step": scene.time_step,
+            "duration": scene.duration,
+            "entities": system.get_entities().iter().map(|e| e.get_identity()).collect::<Vec<_>>(),
+            "sensors": system.get_sensors().iter().map(|s| s.get_identifier()).collect::<Vec<_>>(),
+            "actuators": system.get_actuators().iter().map(|a| a.get_identifier()).collect::<Vec<_>>(),
+        });
+        self.scene = Some(scene);
+        self.system = Some(system);
+        Ok(result)
+    }
+
+    fn get_time_step(&self, time_step: Option<f64>) -> Result<si::Second<f64>, RpcError> {
+        let time_step = match (time_step, &self.scene) {
+            (Some(time_step), _) => time_step,
+            (None, Some(scene)) => scene.time_step,
+            (None, None) => {
+                return Err(RpcError::new(
+                    SESSION_ERROR,
+                    "No scene is loaded.".to_string(),
+                ))
+            }
+        };
+        if !time_step.is_finite() || time_step <= 0.0 {
+            return Err(RpcError::invalid_params(format!(
+                "Time step {} is not positive and finite.",
+                time_step
+            )));
+        }
+        Ok(time_step * si::S)
+    }
+
+    fn check_steps(steps: f64) -> Result<(), RpcError> {
+        if steps > MAX_STEPS as f64 {
+            return Err(RpcError::invalid_params(format!(
+                "{} steps are more than the {} a request may simulate.",
+                steps, MAX_STEPS
+            )));
+        }
+        Ok(())
+    }
+
+    // A system that panicked may be inconsistent, so the scene has to be loaded again
+    fn step(&mut self, params: StepParams) -> Result<Value, RpcError> {
+        let time_step = self.get_time_step(params.time_step)?;
+        Session::check_steps(params.steps as f64)?;
+        let system = self.system_mut()?;
+        let stepped = guard(|| {
+            for _ in 0..params.steps {
+                system.next_state(time_step);
+            }
+            system.get_current_time()
+        });
+        match stepped {
+            Ok(time) => Ok(json!({ "time": time.value_unsafe })),
+            Err(e) => {
+                self.scene = None;
+                self.system = None;
+                Err(e)
+            }
+        }
+    }
+
+    fn get_entities(&self) -> Result<Value, RpcError> {
+        let system = self.system()?;
+        Ok(Value::Array(
+            system.get_entities().iter().map(entity_to_json).collect(),
+        ))
+    }
+
+    fn get_actuators(&self) -> Result<Value, RpcError> {
+        let system = self.system()?;
+        Ok(Value::Array(
+            system
+                .get_actuators()
+                .iter()
+                .map(|a| json!({ "identity": a.get_identifier(), "signal": a.get_input_signal() }))
+                .collect(),
+        ))
+    }
+
+    fn set_input_signal(&mut self, params: InputSignalParams) -> Result<Value, RpcError> {
+        if !self.has_actuator(&params.actuator)? {
+            return Err(RpcError::invalid_params(format!(
+                "Actuator {} not found.",
+                params.actuator
+            )));
+        }
+        if !params.signal.is_finite() {
+            return Err(RpcError::invalid_params(format!(
+                "Signal {} is not finite.",
+                params.signal
+            )));
+        }
+        self.system_mut()?
+            .set_input_signal(&params.actuator, params.signal);
+        Ok(Value::Null)
+    }
+
+    // Signals of released velocity setters are reported as null
+    fn release_actuator(&mut self, params: ActuatorParams) -> Result<Value, RpcError> {
+        if !self.has_actuator(&params.actuator)? {
+            return Err(RpcError::invalid_params(format!(
+                "Actuator {} not found.",
+                params.actuator
+            )));
+        }
+        self.system_mut()?.release_actuator(&params.actuator);
+        Ok(Value::Null)
+    }
+
+    // Latest measurement of every sensor, or of the selected ones, sensors without one are skipped
+    fn get_measurements(&self, params: MeasurementParams) -> Result<Value, RpcError> {
+        let system = self.system()?;
+        let sensor_ids: Vec<&str> = match &params.sensors {
+            Some(ids) => ids.iter().map(|id| id.as_str()).collect(),
+            None => system
+                .get_sensors()
+                .iter()
+                .map(|s| s.get_identifier())
+                .collect(),
+        };
+        let mut measurements = Vec::new();
+        for id in sensor_ids {
+            if !system
+                .get_sensors()
+                .iter()
+                .any(|s| s.get_identifier() == id)
+            {
+                return Err(RpcError::invalid_params(format!(
+                    "Sensor {} not found.",
+                    id
+                )));
+            }
+            if let Some(measurement) = system.get_latest_measurement(id) {
+                measurements.push(measurement_to_json(measurement));
+            }
+        }
+        Ok(Value::Array(measurements))
+    }
+
+    // Actions refer to the entities of the system and the ones spawned by earlier actions
+    fn to_action(
+        &self,
+        description: ActionDescription,
+        entities: &mut Vec<&'static str>,
+    ) -> Result<Action, RpcError> {
+        let action = match description {
+            ActionDescription::ApplyForce {
+                entity,
+                force: [x, y, z],
+                start,
+                duration,
+            } => {
+                if !entities.contains(&entity.as_str()) {
+                    return Err(RpcError::invalid_params(format!(
+                        "Entity {} not found.",
+                        entity
+                    )));
+                }
+                Action::ApplyForce {
+                    entity: intern(&entity),
+                    force: Vector3d::new(x, y, z) * si::N,
+                    start: start * si::S,
+                    duration: duration * si::S,
+                }
+            }
+            ActionDescription::ChangeVelocity {
+                entity,
+                velocity_change: [x, y, z],
+                at,
+            } => {
+                if !entities.contains(&entity.as_str()) {
+                    return Err(RpcError::invalid_params(format!(
+                        "Entity {} not found.",
+                        entity
+                    )));
+                }
+                Action::ChangeVelocity {
+                    entity: intern(&entity),
+                    velocity_change: Vector3d::new(x, y, z) * si::MPS,
+                    at: at * si::S,
+                }
+            }
+            ActionDescription::Spawn { entity, at } => {
+                if entities.contains(&entity.identity.as_str()) {
+                    return Err(RpcError::invalid_params(format!(
+                        "Entity {} already exists.",
+                        entity.identity
+                    )));
+                }
+                let interactions: Vec<&str> = self
+                    .system()?
+                    .get_interactions()
+                    .iter()
+                    .map(|i| i.get_identifier())
+                    .collect();
+                entity
+                    .validate(&interactions)
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+                entities.push(intern(&entity.identity));
+                Action::Spawn {
+                    entity: entity.to_entity(),
+                    at: at * si::S,
+                }
+            }
+            ActionDescription::SetInputSignal {
+                actuator,
+                signal,
+                at,
+            } => {
+                if !self.has_actuator(&actuator)? {
+                    return Err(RpcError::invalid_params(format!(
+                        "Actuator {} not found.",
+                        actuator
+                    )));
+                }
+                Action::SetInputSignal {
+                    actuator: intern(&actuator),
+                    signal,
+                    at: at * si::S,
+                }
+            }
+        };
+        Ok(action)
+    }
+
+    // Simulates a fork of the system, the system of the session is not modified
+    fn predict(&self, params: PredictParams) -> Result<Value, RpcError> {
+        let step_size = self.get_time_step(params.step_size)?;
+        if !params.horizon.is_finite() || params.horizon < 0.0 {
+            return Err(RpcError::invalid_params(format!(
+                "Horizon {} is negative or not finite.",
+                params.horizon
+            )));
+        }
+        Session::check_steps((params.horizon / step_size.value_unsafe).round())?;
+        let mut entities: Vec<&'static str> = self
+            .system()?
+            .get_entities()
+            .iter()
+            .map(|e| e.get_identity())
+            .collect();
+        let actions = params
+            .actions
+            .into_iter()
+            .map(|a| self.to_action(a, &mut entities))
+            .collect::<Result<Vec<Action>, RpcError>>()?;
+        let system = self.system()?;
+        let outcome =
+            guard(|| Predictor::new(params.horizon * si::S, step_size).predict(system, &actions))?
+                .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+        let events: Vec<Value> = outcome
+            .events
+            .iter()
+            .map(|event| {
+                let (kind, entities) = match event.kind {
+                    EventKind::ContactStarted(a, b) => ("contact_started", vec![a, b]),
+                    EventKind::ContactEnded(a, b) => ("contact_ended", vec![a, b]),
+                    EventKind::Spawned(a) => ("spawned", vec![a]),
+                };
+                json!({ "time": event.time.value_unsafe, "kind": kind, "entities": entities })
+            })
+            .collect();
+        let states: Vec<Value> = outcome
+            .final_states
+            .iter()
+            .map(|(identity, state)| {
+                json!({
+                    "identity": identity,
+                    "location": vector(state.get_location()),
+                    "velocity": vector(state.get_velocity()),
+                    "mass": state.get_mass().value_unsafe,
+                })
+            })
+            .collect();
+        Ok(json!({
+            "end_time": outcome.end_time.value_unsafe,
+            "entities": states,
+            "contacts": outcome.contacts,
+            "events": events,
+            "energy": outcome.energy.value_unsafe,
+            "momentum": vector(outcome.momentum),
+        }))
+    }
+}
@@ -0,0 +1,3 @@
+pub mod occupancy_grid;
+pub mod raycast;
+pub mod region;
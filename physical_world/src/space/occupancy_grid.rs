@@ -0,0 +1,213 @@
+use std::collections::VecDeque;
+
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::space::region::Bounds;
+
+// Cubic cells of equal size, starting at the origin corner and extending along the positive axes
+#[derive(Clone, Debug, PartialEq)]
+pub struct OccupancyGrid {
+    origin: Vector3d<si::Meter<f64>>,
+    cell_size: si::Meter<f64>,
+    dimensions: [usize; 3],
+    cells: Vec<bool>,
+}
+
+impl OccupancyGrid {
+    pub fn new(
+        origin: Vector3d<si::Meter<f64>>,
+        cell_size: si::Meter<f64>,
+        dimensions: [usize; 3],
+    ) -> OccupancyGrid {
+        if cell_size.value_unsafe.is_nan() || cell_size <= 0.0 * si::M {
+            panic!("Cell size of occupancy grid has to be positive.");
+        }
+        OccupancyGrid {
+            origin,
+            cell_size,
+            dimensions,
+            cells: vec![false; dimensions[0] * dimensions[1] * dimensions[2]],
+        }
+    }
+
+    pub fn get_origin(&self) -> Vector3d<si::Meter<f64>> {
+        self.origin
+    }
+
+    pub fn get_cell_size(&self) -> si::Meter<f64> {
+        self.cell_size
+    }
+
+    pub fn get_dimensions(&self) -> [usize; 3] {
+        self.dimensions
+    }
+
+    fn get_offset(&self, cell: [usize; 3]) -> usize {
+        (cell[2] * self.dimensions[1] + cell[1]) * self.dimensions[0] + cell[0]
+    }
+
+    pub fn is_occupied(&self, cell: [usize; 3]) -> bool {
+        self.cells[self.get_offset(cell)]
+    }
+
+    pub fn set_occupied(&mut self, cell: [usize; 3], occupied: bool) {
+        let offset = self.get_offset(cell);
+        self.cells[offset] = occupied;
+    }
+
+    // None if the location is outside of the grid
+    pub fn get_cell(&self, location: Vector3d<si::Meter<f64>>) -> Option<[usize; 3]> {
+        let rel_location = (location - self.origin) / self.cell_size;
+        let mut cell = [0; 3];
+        for (c, (r, d)) in cell.iter_mut().zip(
+            [rel_location.x, rel_location.y, rel_location.z]
+                .iter()
+                .zip(self.dimensions.iter()),
+        ) {
+            let r = r.value_unsafe;
+            if !(r >= 0.0 && r < *d as f64) {
+                return None;
+            }
+            *c = r as usize;
+        }
+        Some(cell)
+    }
+
+    pub fn get_cell_center(&self, cell: [usize; 3]) -> Vector3d<si::Meter<f64>> {
+        self.origin
+            + Vector3d::new(
+                cell[0] as f64 + 0.5,
+                cell[1] as f64 + 0.5,
+                cell[2] as f64 + 0.5,
+            ) * self.cell_size
+    }
+
+    pub fn contains(&self, location: Vector3d<si::Meter<f64>>) -> bool {
+        self.get_cell(location)
+            .map(|cell| self.is_occupied(cell))
+            .unwrap_or(false)
+    }
+
+    pub fn get_occupied_cells(&self) -> Vec<[usize; 3]> {
+        let mut cells = Vec::new();
+        for z in 0..self.dimensions[2] {
+            for y in 0..self.dimensions[1] {
+                for x in 0..self.dimensions[0] {
+                    if self.is_occupied([x, y, z]) {
+                        cells.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    pub fn get_volume(&self) -> si::Meter3<f64> {
+        self.cells.iter().filter(|c| **c).count() as f64
+            * self.cell_size
+            * self.cell_size
+            * self.cell_size
+    }
+
+    // Corners of the box around the occupied cells, None if no cell is occupied
+    pub fn get_bounds(&self) -> Option<Bounds> {
+        let cells = self.get_occupied_cells();
+        let first = cells.first()?;
+        let (mut min, mut max) = (*first, *first);
+        for cell in &cells {
+            for i in 0..3 {
+                min[i] = min[i].min(cell[i]);
+                max[i] = max[i].max(cell[i]);
+            }
+        }
+        let corner = |c: [usize; 3]| {
+            self.origin + Vector3d::new(c[0] as f64, c[1] as f64, c[2] as f64) * self.cell_size
+        };
+        Some((corner(min), corner([max[0] + 1, max[1] + 1, max[2] + 1])))
+    }
+
+    fn get_neighbors(&self, cell: [usize; 3]) -> Vec<[usize; 3]> {
+        let mut neighbors = Vec::new();
+        for i in 0..3 {
+            if cell[i] > 0 {
+                let mut neighbor = cell;
+                neighbor[i] -= 1;
+                neighbors.push(neighbor);
+            }
+            if cell[i] + 1 < self.dimensions[i] {
+                let mut neighbor = cell;
+                neighbor[i] += 1;
+                neighbors.push(neighbor);
+            }
+        }
+        neighbors
+    }
+
+    // Groups of occupied cells that share faces, each in a grid of the same extent
+    pub fn get_components(&self) -> Vec<OccupancyGrid> {
+        let mut visited = vec![false; self.cells.len()];
+        let mut components = Vec::new();
+        for start in self.get_occupied_cells() {
+            if visited[self.get_offset(start)] {
+                continue;
+            }
+            let mut component = OccupancyGrid::new(self.origin, self.cell_size, self.dimensions);
+            let mut queue = VecDeque::from([start]);
+            visited[self.get_offset(start)] = true;
+            while let Some(cell) = queue.pop_front() {
+                component.set_occupied(cell, true);
+                for neighbor in self.get_neighbors(cell) {
+                    let offset = self.get_offset(neighbor);
+                    if self.cells[offset] && !visited[offset] {
+                        visited[offset] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod occupancy_grid_tests {
+    use super::*;
+
+    #[test]
+    fn components() {
+        let mut grid =
+            OccupancyGrid::new(Vector3d::new(0.0, 0.0, 0.0) * si::M, 1.0 * si::M, [4, 3, 1]);
+        grid.set_occupied([0, 0, 0], true);
+        grid.set_occupied([1, 0, 0], true);
+        grid.set_occupied([1, 1, 0], true);
+        // Touching only at an edge
+        grid.set_occupied([2, 2, 0], true);
+        grid.set_occupied([3, 2, 0], true);
+        let components = grid.get_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].get_volume(), 3.0 * si::M3);
+        assert_eq!(components[1].get_volume(), 2.0 * si::M3);
+        assert!(components[1].contains(Vector3d::new(3.5, 2.5, 0.5) * si::M));
+        assert!(!components[1].contains(Vector3d::new(0.5, 0.5, 0.5) * si::M));
+    }
+
+    #[test]
+    fn cells() {
+        let grid = OccupancyGrid::new(
+            Vector3d::new(-1.0, -1.0, -1.0) * si::M,
+            0.5 * si::M,
+            [4, 4, 4],
+        );
+        assert_eq!(
+            grid.get_cell(Vector3d::new(0.1, -0.9, 0.99) * si::M),
+            Some([2, 0, 3])
+        );
+        assert_eq!(grid.get_cell(Vector3d::new(1.0, 0.0, 0.0) * si::M), None);
+        assert_eq!(
+            grid.get_cell_center([2, 0, 3]),
+            Vector3d::new(0.25, -0.75, 0.75) * si::M
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::state::shape::Shape;
+use crate::utils::identity::Identity;
+
+// Half-line starting at the origin, the direction is normalised
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    origin: Vector3d<si::Meter<f64>>,
+    direction: Vector3d<f64>,
+}
+
+impl Ray {
+    pub fn new(origin: Vector3d<si::Meter<f64>>, direction: Vector3d<f64>) -> Ray {
+        let norm = direction.norm2().sqrt();
+        if norm.is_nan() || norm == 0.0 {
+            panic!("Direction of ray has to be non-zero.");
+        }
+        Ray {
+            origin,
+            direction: direction / norm,
+        }
+    }
+
+    pub fn get_origin(&self) -> Vector3d<si::Meter<f64>> {
+        self.origin
+    }
+
+    pub fn get_direction(&self) -> Vector3d<f64> {
+        self.direction
+    }
+
+    pub fn get_point(&self, distance: si::Meter<f64>) -> Vector3d<si::Meter<f64>> {
+        self.origin + self.direction * distance
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub entity: &'static str,
+    pub point: Vector3d<si::Meter<f64>>,
+    // Outward unit normal of the surface at the point
+    pub normal: Vector3d<f64>,
+    pub distance: si::Meter<f64>,
+}
+
+// Entities a ray can hit. If there are included tags, entities need at least one of them. They
+// must not have any of the excluded tags and must not be excluded by identity.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct RayFilter {
+    included_tags: Vec<&'static str>,
+    excluded_tags: Vec<&'static str>,
+    excluded_entities: Vec<&'static str>,
+}
+
+impl RayFilter {
+    pub fn new() -> RayFilter {
+        RayFilter::default()
+    }
+
+    pub fn including_tag(mut self, tag: &'static str) -> RayFilter {
+        self.included_tags.push(tag);
+        self
+    }
+
+    pub fn excluding_tag(mut self, tag: &'static str) -> RayFilter {
+        self.excluded_tags.push(tag);
+        self
+    }
+
+    pub fn excluding_entity(mut self, entity_id: &'static str) -> RayFilter {
+        self.excluded_entities.push(entity_id);
+        self
+    }
+
+    pub fn accepts(&self, entity: &Entity) -> bool {
+        (self.included_tags.is_empty() || self.included_tags.iter().any(|t| entity.has_tag(t)))
+            && !self.excluded_tags.iter().any(|t| entity.has_tag(t))
+            && !self.excluded_entities.contains(&entity.get_identity())
+    }
+}
+
+// Distance along the ray to the point where it enters the shape, with the normal there. Shapes
+// containing the origin are not hit, so rays can start on the surface of an entity.
+fn intersect(entity: &Entity, ray: &Ray) -> Option<(f64, [f64; 3])> {
+    let center = entity.get_state().get_location();
+    let origin = [
+        (ray.origin.x - center.x).value_unsafe,
+        (ray.origin.y - center.y).value_unsafe,
+        (ray.origin.z - center.z).value_unsafe,
+    ];
+    let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+    match entity.get_state().get_shape() {
+        Shape::Sphere(sphere) => {
+            let radius = sphere.radius.value_unsafe;
+            let b = (0..3).map(|i| origin[i] * direction[i]).sum::<f64>();
+            let c = origin.iter().map(|o| o * o).sum::<f64>() - radius * radius;
+            let discriminant = b * b - c;
+            if c <= 0.0 || discriminant < 0.0 {
+                return None;
+            }
+            let t = -b - discriminant.sqrt();
+            if t < 0.0 {
+                return None;
+            }
+            let mut normal = [0.0; 3];
+            for i in 0..3 {
+                normal[i] = (origin[i] + t * direction[i]) / radius;
+            }
+            Some((t, normal))
+        }
+        // Slab test, the ray enters through the face of the slab it enters last
+        Shape::Cuboid(cuboid) => {
+            let half_extents = [
+                cuboid.half_extents.x.value_unsafe,
+                cuboid.half_extents.y.value_unsafe,
+                cuboid.half_extents.z.value_unsafe,
+            ];
+            if (0..3).all(|i| origin[i].abs() <= half_extents[i]) {
+                return None;
+            }
+            let (mut t_enter, mut t_exit) = (f64::NEG_INFINITY, f64::INFINITY);
+            let mut axis = 0;
+            for i in 0..3 {
+                if direction[i] == 0.0 {
+                    if origin[i].abs() > half_extents[i] {
+                        return None;
+                    }
+                    continue;
+                }
+                let t0 = (-half_extents[i] - origin[i]) / direction[i];
+                let t1 = (half_extents[i] - origin[i]) / direction[i];
+                if t0.min(t1) > t_enter {
+                    t_enter = t0.min(t1);
+                    axis = i;
+                }
+                t_exit = t_exit.min(t0.max(t1));
+            }
+            if t_enter > t_exit || t_enter < 0.0 {
+                return None;
+            }
+            let mut normal = [0.0; 3];
+            normal[axis] = -direction[axis].signum();
+            Some((t_enter, normal))
+        }
+        Shape::None => None,
+    }
+}
+
+// All hits within the maximum distance, closest first
+pub fn cast_ray_all(
+    world: &[Entity],
+    ray: &Ray,
+    max_distance: si::Meter<f64>,
+    filter: &RayFilter,
+) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = world
+        .iter()
+        .filter(|e| filter.accepts(e))
+        .filter_map(|e| {
+            let (t, normal) = intersect(e, ray)?;
+            let distance = t * si::M;
+            if distance > max_distance {
+                return None;
+            }
+            Some(Hit {
+                entity: e.get_identity(),
+                point: ray.get_point(distance),
+                normal: Vector3d::new(normal[0], normal[1], normal[2]),
+                distance,
+            })
+        })
+        .collect();
+    hits.sort_by(|h0, h1| {
+        h0.distance
+            .value_unsafe
+            .total_cmp(&h1.distance.value_unsafe)
+    });
+    hits
+}
+
+pub fn cast_ray(
+    world: &[Entity],
+    ray: &Ray,
+    max_distance: si::Meter<f64>,
+    filter: &RayFilter,
+) -> Option<Hit> {
+    cast_ray_all(world, ray, max_distance, filter)
+        .into_iter()
+        .next()
+}
+
+// First hit on the straight segment from one location to the other, None for empty segments
+pub fn cast_segment(
+    world: &[Entity],
+    from: Vector3d<si::Meter<f64>>,
+    to: Vector3d<si::Meter<f64>>,
+    filter: &RayFilter,
+) -> Option<Hit> {
+    let delta = to - from;
+    let length = delta.norm2().sqrt();
+    if length == 0.0 * si::M {
+        return None;
+    }
+    let direction = delta / length;
+    let ray = Ray::new(
+        from,
+        Vector3d::new(
+            direction.x.value_unsafe,
+            direction.y.value_unsafe,
+            direction.z.value_unsafe,
+        ),
+    );
+    cast_ray(world, &ray, length, filter)
+}
+
+#[cfg(test)]
+mod raycast_tests {
+    use super::*;
+    use crate::physics::state::shape::{Cuboid, Sphere};
+    use crate::physics::state::state::State;
+
+    fn entity(identifier: &'static str, x: f64, shape: Shape) -> Entity {
+        Entity::new(
+            identifier,
+            State::new(
+                Vector3d::new(x, 0.0, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                shape,
+            ),
+        )
+    }
+
+    fn sphere(identifier: &'static str, x: f64) -> Entity {
+        entity(
+            identifier,
+            x,
+            Shape::Sphere(Sphere {
+                radius: 1.0 * si::M,
+            }),
+        )
+    }
+
+    fn cuboid(identifier: &'static str, x: f64) -> Entity {
+        entity(
+            identifier,
+            x,
+            Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(1.0, 2.0, 2.0) * si::M,
+            }),
+        )
+    }
+
+    fn ray(direction: Vector3d<f64>) -> Ray {
+        Ray::new(Vector3d::new(0.0, 0.0, 0.0) * si::M, direction)
+    }
+
+    #[test]
+    fn sphere_hit() {
+        let world = vec![sphere("A", 5.0)];
+        let hit = cast_ray(
+            &world,
+            &ray(Vector3d::new(2.0, 0.0, 0.0)),
+            100.0 * si::M,
+            &RayFilter::new(),
+        )
+        .unwrap();
+        assert_eq!(hit.entity, "A");
+        assert_eq!(hit.distance, 4.0 * si::M);
+        assert_eq!(hit.point, Vector3d::new(4.0, 0.0, 0.0) * si::M);
+        assert_eq!(hit.normal, Vector3d::new(-1.0, 0.0, 0.0));
+        // Passing by, behind and out of range
+        let filter = RayFilter::new();
+        assert!(cast_ray(
+            &world,
+            &ray(Vector3d::new(1.0, 1.0, 0.0)),
+            100.0 * si::M,
+            &filter
+        )
+        .is_none());
+        assert!(cast_ray(
+            &world,
+            &ray(Vector3d::new(-1.0, 0.0, 0.0)),
+            100.0 * si::M,
+            &filter
+        )
+        .is_none());
+        assert!(cast_ray(
+            &world,
+            &ray(Vector3d::new(1.0, 0.0, 0.0)),
+            3.0 * si::M,
+            &filter
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn cuboid_normal() {
+        let world = vec![cuboid("A", 5.0)];
+        let hit = cast_ray(
+            &world,
+            &ray(Vector3d::new(1.0, 0.5, 0.0)),
+            100.0 * si::M,
+            &RayFilter::new(),
+        )
+        .unwrap();
+        assert!((hit.distance.value_unsafe - 4.0 * 1.25_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(hit.normal, Vector3d::new(-1.0, 0.0, 0.0));
+        // Entering through the top face
+        let world = vec![entity(
+            "B",
+            0.0,
+            Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(5.0, 5.0, 1.0) * si::M,
+            }),
+        )];
+        let hit = cast_ray(
+            &world,
+            &Ray::new(
+                Vector3d::new(0.0, 0.0, 3.0) * si::M,
+                Vector3d::new(0.0, 1.0, -1.0),
+            ),
+            100.0 * si::M,
+            &RayFilter::new(),
+        )
+        .unwrap();
+        assert_eq!(hit.normal, Vector3d::new(0.0, 0.0, 1.0));
+        assert!((hit.point.y.value_unsafe - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn origin_inside_shape() {
+        let world = vec![sphere("A", 0.0), cuboid("B", 0.5), sphere("C", 5.0)];
+        let hit = cast_ray(
+            &world,
+            &ray(Vector3d::new(1.0, 0.0, 0.0)),
+            100.0 * si::M,
+            &RayFilter::new(),
+        )
+        .unwrap();
+        assert_eq!(hit.entity, "C");
+    }
+
+    #[test]
+    fn filters() {
+        let mut tagged = sphere("B", 10.0);
+        tagged.add_tag("wall");
+        let world = vec![sphere("A", 5.0), tagged, sphere("C", 15.0)];
+        let forward = ray(Vector3d::new(1.0, 0.0, 0.0));
+        let hits = cast_ray_all(&world, &forward, 100.0 * si::M, &RayFilter::new());
+        let entities: Vec<&str> = hits.iter().map(|h| h.entity).collect();
+        assert_eq!(entities, vec!["A", "B", "C"]);
+        let walls = RayFilter::new().including_tag("wall");
+        assert_eq!(
+            cast_ray(&world, &forward, 100.0 * si::M, &walls)
+                .unwrap()
+                .entity,
+            "B"
+        );
+        let others = RayFilter::new().excluding_tag("wall").excluding_entity("A");
+        assert_eq!(
+            cast_ray(&world, &forward, 100.0 * si::M, &others)
+                .unwrap()
+                .entity,
+            "C"
+        );
+    }
+
+    #[test]
+    fn segments() {
+        let world = vec![sphere("A", 5.0)];
+        let from = Vector3d::new(0.0, 0.0, 0.0) * si::M;
+        let filter = RayFilter::new();
+        assert!(
+            cast_segment(&world, from, Vector3d::new(3.0, 0.0, 0.0) * si::M, &filter).is_none()
+        );
+        assert_eq!(
+            cast_segment(&world, from, Vector3d::new(10.0, 0.0, 0.0) * si::M, &filter)
+                .unwrap()
+                .distance,
+            4.0 * si::M
+        );
+        assert!(cast_segment(&world, from, from, &filter).is_none());
+    }
+}
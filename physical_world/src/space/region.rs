@@ -0,0 +1,603 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use crate::physics::entity::Entity;
+use crate::physics::state::shape::Shape;
+use crate::sensors::sensor::MeasurementRegion;
+use crate::space::occupancy_grid::OccupancyGrid;
+
+// Lowest and highest corner of an axis-aligned box
+pub type Bounds = (Vector3d<si::Meter<f64>>, Vector3d<si::Meter<f64>>);
+
+// Largest number of cells a region is rasterised into
+pub const MAX_CELLS: usize = 1 << 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegionError {
+    // Resolutions are finite and positive
+    InvalidResolution(si::Meter<f64>),
+    // Number of cells the region would need at the resolution
+    TooManyCells(f64),
+}
+
+impl std::fmt::Display for RegionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RegionError::InvalidResolution(resolution) => {
+                write!(f, "Resolution {} is not positive and finite.", resolution)
+            }
+            RegionError::TooManyCells(cells) => write!(
+                f,
+                "Rasterising needs {} cells, more than {}.",
+                cells, MAX_CELLS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+fn check_resolution(resolution: si::Meter<f64>) -> Result<(), RegionError> {
+    if !resolution.value_unsafe.is_finite() || resolution <= 0.0 * si::M {
+        return Err(RegionError::InvalidResolution(resolution));
+    }
+    Ok(())
+}
+
+// Part of space, built from primitives and set operations. Operations that cannot be computed
+// exactly, like the volume of a composite region, sample the region at the centers of cells of
+// the given resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Region {
+    Empty,
+    // Axis-aligned box between two corners
+    Cuboid {
+        min: Vector3d<si::Meter<f64>>,
+        max: Vector3d<si::Meter<f64>>,
+    },
+    Sphere {
+        center: Vector3d<si::Meter<f64>>,
+        radius: si::Meter<f64>,
+    },
+    // Polygon in the xy plane, extruded between two heights. The vertices are in order, the
+    // polygon does not need to be convex.
+    Prism {
+        vertices: Vec<(si::Meter<f64>, si::Meter<f64>)>,
+        bottom: si::Meter<f64>,
+        top: si::Meter<f64>,
+    },
+    // Part of a sphere around the apex within the angle in radians to the direction
+    Cone {
+        apex: Vector3d<si::Meter<f64>>,
+        direction: Vector3d<f64>,
+        range: si::Meter<f64>,
+        half_angle: f64,
+    },
+    Occupancy(OccupancyGrid),
+    Union(Vec<Region>),
+    Intersection(Vec<Region>),
+    Difference(Box<Region>, Box<Region>),
+}
+
+fn to_array(v: Vector3d<si::Meter<f64>>) -> [f64; 3] {
+    [v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+}
+
+fn from_array(a: [f64; 3]) -> Vector3d<si::Meter<f64>> {
+    Vector3d::new(a[0], a[1], a[2]) * si::M
+}
+
+// Even-odd rule
+fn polygon_contains(vertices: &[(si::Meter<f64>, si::Meter<f64>)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match vertices.last() {
+        Some(vertex) => *vertex,
+        None => return false,
+    };
+    for vertex in vertices {
+        let (x0, y0) = (previous.0.value_unsafe, previous.1.value_unsafe);
+        let (x1, y1) = (vertex.0.value_unsafe, vertex.1.value_unsafe);
+        if (y0 > y) != (y1 > y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0) {
+            inside = !inside;
+        }
+        previous = *vertex;
+    }
+    inside
+}
+
+// Shoelace formula
+fn polygon_area(vertices: &[(si::Meter<f64>, si::Meter<f64>)]) -> si::Meter2<f64> {
+    let mut area = 0.0 * si::M * si::M;
+    for (idx, vertex) in vertices.iter().enumerate() {
+        let next = vertices[(idx + 1) % vertices.len()];
+        area += vertex.0 * next.1 - next.0 * vertex.1;
+    }
+    let area = area / 2.0;
+    if area < 0.0 * si::M * si::M {
+        -area
+    } else {
+        area
+    }
+}
+
+impl Region {
+    pub fn cuboid(
+        center: Vector3d<si::Meter<f64>>,
+        half_extents: Vector3d<si::Meter<f64>>,
+    ) -> Region {
+        Region::Cuboid {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    // Region covered by the shape of the entity, empty for entities without shape
+    pub fn of_entity(entity: &Entity) -> Region {
+        let center = entity.get_state().get_location();
+        match entity.get_state().get_shape() {
+            Shape::Sphere(s) => Region::Sphere {
+                center,
+                radius: s.radius,
+            },
+            Shape::Cuboid(c) => Region::cuboid(center, c.half_extents),
+            Shape::None => Region::Empty,
+        }
+    }
+
+    pub fn union(self, other: Region) -> Region {
+        match (self, other) {
+            (Region::Empty, other) => other,
+            (region, Region::Empty) => region,
+            (Region::Union(mut regions), Region::Union(others)) => {
+                regions.extend(others);
+                Region::Union(regions)
+            }
+            (Region::Union(mut regions), other) => {
+                regions.push(other);
+                Region::Union(regions)
+            }
+            (region, other) => Region::Union(vec![region, other]),
+        }
+    }
+
+    pub fn intersection(self, other: Region) -> Region {
+        match (self, other) {
+            (Region::Empty, _) | (_, Region::Empty) => Region::Empty,
+            (Region::Intersection(mut regions), Region::Intersection(others)) => {
+                regions.extend(others);
+                Region::Intersection(regions)
+            }
+            (Region::Intersection(mut regions), other) => {
+                regions.push(other);
+                Region::Intersection(regions)
+            }
+            (region, other) => Region::Intersection(vec![region, other]),
+        }
+    }
+
+    pub fn difference(self, other: Region) -> Region {
+        match (self, other) {
+            (Region::Empty, _) => Region::Empty,
+            (region, Region::Empty) => region,
+            (region, other) => Region::Difference(Box::new(region), Box::new(other)),
+        }
+    }
+
+    pub fn contains(&self, location: Vector3d<si::Meter<f64>>) -> bool {
+        match self {
+            Region::Empty => false,
+            Region::Cuboid { min, max } => {
+                min.x <= location.x
+                    && location.x <= max.x
+                    && min.y <= location.y
+                    && location.y <= max.y
+                    && min.z <= location.z
+                    && location.z <= max.z
+            }
+            Region::Sphere { center, radius } => (location - *center).norm2() <= *radius * *radius,
+            Region::Prism {
+                vertices,
+                bottom,
+                top,
+            } => {
+                *bottom <= location.z
+                    && location.z <= *top
+                    && polygon_contains(vertices, location.x.value_unsafe, location.y.value_unsafe)
+            }
+            Region::Cone {
+                apex,
+                direction,
+                range,
+                half_angle,
+            } => MeasurementRegion::Cone {
+                apex: *apex,
+                direction: *direction,
+                range: *range,
+                half_angle: *half_angle,
+            }
+            .contains(location),
+            Region::Occupancy(grid) => grid.contains(location),
+            Region::Union(regions) => regions.iter().any(|r| r.contains(location)),
+            Region::Intersection(regions) => regions.iter().all(|r| r.contains(location)),
+            Region::Difference(region, other) => {
+                region.contains(location) && !other.contains(location)
+            }
+        }
+    }
+
+    // Corners of an axis-aligned box that contains the region, None if the region is empty for
+    // sure. The box is not always the smallest one.
+    pub fn get_bounds(&self) -> Option<Bounds> {
+        match self {
+            Region::Empty => None,
+            Region::Cuboid { min, max } => Some((*min, *max)),
+            Region::Sphere { center, radius } => {
+                let extent = Vector3d::new(1.0, 1.0, 1.0) * *radius;
+                Some((*center - extent, *center + extent))
+            }
+            Region::Prism {
+                vertices,
+                bottom,
+                top,
+            } => {
+                vertices.first()?;
+                let xs = vertices.iter().map(|v| v.0.value_unsafe);
+                let ys = vertices.iter().map(|v| v.1.value_unsafe);
+                Some((
+                    Vector3d::new(
+                        xs.clone().fold(f64::INFINITY, f64::min) * si::M,
+                        ys.clone().fold(f64::INFINITY, f64::min) * si::M,
+                        *bottom,
+                    ),
+                    Vector3d::new(
+                        xs.fold(f64::NEG_INFINITY, f64::max) * si::M,
+                        ys.fold(f64::NEG_INFINITY, f64::max) * si::M,
+                        *top,
+                    ),
+                ))
+            }
+            Region::Cone { apex, range, .. } => {
+                let extent = Vector3d::new(1.0, 1.0, 1.0) * *range;
+                Some((*apex - extent, *apex + extent))
+            }
+            Region::Occupancy(grid) => grid.get_bounds(),
+            Region::Union(regions) => regions.iter().filter_map(|r| r.get_bounds()).reduce(
+                |(min0, max0), (min1, max1)| {
+                    let (min0, max0, min1, max1) = (
+                        to_array(min0),
+                        to_array(max0),
+                        to_array(min1),
+                        to_array(max1),
+                    );
+                    let mut min = [0.0; 3];
+                    let mut max = [0.0; 3];
+                    for i in 0..3 {
+                        min[i] = min0[i].min(min1[i]);
+                        max[i] = max0[i].max(max1[i]);
+                    }
+                    (from_array(min), from_array(max))
+                },
+            ),
+            Region::Intersection(regions) => {
+                let mut min = [f64::NEG_INFINITY; 3];
+                let mut max = [f64::INFINITY; 3];
+                for region in regions {
+                    let (region_min, region_max) = region.get_bounds()?;
+                    let (region_min, region_max) = (to_array(region_min), to_array(region_max));
+                    for i in 0..3 {
+                        min[i] = min[i].max(region_min[i]);
+                        max[i] = max[i].min(region_max[i]);
+                    }
+                }
+                if (0..3).any(|i| min[i] > max[i]) {
+                    return None;
+                }
+                Some((from_array(min), from_array(max)))
+            }
+            Region::Difference(region, _) => region.get_bounds(),
+        }
+    }
+
+    // Cells of the given size whose centers are in the region, at most MAX_CELLS of them
+    pub fn rasterise(&self, resolution: si::Meter<f64>) -> Result<OccupancyGrid, RegionError> {
+        check_resolution(resolution)?;
+        let (min, max) = match self.get_bounds() {
+            Some(bounds) => bounds,
+            None => {
+                return Ok(OccupancyGrid::new(
+                    Vector3d::new(0.0, 0.0, 0.0) * si::M,
+                    resolution,
+                    [0; 3],
+                ))
+            }
+        };
+        let extent = to_array((max - min) / resolution.value_unsafe).map(|e| e.ceil().max(1.0));
+        let cells = extent[0] * extent[1] * extent[2];
+        if cells.is_nan() || cells > MAX_CELLS as f64 {
+            return Err(RegionError::TooManyCells(cells));
+        }
+        let dimensions = extent.map(|e| e as usize);
+        let mut grid = OccupancyGrid::new(min, resolution, dimensions);
+        for z in 0..dimensions[2] {
+            for y in 0..dimensions[1] {
+                for x in 0..dimensions[0] {
+                    if self.contains(grid.get_cell_center([x, y, z])) {
+                        grid.set_occupied([x, y, z], true);
+                    }
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    // Exact for primitives
+    pub fn get_volume(&self, resolution: si::Meter<f64>) -> Result<si::Meter3<f64>, RegionError> {
+        check_resolution(resolution)?;
+        let volume = match self {
+            Region::Empty => 0.0 * si::M3,
+            Region::Cuboid { min, max } => {
+                let extent = *max - *min;
+                if extent.x < 0.0 * si::M || extent.y < 0.0 * si::M || extent.z < 0.0 * si::M {
+                    0.0 * si::M3
+                } else {
+                    extent.x * extent.y * extent.z
+                }
+            }
+            Region::Sphere { radius, .. } => {
+                4.0 / 3.0 * std::f64::consts::PI * *radius * *radius * *radius
+            }
+            Region::Prism {
+                vertices,
+                bottom,
+                top,
+            } => {
+                if top < bottom || vertices.is_empty() {
+                    0.0 * si::M3
+                } else {
+                    polygon_area(vertices) * (*top - *bottom)
+                }
+            }
+            Region::Cone {
+                range, half_angle, ..
+            } => {
+                let half_angle = half_angle.clamp(0.0, std::f64::consts::PI);
+                2.0 / 3.0
+                    * std::f64::consts::PI
+                    * *range
+                    * *range
+                    * *range
+                    * (1.0 - half_angle.cos())
+            }
+            Region::Occupancy(grid) => grid.get_volume(),
+            _ => self.rasterise(resolution)?.get_volume(),
+        };
+        Ok(volume)
+    }
+
+    pub fn is_empty(&self, resolution: si::Meter<f64>) -> Result<bool, RegionError> {
+        Ok(self.get_volume(resolution)? == 0.0 * si::M3)
+    }
+
+    pub fn overlaps(
+        &self,
+        other: &Region,
+        resolution: si::Meter<f64>,
+    ) -> Result<bool, RegionError> {
+        Ok(!self
+            .clone()
+            .intersection(other.clone())
+            .is_empty(resolution)?)
+    }
+
+    // Entities without a shape overlap the region if their location is in it
+    pub fn overlaps_entity(
+        &self,
+        entity: &Entity,
+        resolution: si::Meter<f64>,
+    ) -> Result<bool, RegionError> {
+        if entity.get_state().get_shape() == Shape::None {
+            check_resolution(resolution)?;
+            return Ok(self.contains(entity.get_state().get_location()));
+        }
+        self.overlaps(&Region::of_entity(entity), resolution)
+    }
+
+    // Whether the shape of the entity lies completely in the region, or the location of an
+    // entity without a shape
+    pub fn contains_entity(
+        &self,
+        entity: &Entity,
+        resolution: si::Meter<f64>,
+    ) -> Result<bool, RegionError> {
+        if entity.get_state().get_shape() == Shape::None {
+            check_resolution(resolution)?;
+            return Ok(self.contains(entity.get_state().get_location()));
+        }
+        Region::of_entity(entity)
+            .difference(self.clone())
+            .is_empty(resolution)
+    }
+
+    // Connected parts of the region. Primitives are connected, other regions are split into
+    // occupancy grids whose cells share faces.
+    pub fn split(&self, resolution: si::Meter<f64>) -> Result<Vec<Region>, RegionError> {
+        check_resolution(resolution)?;
+        let parts = match self {
+            Region::Empty => Vec::new(),
+            Region::Cuboid { .. } | Region::Sphere { .. } | Region::Cone { .. } => {
+                vec![self.clone()]
+            }
+            Region::Occupancy(grid) => grid
+                .get_components()
+                .into_iter()
+                .map(Region::Occupancy)
+                .collect(),
+            _ => self
+                .rasterise(resolution)?
+                .get_components()
+                .into_iter()
+                .map(Region::Occupancy)
+                .collect(),
+        };
+        Ok(parts)
+    }
+
+    // Distance from the center of the bounds to their corners
+    pub fn get_bounding_radius(&self) -> Option<si::Meter<f64>> {
+        let (min, max) = self.get_bounds()?;
+        Some(((max - min) / 2.0).norm2().sqrt())
+    }
+}
+
+impl From<MeasurementRegion> for Region {
+    fn from(region: MeasurementRegion) -> Region {
+        match region {
+            MeasurementRegion::Point(point) => Region::Cuboid {
+                min: point,
+                max: point,
+            },
+            MeasurementRegion::Sphere { center, radius } => Region::Sphere { center, radius },
+            MeasurementRegion::Cuboid {
+                center,
+                half_extents,
+            } => Region::cuboid(center, half_extents),
+            MeasurementRegion::Cone {
+                apex,
+                direction,
+                range,
+                half_angle,
+            } => Region::Cone {
+                apex,
+                direction,
+                range,
+                half_angle,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod region_tests {
+    use super::*;
+    use crate::physics::state::state::State;
+
+    fn unit_cube(x: f64) -> Region {
+        Region::cuboid(
+            Vector3d::new(x, 0.0, 0.0) * si::M,
+            Vector3d::new(0.5, 0.5, 0.5) * si::M,
+        )
+    }
+
+    #[test]
+    fn set_operations() {
+        let left = unit_cube(0.0);
+        let right = unit_cube(0.5);
+        let location = |x| Vector3d::new(x, 0.0, 0.0) * si::M;
+        let union = left.clone().union(right.clone());
+        assert!(union.contains(location(-0.4)) && union.contains(location(0.9)));
+        let intersection = left.clone().intersection(right.clone());
+        assert!(!intersection.contains(location(-0.4)) && intersection.contains(location(0.1)));
+        let difference = left.difference(right);
+        assert!(difference.contains(location(-0.4)) && !difference.contains(location(0.1)));
+        let resolution = 0.05 * si::M;
+        assert!((union.get_volume(resolution).unwrap().value_unsafe - 1.5).abs() < 1e-9);
+        assert!((intersection.get_volume(resolution).unwrap().value_unsafe - 0.5).abs() < 1e-9);
+        assert!((difference.get_volume(resolution).unwrap().value_unsafe - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prism() {
+        // L-shaped polygon
+        let prism = Region::Prism {
+            vertices: vec![
+                (0.0 * si::M, 0.0 * si::M),
+                (2.0 * si::M, 0.0 * si::M),
+                (2.0 * si::M, 1.0 * si::M),
+                (1.0 * si::M, 1.0 * si::M),
+                (1.0 * si::M, 2.0 * si::M),
+                (0.0 * si::M, 2.0 * si::M),
+            ],
+            bottom: 0.0 * si::M,
+            top: 2.0 * si::M,
+        };
+        assert!(prism.contains(Vector3d::new(0.5, 1.5, 1.0) * si::M));
+        assert!(!prism.contains(Vector3d::new(1.5, 1.5, 1.0) * si::M));
+        assert!(!prism.contains(Vector3d::new(0.5, 0.5, 2.5) * si::M));
+        assert_eq!(prism.get_volume(0.1 * si::M).unwrap(), 6.0 * si::M3);
+        assert_eq!(
+            prism.get_bounds(),
+            Some((
+                Vector3d::new(0.0, 0.0, 0.0) * si::M,
+                Vector3d::new(2.0, 2.0, 2.0) * si::M
+            ))
+        );
+    }
+
+    #[test]
+    fn split() {
+        let region = unit_cube(0.0).union(unit_cube(3.0)).union(unit_cube(0.9));
+        let parts = region.split(0.1 * si::M).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].contains(Vector3d::new(0.0, 0.0, 0.0) * si::M));
+        assert!((parts[0].get_volume(0.1 * si::M).unwrap().value_unsafe - 1.9).abs() < 1e-9);
+        assert!((parts[1].get_volume(0.1 * si::M).unwrap().value_unsafe - 1.0).abs() < 1e-9);
+        assert_eq!(
+            unit_cube(0.0).split(0.1 * si::M).unwrap(),
+            vec![unit_cube(0.0)]
+        );
+        assert!(Region::Empty.split(0.1 * si::M).unwrap().is_empty());
+    }
+
+    #[test]
+    fn disjoint_intersection() {
+        let region = unit_cube(0.0).intersection(unit_cube(2.0));
+        assert_eq!(region.get_bounds(), None);
+        assert!(region.is_empty(0.1 * si::M).unwrap());
+        assert!(!unit_cube(0.0)
+            .overlaps(&unit_cube(2.0), 0.1 * si::M)
+            .unwrap());
+        assert!(unit_cube(0.0)
+            .overlaps(&unit_cube(0.8), 0.1 * si::M)
+            .unwrap());
+    }
+
+    #[test]
+    fn point_masses() {
+        let point = |x: f64| {
+            Entity::new(
+                "point",
+                State::new(
+                    Vector3d::new(x, 0.0, 0.0) * si::M,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                    1.0 * si::KG,
+                    Shape::None,
+                ),
+            )
+        };
+        let cube = unit_cube(0.0);
+        assert!(cube.contains_entity(&point(0.2), 0.1 * si::M).unwrap());
+        assert!(cube.overlaps_entity(&point(0.2), 0.1 * si::M).unwrap());
+        assert!(!cube.contains_entity(&point(2.0), 0.1 * si::M).unwrap());
+        assert!(!cube.overlaps_entity(&point(2.0), 0.1 * si::M).unwrap());
+    }
+
+    #[test]
+    fn invalid_resolutions() {
+        let region = unit_cube(0.0).union(unit_cube(0.5));
+        for resolution in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                region
+                    .rasterise(resolution * si::M)
+                    .err()
+                    .map(|e| e.to_string()),
+                Some(RegionError::InvalidResolution(resolution * si::M).to_string())
+            );
+            assert!(unit_cube(0.0).get_volume(resolution * si::M).is_err());
+        }
+        assert!(matches!(
+            region.rasterise(1e-6 * si::M),
+            Err(RegionError::TooManyCells(_))
+        ));
+    }
+}
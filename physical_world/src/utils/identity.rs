@@ -1,3 +1,39 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
 pub trait Identity {
     fn get_identity(&self) -> &'static str;
 }
+
+// Identities are static, identities created at runtime (scenes, clients, bindings) are leaked
+// once and shared by every later use of the same name
+pub fn intern(identity: &str) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    match interned.get(identity) {
+        Some(&identity) => identity,
+        None => {
+            let identity: &'static str = Box::leak(identity.to_string().into_boxed_str());
+            interned.insert(identity);
+            identity
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_identity {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let identity = intern("interned entity");
+        assert_eq!(identity, "interned entity");
+        assert!(std::ptr::eq(
+            identity,
+            intern(&String::from("interned entity"))
+        ));
+    }
+}
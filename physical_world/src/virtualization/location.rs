@@ -0,0 +1,155 @@
+use dimensioned::si;
+
+use crate::actuators::actuator::Mounting;
+use crate::physics::system::System;
+use crate::recording::columns::Quantity;
+use crate::sensors::error_model::get_standard_deviation;
+use crate::sensors::sensor::{MeasurementRegion, Reading, SensorKind};
+use crate::utils::identity::Identity;
+use crate::virtualization::virtual_sensor::{
+    get_freshness, Observation, OutputProcess, Property, Value, VirtualSensor,
+};
+
+// Position sensors mounted on the entity, corrected by their offset
+pub struct PositionFix {
+    entity: &'static str,
+    max_age: si::Second<f64>,
+}
+
+impl OutputProcess for PositionFix {
+    fn get_identifier(&self) -> &'static str {
+        "position fix"
+    }
+
+    fn get_valid_combinations(&self, system: &System) -> Vec<Vec<&'static str>> {
+        system
+            .get_sensors()
+            .iter()
+            .filter(|s| {
+                s.get_kind() == SensorKind::Position
+                    && s.get_pose().mounting == Mounting::Entity(self.entity)
+            })
+            .map(|s| vec![s.get_identifier()])
+            .collect()
+    }
+
+    fn process(&self, system: &System, combination: &[&'static str]) -> Option<Observation> {
+        let sensor_id = combination[0];
+        let sensor = system
+            .get_sensors()
+            .iter()
+            .find(|s| s.get_identifier() == sensor_id)?;
+        let measurement = system.get_latest_measurement(sensor_id)?;
+        let freshness = get_freshness(system, measurement.time, self.max_age)?;
+        let location = match measurement.reading {
+            Reading::Position(location) => location - sensor.get_pose().offset,
+            _ => return None,
+        };
+        let uncertainty =
+            get_standard_deviation(system.get_error_models(sensor_id), measurement.time);
+        Some(Observation {
+            value: Value::Location(location),
+            region: MeasurementRegion::Sphere {
+                center: location,
+                radius: 2.0 * uncertainty * si::M,
+            },
+            uncertainty,
+            confidence: freshness,
+            time: measurement.time,
+            sensors: vec![sensor_id],
+        })
+    }
+}
+
+// Contact sensors at fixed locations that report the entity. The location of the sensor lies
+// within the entity, so its center is at most the radius of its bounding sphere away.
+pub struct PresenceFix {
+    entity: &'static str,
+    max_age: si::Second<f64>,
+}
+
+impl OutputProcess for PresenceFix {
+    fn get_identifier(&self) -> &'static str {
+        "presence fix"
+    }
+
+    fn get_valid_combinations(&self, system: &System) -> Vec<Vec<&'static str>> {
+        system
+            .get_sensors()
+            .iter()
+            .filter(|s| {
+                s.get_kind() == SensorKind::Contact
+                    && matches!(s.get_pose().mounting, Mounting::Location(_))
+            })
+            .map(|s| vec![s.get_identifier()])
+            .collect()
+    }
+
+    fn process(&self, system: &System, combination: &[&'static str]) -> Option<Observation> {
+        let sensor_id = combination[0];
+        let sensor = system
+            .get_sensors()
+            .iter()
+            .find(|s| s.get_identifier() == sensor_id)?;
+        let measurement = system.get_latest_measurement(sensor_id)?;
+        let freshness = get_freshness(system, measurement.time, self.max_age)?;
+        match &measurement.reading {
+            Reading::Contacts(contacts) if contacts.contains(&self.entity) => (),
+            _ => return None,
+        }
+        let location = sensor.get_pose().get_location(system.get_entities())?;
+        let radius = system
+            .get_entities()
+            .iter()
+            .find(|e| e.get_identity() == self.entity)
+            .map(|e| Quantity::Radius.get_values(e)[0])?;
+        Some(Observation {
+            value: Value::Location(location),
+            region: MeasurementRegion::Sphere {
+                center: location,
+                radius: radius * si::M,
+            },
+            uncertainty: radius / 2.0,
+            confidence: freshness,
+            time: measurement.time,
+            sensors: vec![sensor_id],
+        })
+    }
+}
+
+// Location of the center of an entity
+pub struct EntityLocation {
+    identifier: &'static str,
+    processes: Vec<Box<dyn OutputProcess>>,
+}
+
+impl EntityLocation {
+    // Readings older than the maximum age are ignored
+    pub fn new(
+        identifier: &'static str,
+        entity: &'static str,
+        max_age: si::Second<f64>,
+    ) -> EntityLocation {
+        EntityLocation {
+            identifier,
+            processes: vec![
+                Box::new(PositionFix { entity, max_age }),
+                Box::new(PresenceFix { entity, max_age }),
+            ],
+        }
+    }
+}
+
+impl VirtualSensor for EntityLocation {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_property(&self) -> Property {
+        Property::Location
+    }
+
+    fn get_output_processes(&self) -> &[Box<dyn OutputProcess>] {
+        &self.processes
+    }
+}
@@ -0,0 +1,5 @@
+pub mod location;
+pub mod solver;
+pub mod velocity;
+pub mod virtual_actuator;
+pub mod virtual_sensor;
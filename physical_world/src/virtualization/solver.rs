@@ -0,0 +1,265 @@
+// Effect of one actuator as an affine function of its input signal. Every row of the offset and
+// the gain is one component of the effect.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearModel {
+    pub offset: Vec<f64>,
+    pub gain: Vec<f64>,
+    pub min_signal: f64,
+    pub max_signal: f64,
+}
+
+impl LinearModel {
+    // Largest effect along the unit direction, the bound used to prune combinations
+    fn get_reach(&self, direction: &[f64]) -> f64 {
+        let offset = dot(&self.offset, direction);
+        let gain = dot(&self.gain, direction);
+        if gain == 0.0 {
+            offset
+        } else if gain > 0.0 {
+            offset + gain * self.max_signal
+        } else {
+            offset + gain * self.min_signal
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    // Indices of the selected models, with the signal of each
+    pub selection: Vec<(usize, f64)>,
+    // Norm of the part of the target that is not achieved
+    pub residual: f64,
+}
+
+const MAX_SWEEPS: usize = 500;
+const CONVERGENCE: f64 = 1e-12;
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// Signals of the models that come closest to the target, by coordinate descent on the squared
+// residual. Each coordinate is minimised exactly within its signal range, which converges
+// because the problem is convex.
+pub fn fit(models: &[&LinearModel], target: &[f64]) -> Solution {
+    let mut residual = target.to_vec();
+    let mut signals = Vec::new();
+    for model in models {
+        let signal = 0.0_f64.clamp(model.min_signal, model.max_signal);
+        for (r, (o, g)) in residual
+            .iter_mut()
+            .zip(model.offset.iter().zip(&model.gain))
+        {
+            *r -= o + g * signal;
+        }
+        signals.push(signal);
+    }
+    for _ in 0..MAX_SWEEPS {
+        let mut largest_change: f64 = 0.0;
+        for (model, signal) in models.iter().zip(signals.iter_mut()) {
+            let gain_norm = dot(&model.gain, &model.gain);
+            if gain_norm == 0.0 {
+                continue;
+            }
+            let change = (dot(&model.gain, &residual) / gain_norm)
+                .clamp(model.min_signal - *signal, model.max_signal - *signal);
+            for (r, g) in residual.iter_mut().zip(&model.gain) {
+                *r -= g * change;
+            }
+            *signal += change;
+            largest_change = largest_change.max(change.abs() * gain_norm.sqrt());
+        }
+        if largest_change < CONVERGENCE {
+            break;
+        }
+    }
+    Solution {
+        selection: signals.into_iter().enumerate().collect(),
+        residual: norm(&residual),
+    }
+}
+
+// Smallest combination of models that achieves the target within the tolerance. No combination
+// does better than all models together, so the target is rejected with their residual before
+// any search. Otherwise combinations are searched by size, strongest models first, and branches
+// are cut as soon as the reach of the chosen models and the strongest remaining ones falls
+// short of the target.
+pub fn solve(models: &[LinearModel], target: &[f64], tolerance: f64) -> Result<Solution, f64> {
+    let target_norm = norm(target);
+    if target_norm <= tolerance {
+        return Ok(Solution {
+            selection: Vec::new(),
+            residual: target_norm,
+        });
+    }
+    let all: Vec<&LinearModel> = models.iter().collect();
+    let best = fit(&all, target);
+    if best.residual > tolerance {
+        return Err(best.residual);
+    }
+    let direction: Vec<f64> = target.iter().map(|t| t / target_norm).collect();
+    let mut order: Vec<(usize, f64)> = models
+        .iter()
+        .enumerate()
+        .map(|(idx, model)| (idx, model.get_reach(&direction)))
+        .collect();
+    order.sort_by(|(_, r0), (_, r1)| r1.total_cmp(r0));
+    let needed = target_norm - tolerance;
+    for size in 1..=order.len() {
+        let mut chosen = Vec::new();
+        if let Some(solution) = search(
+            models,
+            target,
+            tolerance,
+            needed,
+            &order,
+            size,
+            0,
+            0.0,
+            &mut chosen,
+        ) {
+            return Ok(solution);
+        }
+    }
+    // Only reached if rounding cut the branch of all models
+    Ok(best)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search(
+    models: &[LinearModel],
+    target: &[f64],
+    tolerance: f64,
+    needed: f64,
+    order: &[(usize, f64)],
+    size: usize,
+    start: usize,
+    reach: f64,
+    chosen: &mut Vec<usize>,
+) -> Option<Solution> {
+    if chosen.len() == size {
+        let selected: Vec<&LinearModel> = chosen.iter().map(|idx| &models[*idx]).collect();
+        let fitted = fit(&selected, target);
+        if fitted.residual > tolerance {
+            return None;
+        }
+        return Some(Solution {
+            selection: fitted
+                .selection
+                .into_iter()
+                .map(|(idx, signal)| (chosen[idx], signal))
+                .collect(),
+            residual: fitted.residual,
+        });
+    }
+    let missing = size - chosen.len();
+    for position in start..=order.len() - missing {
+        // The strongest remaining models follow the position, so later positions cannot do better
+        let bound = reach
+            + order[position..position + missing]
+                .iter()
+                .map(|(_, r)| r)
+                .sum::<f64>();
+        if bound < needed {
+            break;
+        }
+        let (idx, model_reach) = order[position];
+        chosen.push(idx);
+        let solution = search(
+            models,
+            target,
+            tolerance,
+            needed,
+            order,
+            size,
+            position + 1,
+            reach + model_reach,
+            chosen,
+        );
+        chosen.pop();
+        if solution.is_some() {
+            return solution;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+
+    fn model(gain: [f64; 2], min_signal: f64, max_signal: f64) -> LinearModel {
+        LinearModel {
+            offset: vec![0.0, 0.0],
+            gain: gain.to_vec(),
+            min_signal,
+            max_signal,
+        }
+    }
+
+    #[test]
+    fn prefers_fewest_models() {
+        let models = vec![
+            model([1.0, 0.0], 0.0, 1.0),
+            model([1.0, 0.0], 0.0, 1.0),
+            model([3.0, 0.0], 0.0, 1.0),
+        ];
+        let solution = solve(&models, &[2.0, 0.0], 1e-9).unwrap();
+        assert_eq!(solution.selection.len(), 1);
+        assert_eq!(solution.selection[0].0, 2);
+        assert!((solution.selection[0].1 - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn combines_directions() {
+        let models = vec![
+            model([1.0, 0.0], -1.0, 1.0),
+            model([0.0, 1.0], -1.0, 1.0),
+            model([1.0, 1.0], 0.0, 0.1),
+        ];
+        let mut solution = solve(&models, &[0.5, -0.5], 1e-9).unwrap();
+        solution.selection.sort_by_key(|(idx, _)| *idx);
+        assert_eq!(solution.selection.len(), 2);
+        assert_eq!(solution.selection[0].0, 0);
+        assert_eq!(solution.selection[1].0, 1);
+        assert!((solution.selection[0].1 - 0.5).abs() < 1e-9);
+        assert!((solution.selection[1].1 + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unreachable_target() {
+        let models = vec![model([1.0, 0.0], 0.0, 1.0), model([1.0, 0.0], 0.0, 1.0)];
+        let residual = solve(&models, &[3.0, 0.0], 1e-9).unwrap_err();
+        assert!((residual - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unreachable_direction_of_many_models() {
+        // Every model is strong enough along the target, but none can move sideways
+        let models = vec![model([1.0, 0.0], 0.0, 1.0); 40];
+        let residual = solve(&models, &[10.0, 1.0], 1e-9).unwrap_err();
+        assert!((residual - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn offset_counts_towards_target() {
+        let models = vec![LinearModel {
+            offset: vec![1.0, 0.0],
+            gain: vec![0.0, 1.0],
+            min_signal: 0.0,
+            max_signal: 1.0,
+        }];
+        let solution = solve(&models, &[1.0, 0.5], 1e-9).unwrap();
+        assert!((solution.selection[0].1 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_target() {
+        let solution = solve(&[], &[0.0, 0.0], 1e-9).unwrap();
+        assert!(solution.selection.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+use dimensioned::si;
+
+use crate::actuators::actuator::Mounting;
+use crate::physics::system::System;
+use crate::sensors::error_model::get_standard_deviation;
+use crate::sensors::sensor::{Reading, SensorKind};
+use crate::virtualization::virtual_sensor::{
+    get_freshness, Observation, OutputProcess, Property, Value, VirtualSensor,
+};
+
+// Velocity sensors mounted on the entity
+pub struct VelocityFix {
+    entity: &'static str,
+    max_age: si::Second<f64>,
+}
+
+impl OutputProcess for VelocityFix {
+    fn get_identifier(&self) -> &'static str {
+        "velocity fix"
+    }
+
+    fn get_valid_combinations(&self, system: &System) -> Vec<Vec<&'static str>> {
+        system
+            .get_sensors()
+            .iter()
+            .filter(|s| {
+                s.get_kind() == SensorKind::Velocity
+                    && s.get_pose().mounting == Mounting::Entity(self.entity)
+            })
+            .map(|s| vec![s.get_identifier()])
+            .collect()
+    }
+
+    fn process(&self, system: &System, combination: &[&'static str]) -> Option<Observation> {
+        let sensor_id = combination[0];
+        let sensor = system
+            .get_sensors()
+            .iter()
+            .find(|s| s.get_identifier() == sensor_id)?;
+        let measurement = system.get_latest_measurement(sensor_id)?;
+        let freshness = get_freshness(system, measurement.time, self.max_age)?;
+        let velocity = match measurement.reading {
+            Reading::Velocity(velocity) => velocity,
+            _ => return None,
+        };
+        Some(Observation {
+            value: Value::Velocity(velocity),
+            region: sensor.get_measurement_region(system.get_entities())?,
+            uncertainty: get_standard_deviation(
+                system.get_error_models(sensor_id),
+                measurement.time,
+            ),
+            confidence: freshness,
+            time: measurement.time,
+            sensors: vec![sensor_id],
+        })
+    }
+}
+
+// Velocity of an entity
+pub struct EntityVelocity {
+    identifier: &'static str,
+    processes: Vec<Box<dyn OutputProcess>>,
+}
+
+impl EntityVelocity {
+    // Readings older than the maximum age are ignored
+    pub fn new(
+        identifier: &'static str,
+        entity: &'static str,
+        max_age: si::Second<f64>,
+    ) -> EntityVelocity {
+        EntityVelocity {
+            identifier,
+            processes: vec![Box::new(VelocityFix { entity, max_age })],
+        }
+    }
+}
+
+impl VirtualSensor for EntityVelocity {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_property(&self) -> Property {
+        Property::Velocity
+    }
+
+    fn get_output_processes(&self) -> &[Box<dyn OutputProcess>] {
+        &self.processes
+    }
+}
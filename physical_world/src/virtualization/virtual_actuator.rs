@@ -0,0 +1,177 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::system::System;
+use crate::space::region::Region;
+use crate::utils::identity::Identity;
+use crate::virtualization::solver::{solve, LinearModel};
+use crate::virtualization::virtual_sensor::{Property, Value};
+
+#[derive(Debug)]
+pub enum ActuationError {
+    UnsupportedProperty(Property),
+    // No entity has its center in the region
+    NoTargets,
+    // Norm of the part of the change the actuators cannot achieve, in SI units
+    Unachievable { residual: f64 },
+}
+
+impl std::fmt::Display for ActuationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ActuationError::UnsupportedProperty(p) => {
+                write!(f, "Changes of {:?} cannot be actuated.", p)
+            }
+            ActuationError::NoTargets => write!(f, "No entity is in the region."),
+            ActuationError::Unachievable { residual } => {
+                write!(f, "Change cannot be achieved, {} remains.", residual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActuationError {}
+
+// Change of a property of every entity whose center lies in the region
+#[derive(Clone, Debug, PartialEq)]
+pub struct DesiredChange {
+    pub region: Region,
+    pub value: Value,
+}
+
+// Input signals that achieve a change. Actuators that are not part of the plan keep their
+// signals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub signals: Vec<(&'static str, f64)>,
+    pub residual: f64,
+}
+
+impl Plan {
+    pub fn apply(&self, system: &mut System) {
+        for (actuator_id, signal) in &self.signals {
+            system.set_input_signal(actuator_id, *signal);
+        }
+    }
+}
+
+pub trait VirtualActuator {
+    fn get_identifier(&self) -> &'static str;
+    fn get_property(&self) -> Property;
+    // Smallest set of actuators of the system and their signals that achieve the change
+    fn plan(
+        &self,
+        system: &System,
+        change: &DesiredChange,
+        step_size: si::Second<f64>,
+    ) -> Result<Plan, ActuationError>;
+}
+
+// Changes the velocity of entities during the next step with the actuators of the system. The
+// current signals already cause a change, the output of an actuator in the plan is the velocity
+// change it causes on top of that.
+pub struct VelocityActuator {
+    identifier: &'static str,
+    // Largest acceptable norm of the missed velocity change, in m/s
+    tolerance: f64,
+}
+
+impl VelocityActuator {
+    pub fn new(identifier: &'static str, tolerance: f64) -> VelocityActuator {
+        VelocityActuator {
+            identifier,
+            tolerance,
+        }
+    }
+}
+
+fn get_components(v: Vector3d<si::Newton<f64>>) -> [f64; 3] {
+    [v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe]
+}
+
+impl VirtualActuator for VelocityActuator {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn get_property(&self) -> Property {
+        Property::Velocity
+    }
+
+    fn plan(
+        &self,
+        system: &System,
+        change: &DesiredChange,
+        step_size: si::Second<f64>,
+    ) -> Result<Plan, ActuationError> {
+        let velocity_change = match change.value {
+            Value::Velocity(v) => v,
+            value => return Err(ActuationError::UnsupportedProperty(value.get_property())),
+        };
+        let targets: Vec<_> = system
+            .get_entities()
+            .iter()
+            .filter(|e| change.region.contains(e.get_state().get_location()))
+            .collect();
+        if targets.is_empty() {
+            return Err(ActuationError::NoTargets);
+        }
+        // Three rows per target
+        let mut target_rows = Vec::new();
+        for _ in &targets {
+            target_rows.extend([
+                velocity_change.x.value_unsafe,
+                velocity_change.y.value_unsafe,
+                velocity_change.z.value_unsafe,
+            ]);
+        }
+        let mut actuator_ids = Vec::new();
+        let mut models = Vec::new();
+        for actuator in system.get_actuators() {
+            let reached = actuator.get_mounting().get_targets(system.get_entities());
+            let mut model = LinearModel {
+                offset: vec![0.0; target_rows.len()],
+                gain: vec![0.0; target_rows.len()],
+                min_signal: f64::NEG_INFINITY,
+                max_signal: f64::INFINITY,
+            };
+            let mut acts = false;
+            for (idx, target) in targets.iter().enumerate() {
+                if !reached
+                    .iter()
+                    .any(|e| e.get_identity() == target.get_identity())
+                {
+                    continue;
+                }
+                acts = true;
+                let output = actuator.get_output_model(target, step_size);
+                let current = actuator.get_force(target, step_size);
+                let scale = (step_size / target.get_state().get_mass()).value_unsafe;
+                let offset = get_components(output.offset - current);
+                let gain = get_components(output.gain);
+                for (c, current) in get_components(current).iter().enumerate() {
+                    target_rows[3 * idx + c] -= current * scale;
+                    model.offset[3 * idx + c] = offset[c] * scale;
+                    model.gain[3 * idx + c] = gain[c] * scale;
+                }
+                model.min_signal = model.min_signal.max(output.min_signal);
+                model.max_signal = model.max_signal.min(output.max_signal);
+            }
+            if acts && model.min_signal <= model.max_signal {
+                actuator_ids.push(actuator.get_identifier());
+                models.push(model);
+            }
+        }
+        match solve(&models, &target_rows, self.tolerance) {
+            Ok(solution) => Ok(Plan {
+                signals: solution
+                    .selection
+                    .into_iter()
+                    .map(|(idx, signal)| (actuator_ids[idx], signal))
+                    .collect(),
+                residual: solution.residual,
+            }),
+            Err(residual) => Err(ActuationError::Unachievable { residual }),
+        }
+    }
+}
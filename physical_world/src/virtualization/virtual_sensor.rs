@@ -0,0 +1,160 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use crate::physics::system::System;
+use crate::sensors::sensor::MeasurementRegion;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Property {
+    Location,
+    Velocity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Location(Vector3d<si::Meter<f64>>),
+    Velocity(Vector3d<si::MeterPerSecond<f64>>),
+}
+
+impl Value {
+    pub fn get_property(&self) -> Property {
+        match self {
+            Value::Location(_) => Property::Location,
+            Value::Velocity(_) => Property::Velocity,
+        }
+    }
+
+    fn get_components(&self) -> [f64; 3] {
+        match self {
+            Value::Location(v) => [v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe],
+            Value::Velocity(v) => [v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe],
+        }
+    }
+
+    fn with_components(&self, c: [f64; 3]) -> Value {
+        match self {
+            Value::Location(_) => Value::Location(Vector3d::new(c[0], c[1], c[2]) * si::M),
+            Value::Velocity(_) => Value::Velocity(Vector3d::new(c[0], c[1], c[2]) * si::MPS),
+        }
+    }
+}
+
+// Value of a physical property, where it applies and how much it can be trusted
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub value: Value,
+    pub region: MeasurementRegion,
+    // Standard deviation of every component of the value, in SI units
+    pub uncertainty: f64,
+    // Between 0 for no evidence and 1 for fresh readings of every valid combination of sensors
+    pub confidence: f64,
+    // Time of the oldest reading the observation is based on
+    pub time: si::Second<f64>,
+    // Physical sensors the observation is based on
+    pub sensors: Vec<&'static str>,
+}
+
+// Lower bound of uncertainties, so readings of ideal sensors can be weighted
+pub const MIN_UNCERTAINTY: f64 = 1e-9;
+
+// Turns the readings of one combination of physical sensors into an observation
+pub trait OutputProcess {
+    fn get_identifier(&self) -> &'static str;
+    // Identifiers of the physical sensors of every valid combination
+    fn get_valid_combinations(&self, system: &System) -> Vec<Vec<&'static str>>;
+    // None if the sensors have no usable readings
+    fn process(&self, system: &System, combination: &[&'static str]) -> Option<Observation>;
+}
+
+pub trait VirtualSensor {
+    fn get_identifier(&self) -> &'static str;
+    fn get_property(&self) -> Property;
+    fn get_output_processes(&self) -> &[Box<dyn OutputProcess>];
+
+    // Weighted by the inverse variance of the observations. Locations are surrounded by a
+    // sphere of two standard deviations, other values keep the region of the most certain
+    // observation.
+    fn merge(
+        &self,
+        observations: Vec<Observation>,
+        combination_count: usize,
+    ) -> Option<Observation> {
+        let weights = observations
+            .iter()
+            .map(|o| 1.0 / o.uncertainty.max(MIN_UNCERTAINTY).powi(2))
+            .collect::<Vec<f64>>();
+        let total_weight: f64 = weights.iter().sum();
+        let most_certain = observations
+            .iter()
+            .zip(weights.iter())
+            .max_by(|(_, w0), (_, w1)| w0.total_cmp(w1))
+            .map(|(o, _)| o)?;
+        let mut components = [0.0; 3];
+        for (observation, weight) in observations.iter().zip(weights.iter()) {
+            for (c, v) in components
+                .iter_mut()
+                .zip(observation.value.get_components())
+            {
+                *c += v * weight / total_weight;
+            }
+        }
+        let value = most_certain.value.with_components(components);
+        let uncertainty = (1.0 / total_weight).sqrt();
+        let region = match value {
+            Value::Location(center) => MeasurementRegion::Sphere {
+                center,
+                radius: 2.0 * uncertainty * si::M,
+            },
+            _ => most_certain.region,
+        };
+        Some(Observation {
+            value,
+            region,
+            uncertainty,
+            confidence: observations.iter().map(|o| o.confidence).sum::<f64>()
+                / combination_count as f64,
+            time: observations
+                .iter()
+                .map(|o| o.time)
+                .fold(most_certain.time, |t0, t1| if t1 < t0 { t1 } else { t0 }),
+            sensors: observations
+                .iter()
+                .flat_map(|o| o.sensors.iter().copied())
+                .collect(),
+        })
+    }
+
+    // Runs every output process on all of its valid combinations and merges the results
+    fn observe(&self, system: &System) -> Option<Observation> {
+        let mut observations = Vec::new();
+        let mut combination_count = 0;
+        for process in self.get_output_processes() {
+            for combination in process.get_valid_combinations(system) {
+                combination_count += 1;
+                if let Some(observation) = process.process(system, &combination) {
+                    if observation.value.get_property() == self.get_property() {
+                        observations.push(observation);
+                    }
+                }
+            }
+        }
+        if observations.is_empty() {
+            return None;
+        }
+        self.merge(observations, combination_count)
+    }
+}
+
+// Linear loss of confidence, readings at or beyond the maximum age are not used
+pub fn get_freshness(
+    system: &System,
+    time: si::Second<f64>,
+    max_age: si::Second<f64>,
+) -> Option<f64> {
+    let age = system.get_current_time() - time;
+    if age >= max_age {
+        None
+    } else {
+        Some(1.0 - (age / max_age).value_unsafe)
+    }
+}
@@ -0,0 +1,198 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::actuators::brake::Brake;
+use physical_machine::actuators::thruster::Thruster;
+use physical_machine::actuators::velocity_setter::VelocitySetter;
+use physical_machine::physics::state::shape::{Cuboid, Shape};
+use physical_machine::physics::system::System;
+use physical_machine::utils::identity::Identity;
+
+mod common;
+
+use common::entities::{entity, EntityBuilder};
+use common::interactions::interactions;
+
+// Spheres of the chains of these tests
+fn sphere(id: &'static str, x: f64) -> EntityBuilder {
+    entity(id, x).mass(100.0).radius(5.0)
+}
+
+fn velocity_of(system: &System, id: &str) -> f64 {
+    system
+        .get_entities()
+        .iter()
+        .find(|e| e.get_identity() == id)
+        .unwrap()
+        .get_state()
+        .get_velocity()
+        .x
+        .value_unsafe
+}
+
+fn run(system: &mut System, duration: f64) {
+    let steps = (duration / 0.01).round() as usize;
+    for _ in 0..steps {
+        system.next_state(0.01 * si::S);
+    }
+}
+
+#[test]
+fn test_thruster_pushes_chain() {
+    let mut system = System::new(
+        vec![
+            sphere("A", 30.0).build(),
+            sphere("B", 40.0).build(),
+            sphere("C", 20.0).build(),
+        ],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("C"),
+        Vector3d::new(600.0, 0.0, 0.0) * si::N,
+    )));
+    run(&mut system, 0.5);
+    assert_eq!(system.get_momentum().x, 0.0 * si::NS);
+
+    system.set_input_signal("thruster", 0.5);
+    system.next_state(0.01 * si::S);
+    let influences = system.get_last_influences();
+    assert!(!influences.is_empty());
+    assert!(influences.iter().all(|i| i.get_source_id() == "thruster"));
+    // The force reaches the far end of the chain through contacts
+    assert!(influences.iter().any(|i| i.get_receiver_id() == "B"));
+
+    run(&mut system, 0.99);
+    assert!((system.get_momentum().x.value_unsafe - 300.0).abs() < 1e-6);
+    for id in ["A", "B", "C"] {
+        assert!((velocity_of(&system, id) - 1.0).abs() < 0.05);
+    }
+}
+
+#[test]
+fn test_scheduled_input_signals() {
+    let mut system = System::new(vec![sphere("A", 0.0).build()], interactions(), 0.0 * si::S);
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("A"),
+        Vector3d::new(100.0, 0.0, 0.0) * si::N,
+    )));
+    system.schedule_input_signal("thruster", 1.0 * si::S, 1.0);
+    system.schedule_input_signal("thruster", 2.0 * si::S, 0.0);
+    run(&mut system, 1.0);
+    assert_eq!(velocity_of(&system, "A"), 0.0);
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+    assert_eq!(system.get_actuators()[0].get_input_signal(), 0.0);
+}
+
+#[test]
+fn test_brake_does_not_reverse() {
+    let mut system = System::new(
+        vec![sphere("A", 0.0).velocity(2.0).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_actuator(Box::new(Brake::new(
+        "brake",
+        Mounting::Entity("A"),
+        100.0 * si::N,
+    )));
+    system.set_input_signal("brake", 1.0);
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+    run(&mut system, 2.0);
+    assert!(velocity_of(&system, "A").abs() < 1e-9);
+}
+
+#[test]
+fn test_velocity_setter() {
+    let mut system = System::new(
+        vec![sphere("A", 0.0).velocity(2.0).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_actuator(Box::new(VelocitySetter::new(
+        "setter",
+        Mounting::Entity("A"),
+        Vector3d::new(2.0, 0.0, 0.0),
+    )));
+    system.set_input_signal("setter", -3.0);
+    system.next_state(0.01 * si::S);
+    assert!((velocity_of(&system, "A") + 3.0).abs() < 1e-9);
+    system.release_actuator("setter");
+    assert!(system.get_actuators()[0].get_input_signal().is_nan());
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") + 3.0).abs() < 1e-9);
+    // NaN releases the target as well
+    system.set_input_signal("setter", 1.0);
+    system.next_state(0.01 * si::S);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+    system.set_input_signal("setter", f64::NAN);
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+    system.set_input_signal("setter", f64::INFINITY);
+    assert!(system.get_actuators()[0].get_input_signal().is_nan());
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_nan_releases_actuators() {
+    let mut system = System::new(
+        vec![sphere("A", 0.0).velocity(2.0).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("A"),
+        Vector3d::new(100.0, 0.0, 0.0) * si::N,
+    )));
+    system.add_actuator(Box::new(Brake::new(
+        "brake",
+        Mounting::Entity("A"),
+        100.0 * si::N,
+    )));
+    system.set_input_signal("thruster", 1.0);
+    system.set_input_signal("brake", 1.0);
+    system.set_input_signal("thruster", f64::NAN);
+    system.set_input_signal("brake", f64::NAN);
+    for actuator in system.get_actuators() {
+        assert_eq!(actuator.get_input_signal(), 0.0);
+    }
+    run(&mut system, 1.0);
+    assert!((velocity_of(&system, "A") - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_actuator_at_location() {
+    let conveyor = sphere("Conveyor", 0.0)
+        .shape(Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(2.0, 2.0, 2.0) * si::M,
+        }))
+        .build();
+    let mut system = System::new(
+        vec![conveyor, sphere("A", 20.0).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_actuator(Box::new(Thruster::new(
+        "fan",
+        Mounting::Location(Vector3d::new(1.0, 1.0, 0.0) * si::M),
+        Vector3d::new(0.0, 100.0, 0.0) * si::N,
+    )));
+    system.set_input_signal("fan", 1.0);
+    run(&mut system, 1.0);
+    let conveyor = &system.get_entities()[0];
+    assert!((conveyor.get_state().get_velocity().y.value_unsafe - 1.0).abs() < 1e-9);
+    assert_eq!(
+        system.get_entities()[1].get_state().get_velocity().y,
+        0.0 * si::MPS
+    );
+}
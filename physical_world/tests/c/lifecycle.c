@@ -0,0 +1,135 @@
+/* Exercises the C interface from creation to release, exits with 0 on success */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "physical_machine.h"
+
+#define CHECK(condition)                                                        \
+  do {                                                                          \
+    if (!(condition)) {                                                         \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+      return 1;                                                                 \
+    }                                                                           \
+  } while (0)
+
+#define CHECK_STATUS(call, expected)                                            \
+  do {                                                                          \
+    PmStatus status = (call);                                                   \
+    if (status != (expected)) {                                                 \
+      fprintf(stderr, "%s:%d: %s returned %s\n", __FILE__, __LINE__, #call,     \
+              pm_status_message(status));                                       \
+      return 1;                                                                 \
+    }                                                                           \
+  } while (0)
+
+static PmState sphere(double x, double velocity) {
+  PmState state;
+  memset(&state, 0, sizeof(state));
+  state.location.x = x;
+  state.velocity.x = velocity;
+  state.mass = 1.0;
+  state.shape.kind = PM_SHAPE_SPHERE;
+  state.shape.radius = 0.5;
+  return state;
+}
+
+static int test_collision(void) {
+  const char *interactions[] = {"contact force", "elastic collision"};
+  PmSystem *system = NULL;
+  CHECK_STATUS(pm_system_new_with_interactions(interactions, 2, &system), PM_OK);
+  CHECK(system != NULL);
+
+  PmState a = sphere(0.0, 1.0);
+  PmState b = sphere(3.0, 0.0);
+  CHECK_STATUS(pm_system_add_entity(system, "A", &a), PM_OK);
+  CHECK_STATUS(pm_system_add_entity(system, "B", &b), PM_OK);
+  CHECK_STATUS(pm_system_add_entity(system, "A", &b), PM_DUPLICATE_IDENTITY);
+  PmState massless = sphere(6.0, 0.0);
+  massless.mass = 0.0;
+  CHECK_STATUS(pm_system_add_entity(system, "C", &massless), PM_INVALID_ARGUMENT);
+
+  size_t count = 0;
+  CHECK_STATUS(pm_system_get_entity_count(system, &count), PM_OK);
+  CHECK(count == 2);
+  char identity[8];
+  CHECK_STATUS(pm_system_get_entity_identity(system, 1, identity, sizeof(identity)), PM_OK);
+  CHECK(strcmp(identity, "B") == 0);
+  CHECK_STATUS(pm_system_get_entity_identity(system, 1, identity, 1), PM_INVALID_ARGUMENT);
+  CHECK_STATUS(pm_system_get_entity_identity(system, 2, identity, sizeof(identity)), PM_NOT_FOUND);
+
+  double initial_energy = 0.0;
+  CHECK_STATUS(pm_system_get_energy(system, &initial_energy), PM_OK);
+
+  /* A hits B after two seconds and stops, B takes over its velocity */
+  CHECK_STATUS(pm_system_step(system, 0.1, 40), PM_OK);
+  CHECK_STATUS(pm_system_step(system, -0.1, 1), PM_INVALID_ARGUMENT);
+  double time = 0.0;
+  CHECK_STATUS(pm_system_get_time(system, &time), PM_OK);
+  CHECK(fabs(time - 4.0) < 1e-9);
+  PmState state;
+  CHECK_STATUS(pm_system_get_state(system, "A", &state), PM_OK);
+  CHECK(fabs(state.velocity.x) < 1e-6);
+  CHECK(state.shape.kind == PM_SHAPE_SPHERE && state.shape.radius == 0.5);
+  CHECK_STATUS(pm_system_get_state(system, "B", &state), PM_OK);
+  CHECK(fabs(state.velocity.x - 1.0) < 1e-6);
+  CHECK_STATUS(pm_system_get_state(system, "C", &state), PM_NOT_FOUND);
+
+  PmVector momentum;
+  CHECK_STATUS(pm_system_get_momentum(system, &momentum), PM_OK);
+  CHECK(fabs(momentum.x - 1.0) < 1e-6);
+  double energy = 0.0;
+  CHECK_STATUS(pm_system_get_energy(system, &energy), PM_OK);
+  CHECK(fabs(energy - initial_energy) < 1e-6);
+
+  pm_system_free(system);
+  return 0;
+}
+
+static int test_scene(void) {
+  const char *scene =
+      "{\"name\": \"drift\", \"description\": \"\", \"duration\": 1.0, \"time_step\": 0.1,"
+      " \"interactions\": [], \"entities\": [{\"identity\": \"Probe\","
+      " \"location\": [0.0, 0.0, 0.0], \"velocity\": [0.0, 2.0, 0.0], \"mass\": 1.0}],"
+      " \"actuators\": [{\"identity\": \"brake\", \"kind\": \"brake\", \"max_force\": 1.0,"
+      " \"mounting\": {\"entity\": \"Probe\"}}]}";
+  PmSystem *system = NULL;
+  CHECK_STATUS(pm_system_from_scene_json(scene, &system), PM_OK);
+  CHECK_STATUS(pm_system_set_input_signal(system, "brake", 0.0), PM_OK);
+  CHECK_STATUS(pm_system_set_input_signal(system, "thruster", 1.0), PM_NOT_FOUND);
+  CHECK_STATUS(pm_system_release_actuator(system, "brake"), PM_OK);
+  CHECK_STATUS(pm_system_release_actuator(system, "thruster"), PM_NOT_FOUND);
+  CHECK_STATUS(pm_system_step(system, 0.5, 2), PM_OK);
+  PmState state;
+  CHECK_STATUS(pm_system_get_state(system, "Probe", &state), PM_OK);
+  CHECK(fabs(state.location.y - 2.0) < 1e-9);
+  CHECK(state.shape.kind == PM_SHAPE_NONE);
+  pm_system_free(system);
+
+  PmSystem *invalid = NULL;
+  CHECK_STATUS(pm_system_from_scene_json("{", &invalid), PM_SCENE_ERROR);
+  CHECK(invalid == NULL);
+  return 0;
+}
+
+static int test_null_pointers(void) {
+  PmSystem *system = NULL;
+  CHECK_STATUS(pm_system_new(NULL), PM_NULL_POINTER);
+  CHECK_STATUS(pm_system_step(NULL, 0.1, 1), PM_NULL_POINTER);
+  CHECK_STATUS(pm_system_new(&system), PM_OK);
+  CHECK_STATUS(pm_system_get_time(system, NULL), PM_NULL_POINTER);
+  CHECK_STATUS(pm_system_add_entity(system, NULL, NULL), PM_NULL_POINTER);
+  CHECK(strcmp(pm_status_message(PM_OK), "ok") == 0);
+  pm_system_free(system);
+  pm_system_free(NULL);
+  return 0;
+}
+
+int main(void) {
+  if (test_collision() || test_scene() || test_null_pointers()) {
+    return 1;
+  }
+  printf("ok\n");
+  return 0;
+}
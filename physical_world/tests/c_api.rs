@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The shared library is built next to the binary
+fn library_dir() -> PathBuf {
+    Path::new(env!("CARGO_BIN_EXE_physical_machine"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+#[test]
+fn test_c_lifecycle() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let program = library_dir.join("c_api_lifecycle");
+    let compiled = Command::new("cc")
+        .arg(manifest_dir.join("tests/c/lifecycle.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .args(["-lphysical_machine", "-lm", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(compiled.success());
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
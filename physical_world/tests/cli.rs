@@ -0,0 +1,95 @@
+use std::process::{Command, Output};
+
+use physical_machine::scene::examples::get_example_names;
+
+fn physical_machine(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_physical_machine"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_list_examples() {
+    let output = physical_machine(&["list"]);
+    assert!(output.status.success());
+    let listing = stdout(&output);
+    for name in get_example_names() {
+        assert!(listing.contains(name), "{} is not listed", name);
+    }
+}
+
+#[test]
+fn test_summary() {
+    let output = physical_machine(&["summary", "newton_pendulum_1", "--at", "0"]);
+    assert!(output.status.success());
+    let summary = stdout(&output);
+    assert!(summary.contains("t = 0.000 s"));
+    assert!(summary.contains("entities: 5"));
+    assert!(summary.contains("energy: 101.000001 J"));
+    assert!(summary.contains("momentum: (200.000000, 0.000000, 0.000000) N*s"));
+    assert!(summary.contains("contacts: A-B, B-C, D-E"));
+}
+
+#[test]
+fn test_run_scene_file() {
+    let output = physical_machine(&[
+        "run",
+        "scenes/wall_collisions.json",
+        "--duration",
+        "1",
+        "--summary-every",
+        "5",
+    ]);
+    assert!(output.status.success());
+    let summaries = stdout(&output);
+    assert!(summaries.contains("t = 0.000 s"));
+    assert!(summaries.contains("t = 0.500 s"));
+    assert!(summaries.contains("t = 1.000 s"));
+}
+
+#[test]
+fn test_unknown_scene() {
+    let output = physical_machine(&["summary", "no_such_scene"]);
+    assert!(!output.status.success());
+    let error = String::from_utf8(output.stderr).unwrap();
+    assert!(error.contains("No scene file or example named no_such_scene."));
+}
+
+#[test]
+fn test_export() {
+    std::fs::create_dir_all("csv").unwrap();
+    std::fs::create_dir_all("img").unwrap();
+    let output = physical_machine(&[
+        "export",
+        "simultaneous_collisions",
+        "--duration",
+        "0.5",
+        "--csv",
+        "csv/cli_export.csv",
+        "--plot",
+        "img/cli_export_velocity.svg",
+        "--quantity",
+        "velocity",
+        "--trajectory",
+        "img/cli_export_trajectory.png",
+    ]);
+    assert!(output.status.success());
+    let csv = std::fs::read_to_string("csv/cli_export.csv").unwrap();
+    assert!(csv.starts_with("time,A_location_x"));
+    // Header, initial state and 50 steps
+    assert_eq!(csv.lines().count(), 52);
+    let plot = std::fs::read_to_string("img/cli_export_velocity.svg").unwrap();
+    assert!(plot.contains("velocity x [m/s]"));
+    assert!(std::path::Path::new("img/cli_export_trajectory.png").exists());
+}
+
+#[test]
+fn test_export_without_outputs() {
+    let output = physical_machine(&["export", "simultaneous_collisions"]);
+    assert!(!output.status.success());
+}
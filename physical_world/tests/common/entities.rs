@@ -0,0 +1,72 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+
+// Entity on the x axis, by default a sphere with a radius of 0.5 m and 1 kg at rest
+#[derive(Clone, Copy, Debug)]
+pub struct EntityBuilder {
+    id: &'static str,
+    x: f64,
+    velocity: f64,
+    force: f64,
+    mass: f64,
+    shape: Shape,
+}
+
+pub fn entity(id: &'static str, x: f64) -> EntityBuilder {
+    EntityBuilder {
+        id,
+        x,
+        velocity: 0.0,
+        force: 0.0,
+        mass: 1.0,
+        shape: Shape::Sphere(Sphere {
+            radius: 0.5 * si::M,
+        }),
+    }
+}
+
+impl EntityBuilder {
+    pub fn velocity(mut self, velocity: f64) -> EntityBuilder {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn force(mut self, force: f64) -> EntityBuilder {
+        self.force = force;
+        self
+    }
+
+    pub fn mass(mut self, mass: f64) -> EntityBuilder {
+        self.mass = mass;
+        self
+    }
+
+    pub fn radius(mut self, radius: f64) -> EntityBuilder {
+        self.shape = Shape::Sphere(Sphere {
+            radius: radius * si::M,
+        });
+        self
+    }
+
+    pub fn shape(mut self, shape: Shape) -> EntityBuilder {
+        self.shape = shape;
+        self
+    }
+
+    pub fn build(self) -> Entity {
+        Entity::new(
+            self.id,
+            State::new(
+                Vector3d::new(self.x, 0.0, 0.0) * si::M,
+                Vector3d::new(self.velocity, 0.0, 0.0) * si::MPS,
+                Vector3d::new(self.force, 0.0, 0.0) * si::N,
+                self.mass * si::KG,
+                self.shape,
+            ),
+        )
+    }
+}
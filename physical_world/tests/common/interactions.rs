@@ -1,10 +1,12 @@
-use physical_machine::physics::interaction::interaction::Interaction;
-use physical_machine::physics::interaction::elastic_collision::ElasticCollision;
 use physical_machine::physics::interaction::contact_forces::ContactForces;
+use physical_machine::physics::interaction::elastic_collision::ElasticCollision;
+use physical_machine::physics::interaction::interaction::Interaction;
 // use physical_machine::physics::interaction::gravity::Gravity;
 
-pub const INTERACTIONS: [&'static dyn Interaction; 2] = [
-    &ContactForces,
-    &ElasticCollision,
-    //&Gravity,
-];
\ No newline at end of file
+pub fn interactions() -> Vec<Box<dyn Interaction>> {
+    vec![
+        Box::new(ContactForces::default()),
+        Box::new(ElasticCollision::default()),
+        //Box::new(Gravity::default()),
+    ]
+}
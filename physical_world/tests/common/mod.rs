@@ -1,6 +1,9 @@
+#![allow(dead_code)]
+
+pub mod entities;
+pub mod interactions;
 pub mod logging;
+pub mod permutation;
 pub mod simulation;
-pub mod plotter;
-pub mod interactions;
 
 pub fn setup() {}
@@ -0,0 +1,90 @@
+use dimensioned::si;
+use itertools::Itertools;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::system::System;
+use physical_machine::utils::identity::Identity;
+
+type Trajectory = Vec<Vec<(&'static str, Vector3d<f64>, Vector3d<f64>)>>;
+
+// Orders of the entities to compare, all of them for up to four entities, otherwise every
+// rotation forwards and backwards
+fn get_orders(count: usize) -> Vec<Vec<usize>> {
+    if count <= 4 {
+        return (0..count).permutations(count).collect();
+    }
+    (0..count)
+        .flat_map(|shift| {
+            let rotation: Vec<usize> = (0..count).map(|idx| (idx + shift) % count).collect();
+            let reversed = rotation.iter().rev().copied().collect();
+            [rotation, reversed]
+        })
+        .collect()
+}
+
+// Locations and velocities by identity after every step
+fn get_trajectory(
+    system: &System,
+    order: &[usize],
+    step_size: si::Second<f64>,
+    steps: usize,
+) -> Trajectory {
+    let entities: Vec<Entity> = order
+        .iter()
+        .map(|&idx| system.get_entities()[idx].clone())
+        .collect();
+    let mut permuted = System::new(
+        entities,
+        system.get_interactions().clone(),
+        system.get_current_time(),
+    );
+    permuted.set_position_correction(system.get_position_correction());
+    permuted.set_propagation_limits(system.get_propagation_limits());
+    permuted.set_tolerances(system.get_tolerances());
+    (0..steps)
+        .map(|_| {
+            permuted.next_state(step_size);
+            permuted
+                .get_entities()
+                .iter()
+                .map(|e| {
+                    let state = e.get_state();
+                    let location = state.get_location();
+                    let velocity = state.get_velocity();
+                    (
+                        e.get_identity(),
+                        Vector3d::new(
+                            location.x.value_unsafe,
+                            location.y.value_unsafe,
+                            location.z.value_unsafe,
+                        ),
+                        Vector3d::new(
+                            velocity.x.value_unsafe,
+                            velocity.y.value_unsafe,
+                            velocity.z.value_unsafe,
+                        ),
+                    )
+                })
+                .sorted_by_key(|(identity, _, _)| *identity)
+                .collect()
+        })
+        .collect()
+}
+
+// Simulates the entities and interactions of the system in different orders and checks
+// that every order results in exactly the same trajectories
+pub fn assert_permutation_invariant(system: &System, step_size: si::Second<f64>, steps: usize) {
+    let orders = get_orders(system.get_entities().len());
+    let reference = get_trajectory(system, &orders[0], step_size, steps);
+    for order in &orders[1..] {
+        let trajectory = get_trajectory(system, order, step_size, steps);
+        for (step, (expected, actual)) in reference.iter().zip(&trajectory).enumerate() {
+            assert_eq!(
+                expected, actual,
+                "Trajectories diverge at step {} for the order {:?}",
+                step, order
+            );
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use dimensioned::{si, Sqrt};
-use csv;
 
 use physical_machine::physics::system::System;
-use physical_machine::physics::state::shape::Shape;
-use physical_machine::utils::identity::Identity;
+use physical_machine::plotting::chart::Chart;
+use physical_machine::plotting::series::{quantity_over_time, Component};
+use physical_machine::recording::columns::{Columns, Quantity};
+use physical_machine::recording::csv_recorder::CsvRecorder;
+use physical_machine::recording::recorder::{Recorder, RecorderError};
+use physical_machine::recording::sampling::Sampling;
 use physical_machine::utils::round::Round;
 
-use super::plotter::{DataSeries, Plotter};
-
 pub struct Simulation {
     pub simulation_time: si::Second<f64>,
     pub time_step: si::Second<f64>,
@@ -43,7 +44,7 @@ impl Simulation {
         let allowed_error: f64 = 0.007;
         if check_preserved_quantities && momentum_before != 0.0 && energy_before != 0.0 {
             let momentum_diff = 1.0 - momentum_after / momentum_before;
-            println!("{}% difference in momentum.", momentum_diff);
+            log::debug!("{}% difference in momentum.", momentum_diff);
             assert!(
                 momentum_after >= momentum_before * (1.0 - allowed_error)
                     && momentum_after <= momentum_before * (1.0 + allowed_error),
@@ -54,7 +55,7 @@ impl Simulation {
                 self.system.get_current_time(),
             );
             let energy_diff = 1.0 - energy_after / energy_before;
-            println!("{}% difference in energy.", energy_diff);
+            log::debug!("{}% difference in energy.", energy_diff);
             assert!(
                 energy_after >= energy_before * (1.0 - allowed_error)
                     && energy_after <= energy_before * (1.0 + allowed_error),
@@ -67,101 +68,67 @@ impl Simulation {
         }
     }
 
-    pub fn run(mut self, check_preserved_quantities: bool) -> Vec<SimulationStep> {
+    pub fn run(self, check_preserved_quantities: bool) -> Vec<SimulationStep> {
+        match self.run_recorded(check_preserved_quantities, &mut []) {
+            Ok(history) => history,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn run_recorded(
+        mut self,
+        check_preserved_quantities: bool,
+        recorders: &mut [&mut dyn Recorder],
+    ) -> Result<Vec<SimulationStep>, RecorderError> {
         let mut time = 0.0 * si::S;
         let mut history = Vec::<SimulationStep>::new();
         history.push(SimulationStep {
             time,
             system: self.system.clone(),
         });
+        for recorder in recorders.iter_mut() {
+            recorder.record(&self.system)?;
+        }
         while time <= self.simulation_time {
             self.step(check_preserved_quantities);
-            time = time + self.time_step;
+            time += self.time_step;
             history.push(SimulationStep {
                 time,
                 system: self.system.clone(),
             });
+            for recorder in recorders.iter_mut() {
+                recorder.record(&self.system)?;
+            }
+        }
+        for recorder in recorders.iter_mut() {
+            recorder.flush()?;
         }
-        history
+        Ok(history)
     }
 }
 
-pub fn write_csv(history: &Vec<SimulationStep>, filename: &str) {
-    let file = std::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .append(false)
-        .truncate(true)
-        .open(filename)
-        .unwrap();
-    let mut writer = csv::Writer::from_writer(file);
-    let mut header = Vec::<String>::new();
-    header.push("time".to_string());
-    for (idx, _entity) in history[0].system.get_entities().iter().enumerate() {
-        header.push("e".to_string() + &idx.to_string() + "_identity");
-        header.push("e".to_string() + &idx.to_string() + "_location_x");
-        header.push("e".to_string() + &idx.to_string() + "_location_y");
-        header.push("e".to_string() + &idx.to_string() + "_location_z");
-        header.push("e".to_string() + &idx.to_string() + "_velocity_x");
-        header.push("e".to_string() + &idx.to_string() + "_velocity_y");
-        header.push("e".to_string() + &idx.to_string() + "_velocity_z");
-        header.push("e".to_string() + &idx.to_string() + "_net_force_x");
-        header.push("e".to_string() + &idx.to_string() + "_net_force_y");
-        header.push("e".to_string() + &idx.to_string() + "_net_force_z");
-        header.push("e".to_string() + &idx.to_string() + "mass");
-        header.push("e".to_string() + &idx.to_string() + "radius");
+pub fn write_csv(history: &[SimulationStep], filename: &str) {
+    let result = CsvRecorder::create(filename, Sampling::EveryStep, Columns::all()).and_then(
+        |mut recorder| {
+            for sim_step in history {
+                recorder.record(&sim_step.system)?;
+            }
+            recorder.flush()
+        },
+    );
+    if let Err(e) = result {
+        panic!("Encountered error while writing {filename}:\n {e}");
     }
-    match writer.write_record(&header) {
-        Ok(()) => (),
-        Err(e) => panic!("{}", e),
-    };
-    for sim_step in history {
-        let mut entry = Vec::<String>::new();
-        entry.push(sim_step.time.value_unsafe.to_string());
-        for entity in sim_step.system.get_entities() {
-            entry.push(entity.get_identity().to_string());
-            entry.push(entity.get_state().get_location().x.value_unsafe.to_string());
-            entry.push(entity.get_state().get_location().y.value_unsafe.to_string());
-            entry.push(entity.get_state().get_location().z.value_unsafe.to_string());
-            entry.push(entity.get_state().get_velocity().x.value_unsafe.to_string());
-            entry.push(entity.get_state().get_velocity().y.value_unsafe.to_string());
-            entry.push(entity.get_state().get_velocity().z.value_unsafe.to_string());
-            entry.push(entity.get_state().get_net_force().x.value_unsafe.to_string());
-            entry.push(entity.get_state().get_net_force().y.value_unsafe.to_string());
-            entry.push(entity.get_state().get_net_force().z.value_unsafe.to_string());
-            entry.push(entity.get_state().get_mass().value_unsafe.to_string());
-            match entity.get_state().get_shape() {
-                Shape::Sphere(s) => entry.push(s.radius.value_unsafe.to_string()),
-                _ => panic!("Unknown shape."),
-            };
-        }
-        match writer.write_record(&entry) {
-            Ok(()) => (),
-            Err(e) => panic!("{}", e),
-        };
-    }
-    match writer.flush() {
-        Ok(()) => (),
-        Err(e) => panic!("{}", e),
-    };
 }
 
-pub fn plot_results(history: &Vec<SimulationStep>, filename: &str, title: &str) {
-    let mut data_series = Vec::<DataSeries>::new();
-    for entity in history[0].system.get_entities() {
-        data_series.push(DataSeries {
-            name: entity.get_identity().to_string(),
-            data: Vec::<(f64, f64)>::new(),
-        });
-    }
-    for (i, _) in history[0].system.get_entities().iter().enumerate() {
-        for data_point in history {
-            let entity = data_point.system.get_entities()[i].clone();
-            data_series[i].data.push((
-                data_point.time.value_unsafe,
-                entity.get_state().get_location().x.value_unsafe,
-            ));
-        }
+pub fn plot_results(history: &[SimulationStep], filename: &str, title: &str) {
+    let series = quantity_over_time(
+        history.iter().map(|s| &s.system),
+        Quantity::Location,
+        Component::X,
+    );
+    let chart = Chart::new(title, "Time [s]", "Location x [m]");
+    if let Err(e) = chart.plot(&series, filename) {
+        panic!("Encountered error while plotting {filename}:\n {e}");
     }
-    data_series.plot(filename, title);
 }
@@ -0,0 +1,158 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::interaction::contact_solver::ContactSolver;
+use physical_machine::physics::state::shape::{Cuboid, Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::scene::scene::{Scene, SceneError};
+
+fn body(
+    id: &'static str,
+    location: Vector3d<f64>,
+    velocity: f64,
+    force: Vector3d<f64>,
+    mass: f64,
+    shape: Shape,
+) -> Entity {
+    Entity::new(
+        id,
+        State::new(
+            location * si::M,
+            Vector3d::new(velocity, 0.0, 0.0) * si::MPS,
+            force * si::N,
+            mass * si::KG,
+            shape,
+        ),
+    )
+}
+
+fn ball(id: &'static str, location: Vector3d<f64>, velocity: f64, force: Vector3d<f64>) -> Entity {
+    body(
+        id,
+        location,
+        velocity,
+        force,
+        1.0,
+        Shape::Sphere(Sphere {
+            radius: 0.5 * si::M,
+        }),
+    )
+}
+
+fn solved(entities: Vec<Entity>) -> System {
+    System::new(
+        entities,
+        vec![Box::new(ContactSolver::default())],
+        0.0 * si::S,
+    )
+}
+
+fn velocity(system: &System, idx: usize) -> Vector3d<f64> {
+    let v = system.get_entities()[idx].get_state().get_velocity();
+    Vector3d::new(v.x.value_unsafe, v.y.value_unsafe, v.z.value_unsafe)
+}
+
+const NONE: Vector3d<f64> = Vector3d {
+    x: 0.0,
+    y: 0.0,
+    z: 0.0,
+};
+
+#[test]
+fn test_elastic_collision_swaps_velocities() {
+    let mut system = solved(vec![
+        ball("A", Vector3d::new(0.0, 0.0, 0.0), 1.0, NONE),
+        ball("B", Vector3d::new(1.0, 0.0, 0.0), -1.0, NONE),
+    ]);
+    let energy = system.get_energy();
+    system.next_state(0.01 * si::S);
+    assert!((velocity(&system, 0).x + 1.0).abs() < 1e-9);
+    assert!((velocity(&system, 1).x - 1.0).abs() < 1e-9);
+    assert!((system.get_energy() - energy).value_unsafe.abs() < 1e-9);
+}
+
+#[test]
+fn test_wedged_body_stays_at_rest() {
+    // B is squeezed between A and C from both sides
+    let mut system = solved(vec![
+        ball(
+            "A",
+            Vector3d::new(0.0, 0.0, 0.0),
+            0.0,
+            Vector3d::new(10.0, 0.0, 0.0),
+        ),
+        ball("B", Vector3d::new(1.0, 0.0, 0.0), 0.0, NONE),
+        ball(
+            "C",
+            Vector3d::new(2.0, 0.0, 0.0),
+            0.0,
+            Vector3d::new(-10.0, 0.0, 0.0),
+        ),
+    ]);
+    for _ in 0..100 {
+        system.next_state(0.01 * si::S);
+    }
+    for idx in 0..3 {
+        assert!(velocity(&system, idx).norm2() < 1e-12, "{} moves", idx);
+    }
+    assert_eq!(system.get_contacts(), vec![("A", "B"), ("B", "C")]);
+}
+
+#[test]
+fn test_stack_rests_on_ground() {
+    let weight = Vector3d::new(0.0, 0.0, -9.81);
+    let ground = body(
+        "Ground",
+        Vector3d::new(0.0, 0.0, -0.5),
+        0.0,
+        NONE,
+        1e12,
+        Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(5.0, 5.0, 0.5) * si::M,
+        }),
+    );
+    let mut system = solved(vec![
+        ground,
+        ball("A", Vector3d::new(0.0, 0.0, 0.5), 0.0, weight),
+        ball("B", Vector3d::new(0.0, 0.0, 1.5), 0.0, weight),
+        ball("C", Vector3d::new(0.0, 0.0, 2.5), 0.0, weight),
+    ]);
+    for _ in 0..200 {
+        system.next_state(0.01 * si::S);
+    }
+    for (idx, height) in [(1, 0.5), (2, 1.5), (3, 2.5)] {
+        let location = system.get_entities()[idx].get_state().get_location();
+        assert!(
+            (location.z.value_unsafe - height).abs() < 1e-6,
+            "{} sank to {}",
+            idx,
+            location.z
+        );
+    }
+    // The ground carries the whole stack
+    let ground_force: f64 = system
+        .get_last_influences()
+        .iter()
+        .filter(|i| i.get_receiver_id() == "Ground")
+        .map(|i| i.get_state_change().get_net_force().z.value_unsafe)
+        .sum();
+    assert!((ground_force + 3.0 * 9.81).abs() < 1e-6);
+}
+
+#[test]
+fn test_solver_replaces_force_propagation_in_scenes() {
+    let scene = r#"{
+        "name": "solver", "description": "", "duration": 1.0, "time_step": 0.1,
+        "interactions": ["contact solver", "elastic collision"],
+        "entities": []
+    }"#;
+    assert!(matches!(
+        Scene::from_json(scene),
+        Err(SceneError::InvalidValue(_))
+    ));
+    let scene = scene.replace(", \"elastic collision\"", ", \"Gravity\"");
+    let system = Scene::from_json(&scene).unwrap().to_system().unwrap();
+    assert!(system.get_entities().is_empty());
+}
@@ -0,0 +1,204 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::interaction::contact_forces::ContactForces;
+use physical_machine::physics::interaction::interaction::{Interaction, PropagationLimits};
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::state::state_influence::StateInfluence;
+use physical_machine::physics::system::System;
+use physical_machine::utils::identity::Identity;
+
+const RING: [&str; 6] = ["A", "B", "C", "D", "E", "F"];
+
+// Passes every force on to the next entity of the ring, starting at entity A
+#[derive(Clone, Copy)]
+struct Relay {
+    length: usize,
+}
+
+impl Relay {
+    fn next(&self, identity: &str) -> &'static str {
+        let idx = RING.iter().position(|&id| id == identity).unwrap();
+        RING[(idx + 1) % self.length]
+    }
+}
+
+impl Interaction for Relay {
+    fn get_identifier(&self) -> &'static str {
+        "relay"
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn is_neighbor(&self, transmitter: &Entity, receiver: &Entity) -> bool {
+        self.next(transmitter.get_identity()) == receiver.get_identity()
+    }
+
+    fn init(
+        &self,
+        source: &Entity,
+        neighbors: Vec<&Entity>,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        if source.get_identity() != "A" {
+            return Vec::new();
+        }
+        vec![StateInfluence::force_influence(
+            source.get_identity(),
+            source.get_identity(),
+            neighbors[0].get_identity(),
+            self.get_identifier(),
+            Vector3d::new(10.0, 0.0, 0.0) * si::N,
+        )]
+    }
+
+    fn react(
+        &self,
+        reactor: &Entity,
+        neighbors: Vec<&Entity>,
+        influence: StateInfluence,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        vec![StateInfluence::force_influence(
+            influence.get_source_id(),
+            reactor.get_identity(),
+            neighbors[0].get_identity(),
+            self.get_identifier(),
+            influence.get_state_change().get_net_force(),
+        )]
+    }
+}
+
+const TRIANGLE: Relay = Relay { length: 3 };
+const HEXAGON: Relay = Relay { length: 6 };
+
+fn ring(length: usize, interaction: Relay) -> System {
+    let entities = RING[..length]
+        .iter()
+        .enumerate()
+        .map(|(idx, &id)| {
+            Entity::new(
+                id,
+                State::new(
+                    Vector3d::new(idx as f64 * 10.0, 0.0, 0.0) * si::M,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                    1.0 * si::KG,
+                    Shape::None,
+                ),
+            )
+        })
+        .collect();
+    System::new(entities, vec![Box::new(interaction)], 0.0 * si::S)
+}
+
+fn total_force(system: &System) -> Vector3d<si::Newton<f64>> {
+    system
+        .get_last_influences()
+        .iter()
+        .fold(Vector3d::new(0.0, 0.0, 0.0) * si::N, |total, influence| {
+            total + influence.get_state_change().get_net_force()
+        })
+}
+
+// Receivers of the influences that end the propagation
+fn receivers(system: &System) -> Vec<&'static str> {
+    system
+        .get_last_influences()
+        .iter()
+        .map(|influence| {
+            assert_eq!(influence.get_transmitter_id(), influence.get_receiver_id());
+            influence.get_receiver_id()
+        })
+        .collect()
+}
+
+#[test]
+fn test_cycle_is_absorbed() {
+    let mut system = ring(3, TRIANGLE);
+    system.next_state(1.0 * si::S);
+    // A -> B -> C -> A closes the cycle, A keeps the force
+    assert_eq!(receivers(&system), vec!["A"]);
+    assert_eq!(total_force(&system).x, 10.0 * si::N);
+    assert!(system.get_exhausted_interactions().is_empty());
+}
+
+#[test]
+fn test_depth_limit() {
+    let mut system = ring(6, HEXAGON);
+    system.set_propagation_limits(PropagationLimits {
+        max_depth: 3,
+        ..Default::default()
+    });
+    system.next_state(1.0 * si::S);
+    assert_eq!(receivers(&system), vec!["E"]);
+    assert_eq!(total_force(&system).x, 10.0 * si::N);
+    assert_eq!(system.get_exhausted_interactions(), &vec!["relay"]);
+
+    system.set_propagation_limits(PropagationLimits::default());
+    system.next_state(1.0 * si::S);
+    assert_eq!(receivers(&system), vec!["A"]);
+    assert!(system.get_exhausted_interactions().is_empty());
+}
+
+#[test]
+fn test_reaction_limit() {
+    let mut system = ring(6, HEXAGON);
+    system.set_propagation_limits(PropagationLimits {
+        max_reactions: 1,
+        ..Default::default()
+    });
+    system.next_state(1.0 * si::S);
+    assert_eq!(receivers(&system), vec!["C"]);
+    assert_eq!(system.get_exhausted_interactions(), &vec!["relay"]);
+}
+
+#[test]
+fn test_touching_triangle_conserves_pushing_force() {
+    let sphere = |id: &'static str, x: f64, y: f64| {
+        Entity::new(
+            id,
+            State::new(
+                Vector3d::new(x, y, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                Shape::Sphere(Sphere {
+                    radius: 0.501 * si::M,
+                }),
+            ),
+        )
+    };
+    let mut system = System::new(
+        vec![
+            sphere("A", 0.0, 0.0),
+            sphere("B", 1.0, 0.0),
+            sphere("C", 0.5, 3.0_f64.sqrt() / 2.0),
+        ],
+        vec![Box::new(ContactForces::default())],
+        0.0 * si::S,
+    );
+    let push = Vector3d::new(10.0, 10.0, 0.0) * si::N;
+    system.apply_influence(StateInfluence::force_influence(
+        "Pusher",
+        "Pusher",
+        "A",
+        "contact force",
+        push,
+    ));
+    system.next_state(1.0 * si::S);
+    let force = total_force(&system);
+    assert!((force.x - push.x).value_unsafe.abs() < 1e-9);
+    assert!((force.y - push.y).value_unsafe.abs() < 1e-9);
+    // The push is split among B and C without passing on more than was applied
+    let receivers = receivers(&system);
+    assert!(receivers.contains(&"B") && receivers.contains(&"C"));
+    for influence in system.get_last_influences() {
+        let share = influence.get_state_change().get_net_force();
+        assert!(share.x.value_unsafe >= -1e-9 && share.y.value_unsafe >= -1e-9);
+    }
+}
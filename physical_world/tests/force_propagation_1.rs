@@ -2,21 +2,21 @@ use dimensioned::si;
 use vector3d::Vector3d;
 
 use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
 use physical_machine::physics::state::state::State;
 use physical_machine::physics::system::System;
-use physical_machine::physics::state::shape::{Shape, Sphere};
 
 mod common;
 
-use common::simulation::{plot_results, write_csv};
+use common::interactions::interactions;
 use common::simulation::Simulation;
-use common::interactions::INTERACTIONS;
+use common::simulation::{plot_results, write_csv};
 
 #[test]
 fn test_force_propagation_1() {
     let name = "force_propagation_1";
     let mut log_filename = "log/".to_owned();
-    log_filename.push_str(&name);
+    log_filename.push_str(name);
     log_filename.push_str(".log");
     common::setup();
     common::logging::init_log(&log_filename);
@@ -57,19 +57,15 @@ fn test_force_propagation_1() {
     let sim = Simulation {
         simulation_time: 50.0 * si::S,
         time_step: 0.01 * si::S,
-        system: System::new(
-            vec![e1, e2, e3],
-            INTERACTIONS.to_vec(),
-            0.0 * si::S,
-        ),
+        system: System::new(vec![e1, e2, e3], interactions(), 0.0 * si::S),
     };
     let history = sim.run(false);
     let mut img_filename = "img/".to_owned();
-    img_filename.push_str(&name);
+    img_filename.push_str(name);
     img_filename.push_str(".png");
-    plot_results(&history, &img_filename, &name);
+    plot_results(&history, &img_filename, name);
     let mut csv_filename = "csv/".to_owned();
-    csv_filename.push_str(&name);
+    csv_filename.push_str(name);
     csv_filename.push_str(".csv");
     write_csv(&history, &csv_filename);
 }
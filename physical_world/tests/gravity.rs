@@ -2,21 +2,21 @@ use dimensioned::si;
 use vector3d::Vector3d;
 
 use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
 use physical_machine::physics::state::state::State;
 use physical_machine::physics::system::System;
-use physical_machine::physics::state::shape::{Shape, Sphere};
 
 mod common;
 
-use common::simulation::{plot_results, write_csv};
+use common::interactions::interactions;
 use common::simulation::Simulation;
-use common::interactions::INTERACTIONS;
+use common::simulation::{plot_results, write_csv};
 
 #[test]
 pub fn test_gravity() {
     let name = "gravity";
     let mut log_filename = "log/".to_owned();
-    log_filename.push_str(&name);
+    log_filename.push_str(name);
     log_filename.push_str(".log");
     common::setup();
     common::logging::init_log(&log_filename);
@@ -48,19 +48,15 @@ pub fn test_gravity() {
     let sim = Simulation {
         simulation_time: 300.0 * si::S,
         time_step: 0.1 * si::S,
-        system: System::new(
-            vec![e1, ground],
-            INTERACTIONS.to_vec(),
-            0.0 * si::S,
-        ),
+        system: System::new(vec![e1, ground], interactions(), 0.0 * si::S),
     };
     let history = sim.run(true);
     let mut img_filename = "img/".to_owned();
-    img_filename.push_str(&name);
+    img_filename.push_str(name);
     img_filename.push_str(".png");
-    plot_results(&history, &img_filename, &name);
+    plot_results(&history, &img_filename, name);
     let mut csv_filename = "csv/".to_owned();
-    csv_filename.push_str(&name);
+    csv_filename.push_str(name);
     csv_filename.push_str(".csv");
     write_csv(&history, &csv_filename);
 }
@@ -0,0 +1,150 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::interaction::contact_forces::ContactForces;
+use physical_machine::physics::interaction::contact_solver::ContactSolver;
+use physical_machine::physics::interaction::elastic_collision::ElasticCollision;
+use physical_machine::physics::interaction::gravity::Gravity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+
+fn ball(id: &'static str, x: f64, velocity: f64, mass: f64) -> Entity {
+    Entity::new(
+        id,
+        State::new(
+            Vector3d::new(x, 0.0, 0.0) * si::M,
+            Vector3d::new(velocity, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            mass * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 0.5 * si::M,
+            }),
+        ),
+    )
+}
+
+fn velocity(system: &System, idx: usize) -> f64 {
+    system.get_entities()[idx]
+        .get_state()
+        .get_velocity()
+        .x
+        .value_unsafe
+}
+
+fn gravity(identifier: &'static str, constant: f64) -> Gravity {
+    Gravity::new(identifier, constant, 0.0 * si::M)
+}
+
+#[test]
+fn test_gravity_interactions_with_different_constants() {
+    // 1 kg at 10 m from 100 kg, each constant pulls with constant N
+    let entities = vec![ball("A", 0.0, 0.0, 1.0), ball("B", 10.0, 0.0, 100.0)];
+    let mut weak = System::new(
+        entities.clone(),
+        vec![Box::new(gravity("weak", 1.0))],
+        0.0 * si::S,
+    );
+    let mut both = System::new(
+        entities,
+        vec![
+            Box::new(gravity("weak", 1.0)),
+            Box::new(gravity("strong", 2.0)),
+        ],
+        0.0 * si::S,
+    );
+    weak.next_state(0.1 * si::S);
+    both.next_state(0.1 * si::S);
+    assert!((velocity(&weak, 0) - 0.1).abs() < 1e-9);
+    assert!((velocity(&both, 0) - 0.3).abs() < 1e-9);
+    assert!((velocity(&both, 1) + 0.003).abs() < 1e-9);
+    // Clones own copies of the interactions
+    let fork = both.clone();
+    assert_eq!(fork.get_interactions(), both.get_interactions());
+    assert_eq!(fork.get_interactions()[1].get_identifier(), "strong");
+}
+
+#[test]
+fn test_softening_bounds_pull() {
+    let entities = vec![ball("A", 0.0, 0.0, 1.0), ball("B", 1.0, 0.0, 1.0)];
+    let mut system = System::new(
+        entities,
+        vec![Box::new(Gravity::new("Gravity", 1.0, 1.0 * si::M))],
+        0.0 * si::S,
+    );
+    system.next_state(1.0 * si::S);
+    // 1 / (1 + 1)^(3/2) instead of 1 at a distance of 1 m
+    assert!((velocity(&system, 0) - 0.5_f64.powf(1.5)).abs() < 1e-9);
+}
+
+#[test]
+fn test_contact_solver_restitution() {
+    let solver = ContactSolver {
+        restitution: 0.0,
+        ..Default::default()
+    };
+    let mut system = System::new(
+        vec![ball("A", 0.0, 1.0, 1.0), ball("B", 1.0, 0.0, 1.0)],
+        vec![Box::new(solver)],
+        0.0 * si::S,
+    );
+    system.next_state(0.01 * si::S);
+    // Inelastic, the entities move on together
+    assert!((velocity(&system, 0) - 0.5).abs() < 1e-9);
+    assert!((velocity(&system, 1) - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_collision_restitution() {
+    let collide = |restitution: f64| {
+        let mut system = System::new(
+            vec![ball("A", 0.0, 1.0, 1.0), ball("B", 1.0, 0.0, 1.0)],
+            vec![Box::new(ElasticCollision::new("collision", restitution))],
+            0.0 * si::S,
+        );
+        system.next_state(0.01 * si::S);
+        (velocity(&system, 0), velocity(&system, 1))
+    };
+    let (a, b) = collide(1.0);
+    assert!(a.abs() < 1e-9 && (b - 1.0).abs() < 1e-9);
+    // Inelastic, the entities move on together
+    let (a, b) = collide(0.0);
+    assert!((a - 0.5).abs() < 1e-9 && (b - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_contact_interactions_with_different_identifiers() {
+    let system = System::new(
+        Vec::new(),
+        vec![
+            Box::new(ContactForces::new("pushing")),
+            Box::new(ContactForces::default()),
+            Box::new(ElasticCollision::new("bouncy", 1.0)),
+            Box::new(ElasticCollision::new("sticky", 0.0)),
+        ],
+        0.0 * si::S,
+    );
+    let identifiers = system
+        .get_interactions()
+        .iter()
+        .map(|i| i.get_identifier())
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        identifiers,
+        vec!["pushing", "contact force", "bouncy", "sticky"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Identities for interactions are not unique.")]
+fn test_identifiers_stay_unique() {
+    let mut system = System::new(
+        Vec::new(),
+        vec![Box::new(gravity("weak", 1.0))],
+        0.0 * si::S,
+    );
+    system.add_interaction(Box::new(gravity("strong", 2.0)));
+    assert_eq!(system.get_interactions().len(), 2);
+    system.add_interaction(Box::new(gravity("weak", 3.0)));
+}
@@ -0,0 +1,143 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::lighting::light_source::{Emission, Falloff, LightSource};
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Cuboid, Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::sensors::light_sensor::LightSensor;
+use physical_machine::sensors::sensor::{Pose, Reading};
+use physical_machine::space::region::Region;
+
+mod common;
+
+use common::interactions::interactions;
+
+// A lamp on a ball rolling along the x axis, a wall in front of it and a column to the side
+fn system() -> System {
+    let entity = |identifier, location, velocity, shape| {
+        Entity::new(
+            identifier,
+            State::new(
+                location,
+                velocity,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                shape,
+            ),
+        )
+    };
+    let ball = entity(
+        "Ball",
+        Vector3d::new(0.0, 0.0, 0.0) * si::M,
+        Vector3d::new(1.0, 0.0, 0.0) * si::MPS,
+        Shape::Sphere(Sphere {
+            radius: 0.5 * si::M,
+        }),
+    );
+    let column = entity(
+        "Column",
+        Vector3d::new(0.0, 5.0, 0.0) * si::M,
+        Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+        Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(1.0, 1.0, 10.0) * si::M,
+        }),
+    );
+    let mut system = System::new(vec![ball, column], interactions(), 0.0 * si::S);
+    let mut pose = Pose::new(Mounting::Entity("Ball"));
+    pose.offset = Vector3d::new(0.0, 0.0, 1.0) * si::M;
+    system.add_light_source(LightSource::new(
+        "lamp",
+        pose,
+        100.0 * si::CD,
+        Falloff::InverseSquare,
+        Emission::Point,
+    ));
+    system.set_light_level("lamp", 1.0);
+    system
+}
+
+fn illuminance(system: &System, x: f64, y: f64, z: f64) -> f64 {
+    system
+        .get_illuminance_field()
+        .get_illuminance(Vector3d::new(x, y, z) * si::M)
+        .value_unsafe
+}
+
+#[test]
+fn test_sources_follow_entities() {
+    let mut system = system();
+    assert!((illuminance(&system, 0.0, -2.0, 1.0) - 25.0).abs() < 1e-9);
+    for _ in 0..100 {
+        system.next_state(0.01 * si::S);
+    }
+    assert!((illuminance(&system, 1.0, -2.0, 1.0) - 25.0).abs() < 1e-9);
+    system.set_light_level("lamp", 0.2);
+    assert!((illuminance(&system, 1.0, -2.0, 1.0) - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_shadows() {
+    let system = system();
+    assert_eq!(illuminance(&system, 0.0, 10.0, 1.0), 0.0);
+    assert!(illuminance(&system, 0.0, -10.0, 1.0) > 0.0);
+    // The lit face of the column and the ball below the lamp
+    assert!((illuminance(&system, 0.0, 4.0, 1.0) - 100.0 / 16.0).abs() < 1e-9);
+    assert!((illuminance(&system, 0.0, 0.0, 0.0) - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_average_over_region() {
+    let mut system = system();
+    let mut pose = Pose::new(Mounting::Location(Vector3d::new(20.0, 0.0, 5.0) * si::M));
+    pose.direction = Vector3d::new(0.0, 0.0, -1.0);
+    system.add_light_source(LightSource::new(
+        "spot",
+        pose,
+        400.0 * si::CD,
+        Falloff::InverseSquare,
+        Emission::Spot {
+            half_angle: std::f64::consts::FRAC_PI_4,
+        },
+    ));
+    system.set_light_level("lamp", 0.0);
+    system.set_light_level("spot", 1.0);
+    let field = system.get_illuminance_field();
+    let floor = |x| {
+        Region::cuboid(
+            Vector3d::new(x, 0.0, 0.0) * si::M,
+            Vector3d::new(2.0, 2.0, 0.05) * si::M,
+        )
+    };
+    let lit = field
+        .get_average_illuminance(&floor(20.0), 0.1 * si::M)
+        .unwrap()
+        .unwrap();
+    assert!(lit.value_unsafe > 14.0 && lit.value_unsafe < 16.0);
+    let dark = field
+        .get_average_illuminance(&floor(30.0), 0.1 * si::M)
+        .unwrap()
+        .unwrap();
+    assert_eq!(dark.value_unsafe, 0.0);
+    assert_eq!(
+        field.get_average_illuminance(&Region::Empty, 0.1 * si::M),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_light_sensor() {
+    let mut system = system();
+    let pose = Pose::new(Mounting::Location(Vector3d::new(0.0, -2.0, 1.0) * si::M));
+    system.add_sensor(Box::new(LightSensor::new("photodiode", pose, 100.0)));
+    system.next_state(0.01 * si::S);
+    match system.get_latest_measurement("photodiode").unwrap().reading {
+        Reading::Illuminance(illuminance) => {
+            let expected = 100.0 / (0.01_f64.powi(2) + 4.0);
+            assert!((illuminance.value_unsafe - expected).abs() < 1e-9);
+        }
+        ref reading => panic!("Unexpected reading {:?}", reading),
+    }
+}
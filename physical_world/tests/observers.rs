@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dimensioned::si;
+
+use physical_machine::physics::observer::StepObserver;
+use physical_machine::physics::state::state_influence::StateInfluence;
+use physical_machine::physics::system::System;
+
+mod common;
+
+use common::entities::entity;
+use common::interactions::interactions;
+
+// Writes every event into a log shared with the test
+struct Recorder {
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+impl StepObserver for Recorder {
+    fn before_step(&mut self, system: &System, step_size: si::Second<f64>) {
+        self.events.borrow_mut().push(format!(
+            "before {:.1} {:.1}",
+            system.get_current_time().value_unsafe,
+            step_size.value_unsafe
+        ));
+    }
+
+    fn influences_computed(&mut self, _system: &System, influences: &[StateInfluence]) {
+        let mut receivers: Vec<&str> = influences.iter().map(|i| i.get_receiver_id()).collect();
+        receivers.sort();
+        receivers.dedup();
+        self.events
+            .borrow_mut()
+            .push(format!("influences {}", receivers.join(",")));
+    }
+
+    fn after_step(&mut self, system: &System) {
+        self.events.borrow_mut().push(format!(
+            "after {:.1}",
+            system.get_current_time().value_unsafe
+        ));
+    }
+}
+
+#[test]
+fn test_observer_sees_every_step_in_order() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut system = System::new(
+        vec![
+            entity("A", 0.0).velocity(1.0).build(),
+            entity("B", 1.0).build(),
+        ],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.add_observer(Box::new(Recorder {
+        events: events.clone(),
+    }));
+    system.next_state(0.5 * si::S);
+    system.next_state(0.5 * si::S);
+    assert_eq!(
+        *events.borrow(),
+        vec![
+            "before 0.0 0.5",
+            "influences A,B",
+            "after 0.5",
+            "before 0.5 0.5",
+            // A stopped and B moves away after the collision
+            "influences ",
+            "after 1.0"
+        ]
+    );
+}
+
+#[test]
+fn test_forks_are_not_observed() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut system = System::new(
+        vec![entity("A", 0.0).velocity(1.0).build()],
+        Vec::new(),
+        0.0 * si::S,
+    );
+    system.add_observer(Box::new(Recorder {
+        events: events.clone(),
+    }));
+    let mut fork = system.clone();
+    fork.next_state(1.0 * si::S);
+    assert!(events.borrow().is_empty());
+    system.next_state(1.0 * si::S);
+    assert_eq!(
+        *events.borrow(),
+        vec!["before 0.0 1.0", "influences ", "after 1.0"]
+    );
+}
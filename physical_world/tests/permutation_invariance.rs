@@ -0,0 +1,82 @@
+use dimensioned::si;
+
+use physical_machine::physics::interaction::contact_solver::ContactSolver;
+use physical_machine::physics::position_correction::PositionCorrection;
+use physical_machine::physics::system::System;
+use physical_machine::scene::examples::{get_example, get_example_names};
+
+mod common;
+
+use common::entities::entity;
+use common::interactions::interactions;
+use common::permutation::assert_permutation_invariant;
+
+#[test]
+fn test_examples() {
+    for name in get_example_names() {
+        let scene = get_example(name).unwrap().unwrap();
+        let system = scene.to_system().unwrap();
+        assert_permutation_invariant(&system, scene.get_time_step(), scene.get_step_count());
+    }
+}
+
+#[test]
+fn test_simultaneous_collisions_different_masses() {
+    let system = System::new(
+        vec![
+            entity("A", 15.0)
+                .velocity(1.0)
+                .mass(100.0)
+                .radius(5.0)
+                .build(),
+            entity("B", 75.0)
+                .velocity(-1.0)
+                .mass(50.0)
+                .radius(5.0)
+                .build(),
+            entity("C", 45.0).mass(100.0).radius(5.0).build(),
+        ],
+        interactions(),
+        0.0 * si::S,
+    );
+    assert_permutation_invariant(&system, 0.01 * si::S, 5000);
+}
+
+#[test]
+fn test_opposing_forces() {
+    // Forces pushing the outer spheres into the middle one
+    let system = System::new(
+        vec![
+            entity("A", 30.0).mass(100.0).radius(2.0).build(),
+            entity("B", 34.0)
+                .force(-10.0)
+                .mass(100.0)
+                .radius(2.0)
+                .build(),
+            entity("C", 26.0)
+                .force(10.0)
+                .mass(100.0)
+                .radius(2.0)
+                .build(),
+        ],
+        interactions(),
+        0.0 * si::S,
+    );
+    assert_permutation_invariant(&system, 0.01 * si::S, 10000);
+}
+
+#[test]
+fn test_contact_solver_with_position_correction() {
+    let mut system = System::new(
+        vec![
+            entity("A", 0.0).velocity(1.0).build(),
+            entity("B", 0.9).mass(2.0).build(),
+            entity("C", 1.8).build(),
+            entity("D", 2.9).velocity(-1.0).mass(3.0).build(),
+        ],
+        vec![Box::new(ContactSolver::default())],
+        0.0 * si::S,
+    );
+    system.set_position_correction(Some(PositionCorrection::default()));
+    assert_permutation_invariant(&system, 0.01 * si::S, 300);
+}
@@ -0,0 +1,118 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::plotting::chart::{Chart, ImageFormat, PlotError};
+use physical_machine::plotting::series::{
+    phase_space, quantity_over_time, system_energy_over_time, system_momentum_over_time,
+    trajectory, Component, Projection,
+};
+use physical_machine::recording::columns::Quantity;
+
+mod common;
+
+use common::interactions::interactions;
+use common::simulation::Simulation;
+
+const IDENTIFIERS: [&str; 8] = ["A", "B", "C", "D", "E", "F", "G", "H"];
+
+fn history() -> Vec<System> {
+    let entities = IDENTIFIERS
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            let angle = idx as f64 * std::f64::consts::PI / 4.0;
+            Entity::new(
+                id,
+                State::new(
+                    Vector3d::new(20.0 * angle.cos(), 20.0 * angle.sin(), idx as f64) * si::M,
+                    Vector3d::new(angle.cos(), angle.sin(), 0.1) * si::MPS,
+                    Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                    10.0 * si::KG,
+                    Shape::Sphere(Sphere {
+                        radius: 1.0 * si::M,
+                    }),
+                ),
+            )
+        })
+        .collect();
+    let sim = Simulation {
+        simulation_time: 5.0 * si::S,
+        time_step: 0.1 * si::S,
+        system: System::new(entities, interactions(), 0.0 * si::S),
+    };
+    sim.run(false).into_iter().map(|s| s.system).collect()
+}
+
+#[test]
+fn test_plot_quantities() {
+    let history = history();
+    let velocity = quantity_over_time(&history, Quantity::Velocity, Component::Norm);
+    assert_eq!(velocity.len(), IDENTIFIERS.len());
+    assert!(velocity.iter().all(|s| s.data.len() == history.len()));
+    let mut chart = Chart::new("velocity", "Time [s]", "Velocity [m/s]");
+    chart.size = (800, 600);
+    chart.plot(&velocity, "img/plotting_velocity.svg").unwrap();
+    let svg = std::fs::read_to_string("img/plotting_velocity.svg").unwrap();
+    assert!(svg.contains("width=\"800\""));
+    for id in IDENTIFIERS {
+        assert!(
+            svg.contains(&format!(">\n{}\n</text>", id)),
+            "Missing legend entry {}.",
+            id
+        );
+    }
+
+    let energy = vec![
+        system_energy_over_time(&history),
+        system_momentum_over_time(&history, Component::Z),
+    ];
+    Chart::new("energy and momentum", "Time [s]", "[J], [Ns]")
+        .plot(&energy, "img/plotting_energy.png")
+        .unwrap();
+    assert!(std::fs::metadata("img/plotting_energy.png").unwrap().len() > 0);
+}
+
+#[test]
+fn test_plot_projections() {
+    let history = history();
+    let xy = trajectory(&history, Projection::XY);
+    let first = xy[1].data[0];
+    assert!((first.0 - 20.0 * (std::f64::consts::PI / 4.0).cos()).abs() < 1e-9);
+    assert!((first.1 - 20.0 * (std::f64::consts::PI / 4.0).sin()).abs() < 1e-9);
+    Chart::new("trajectory", "x [m]", "y [m]")
+        .plot(&xy, "img/plotting_trajectory_xy.svg")
+        .unwrap();
+    Chart::new("trajectory", "x [m]", "z [m]")
+        .plot(
+            &trajectory(&history, Projection::XZ),
+            "img/plotting_trajectory_xz.png",
+        )
+        .unwrap();
+
+    let phase = phase_space(&history, Component::X);
+    assert_eq!(phase[0].data[0], (20.0, 1.0));
+    let mut chart = Chart::new("phase space", "x [m]", "v_x [m/s]");
+    chart.format = Some(ImageFormat::Svg);
+    chart
+        .plot(&phase, "img/plotting_phase_space.image")
+        .unwrap();
+}
+
+#[test]
+fn test_plot_errors() {
+    let chart = Chart::new("empty", "x", "y");
+    assert!(matches!(
+        chart.plot(&[], "img/plotting_empty.svg"),
+        Err(PlotError::NoData)
+    ));
+    assert!(!std::path::Path::new("img/plotting_empty.svg").exists());
+    let series = quantity_over_time(&history(), Quantity::Mass, Component::X);
+    assert!(matches!(
+        chart.plot(&series, "img/plotting_mass.txt"),
+        Err(PlotError::UnknownFormat(_))
+    ));
+}
@@ -0,0 +1,124 @@
+use dimensioned::{si, Sqrt};
+use vector3d::Vector3d;
+
+use physical_machine::physics::interaction::helpers::get_penetration_depth;
+use physical_machine::physics::position_correction::PositionCorrection;
+use physical_machine::physics::state::shape::{Cuboid, Shape};
+use physical_machine::physics::system::System;
+
+mod common;
+
+use common::entities::entity;
+
+fn center_of_mass(system: &System) -> f64 {
+    let entities = system.get_entities();
+    let mass: f64 = entities
+        .iter()
+        .map(|e| e.get_state().get_mass().value_unsafe)
+        .sum();
+    entities
+        .iter()
+        .map(|e| {
+            e.get_state().get_mass().value_unsafe * e.get_state().get_location().x.value_unsafe
+        })
+        .sum::<f64>()
+        / mass
+}
+
+fn depth(system: &System, idx0: usize, idx1: usize) -> f64 {
+    let entities = system.get_entities();
+    get_penetration_depth(&entities[idx0], &entities[idx1])
+        .unwrap()
+        .value_unsafe
+}
+
+#[test]
+fn test_overlap_stays_without_correction() {
+    let mut system = System::new(
+        vec![entity("A", 0.0).build(), entity("B", 0.6).build()],
+        Vec::new(),
+        0.0 * si::S,
+    );
+    assert_eq!(system.get_position_correction(), None);
+    system.next_state(0.1 * si::S);
+    assert!(system.get_corrections().is_empty());
+    assert!((depth(&system, 0, 1) - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn test_separation_conserves_momentum_and_center_of_mass() {
+    // A runs deep into B within one large step
+    let mut system = System::new(
+        vec![
+            entity("A", 0.0).velocity(8.0).build(),
+            entity("B", 1.5).mass(3.0).build(),
+        ],
+        Vec::new(),
+        0.0 * si::S,
+    );
+    system.set_position_correction(Some(PositionCorrection::default()));
+    let momentum = system.get_momentum();
+    system.next_state(0.1 * si::S);
+    // Overlap of 0.3 m, A moves three times as far as B
+    let corrections = system.get_corrections();
+    assert_eq!(corrections.len(), 1);
+    assert_eq!(corrections[0].entities, ("A", "B"));
+    assert!((corrections[0].depth.value_unsafe - 0.3).abs() < 1e-5);
+    let locations: Vec<f64> = system
+        .get_entities()
+        .iter()
+        .map(|e| e.get_state().get_location().x.value_unsafe)
+        .collect();
+    assert!((locations[0] - (0.8 - 0.225)).abs() < 1e-5);
+    assert!((locations[1] - (1.5 + 0.075)).abs() < 1e-5);
+    assert!((depth(&system, 0, 1) - 1e-6).abs() < 1e-9);
+    assert!((center_of_mass(&system) - (0.8 + 4.5) / 4.0).abs() < 1e-9);
+    assert_eq!(system.get_momentum(), momentum);
+}
+
+#[test]
+fn test_sphere_leaves_cuboid_through_closest_face() {
+    let wall = entity("Wall", 0.0)
+        .mass(1e12)
+        .shape(Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(1.0, 5.0, 5.0) * si::M,
+        }))
+        .build();
+    let mut system = System::new(
+        vec![wall, entity("Ball", 0.8).build()],
+        Vec::new(),
+        0.0 * si::S,
+    );
+    system.set_position_correction(Some(PositionCorrection {
+        slop: 0.0 * si::M,
+        ..PositionCorrection::default()
+    }));
+    system.next_state(0.01 * si::S);
+    let ball = system.get_entities()[1].get_state().get_location();
+    assert!((ball.x.value_unsafe - 1.5).abs() < 1e-9);
+    assert!(depth(&system, 0, 1).abs() < 1e-9);
+}
+
+#[test]
+fn test_iterations_resolve_chains() {
+    let mut system = System::new(
+        vec![
+            entity("A", 0.0).build(),
+            entity("B", 0.8).build(),
+            entity("C", 1.6).build(),
+        ],
+        Vec::new(),
+        0.0 * si::S,
+    );
+    system.set_position_correction(Some(PositionCorrection {
+        slop: 0.0 * si::M,
+        factor: 1.0,
+        iterations: 50,
+    }));
+    system.next_state(0.01 * si::S);
+    assert!(depth(&system, 0, 1) < 1e-6);
+    assert!(depth(&system, 1, 2) < 1e-6);
+    assert!((center_of_mass(&system) - 0.8).abs() < 1e-9);
+    let momentum = system.get_momentum();
+    assert!(momentum.norm2().sqrt() == 0.0 * si::NS);
+}
@@ -0,0 +1,174 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::actuators::thruster::Thruster;
+use physical_machine::physics::system::System;
+use physical_machine::prediction::action::{Action, ActionError};
+use physical_machine::prediction::predictor::{EventKind, Predictor};
+
+mod common;
+
+use common::entities::{entity, EntityBuilder};
+use common::interactions::interactions;
+
+// Spheres of the chains of these tests
+fn sphere(id: &'static str, x: f64) -> EntityBuilder {
+    entity(id, x).mass(100.0).radius(5.0)
+}
+
+// Chain of three touching spheres
+fn chain() -> System {
+    System::new(
+        vec![
+            sphere("A", 30.0).build(),
+            sphere("B", 40.0).build(),
+            sphere("C", 20.0).build(),
+        ],
+        interactions(),
+        0.0 * si::S,
+    )
+}
+
+#[test]
+fn test_force_propagates_through_contacts() {
+    let system = chain();
+    let predictor = Predictor::new(1.0 * si::S, 0.01 * si::S);
+    let outcome = predictor
+        .predict(
+            &system,
+            &[Action::ApplyForce {
+                entity: "C",
+                force: Vector3d::new(300.0, 0.0, 0.0) * si::N,
+                start: 0.0 * si::S,
+                duration: 1.0 * si::S,
+            }],
+        )
+        .unwrap();
+    // The chain is pushed as a whole, 300 N on 300 kg for a second
+    assert!((outcome.momentum.x - 300.0 * si::NS).value_unsafe.abs() < 1e-6);
+    for id in ["A", "B", "C"] {
+        let velocity = outcome.get_state(id).unwrap().get_velocity();
+        assert!(
+            (velocity.x - 1.0 * si::MPS).value_unsafe.abs() < 0.05,
+            "{} moves at {}",
+            id,
+            velocity
+        );
+    }
+    assert!(outcome.is_in_contact("A", "B"));
+    assert!(outcome.is_in_contact("A", "C"));
+}
+
+#[test]
+fn test_live_system_is_untouched() {
+    let system = chain();
+    let before = system.get_entities().clone();
+    let predictor = Predictor::new(0.5 * si::S, 0.01 * si::S);
+    predictor
+        .predict(
+            &system,
+            &[
+                Action::ChangeVelocity {
+                    entity: "B",
+                    velocity_change: Vector3d::new(2.0, 0.0, 0.0) * si::MPS,
+                    at: 0.0 * si::S,
+                },
+                Action::Spawn {
+                    entity: sphere("D", 60.0).build(),
+                    at: 0.1 * si::S,
+                },
+            ],
+        )
+        .unwrap();
+    assert_eq!(system.get_current_time(), 0.0 * si::S);
+    assert_eq!(system.get_entities().len(), 3);
+    for (entity, original) in system.get_entities().iter().zip(before.iter()) {
+        assert_eq!(entity.get_state(), original.get_state());
+    }
+    assert!(system.get_external_influences().is_empty());
+}
+
+#[test]
+fn test_compare_candidates() {
+    let system = chain();
+    let predictor = Predictor::new(2.0 * si::S, 0.01 * si::S);
+    let candidates = [0.0, 5.0, 15.0]
+        .iter()
+        .map(|speed| {
+            vec![Action::Spawn {
+                entity: sphere("D", 80.0).velocity(-*speed).build(),
+                at: 0.0 * si::S,
+            }]
+        })
+        .collect::<Vec<Vec<Action>>>();
+    let outcomes = predictor.predict_all(&system, &candidates).unwrap();
+    assert_eq!(outcomes.len(), 3);
+    // Only the fastest sphere reaches the chain within the horizon
+    let hits = outcomes
+        .iter()
+        .map(|o| {
+            o.events
+                .iter()
+                .any(|e| e.kind == EventKind::ContactStarted("B", "D"))
+        })
+        .collect::<Vec<bool>>();
+    assert_eq!(hits, vec![false, false, true]);
+}
+
+#[test]
+fn test_input_signal_of_actuator() {
+    let mut system = System::new(vec![sphere("A", 0.0).build()], interactions(), 0.0 * si::S);
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("A"),
+        Vector3d::new(100.0, 0.0, 0.0) * si::N,
+    )));
+    let predictor = Predictor::new(1.0 * si::S, 0.01 * si::S);
+    let outcome = predictor
+        .predict(
+            &system,
+            &[Action::SetInputSignal {
+                actuator: "thruster",
+                signal: 1.0,
+                at: 0.5 * si::S,
+            }],
+        )
+        .unwrap();
+    let velocity = outcome.get_state("A").unwrap().get_velocity();
+    assert!((velocity.x - 0.5 * si::MPS).value_unsafe.abs() < 1e-9);
+    assert_eq!(system.get_actuators()[0].get_input_signal(), 0.0);
+}
+
+#[test]
+fn test_actions_on_missing_entities_fail() {
+    let system = chain();
+    let predictor = Predictor::new(0.5 * si::S, 0.01 * si::S);
+    let push = |entity: &'static str, at: f64| Action::ChangeVelocity {
+        entity,
+        velocity_change: Vector3d::new(1.0, 0.0, 0.0) * si::MPS,
+        at: at * si::S,
+    };
+    let spawn = |at: f64| Action::Spawn {
+        entity: sphere("D", 60.0).build(),
+        at: at * si::S,
+    };
+    assert_eq!(
+        predictor.predict(&system, &[push("Ghost", 0.0)]).err(),
+        Some(ActionError::UnknownEntity("Ghost"))
+    );
+    // Spawned entities can be acted on once they exist
+    assert!(predictor
+        .predict(&system, &[spawn(0.1), push("D", 0.2)])
+        .is_ok());
+    assert_eq!(
+        predictor
+            .predict(&system, &[spawn(0.2), push("D", 0.1)])
+            .err(),
+        Some(ActionError::UnknownEntity("D"))
+    );
+    assert_eq!(
+        predictor.predict(&system, &[spawn(0.1), spawn(0.2)]).err(),
+        Some(ActionError::DuplicateIdentity("D"))
+    );
+}
@@ -0,0 +1,189 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Cuboid, Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::sensors::proximity_sensor::ProximitySensor;
+use physical_machine::sensors::sensor::{Pose, Reading};
+use physical_machine::space::raycast::{Ray, RayFilter};
+
+mod common;
+
+use common::interactions::interactions;
+
+fn entity(identifier: &'static str, x: f64, y: f64, shape: Shape) -> Entity {
+    Entity::new(
+        identifier,
+        State::new(
+            Vector3d::new(x, y, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            1.0 * si::KG,
+            shape,
+        ),
+    )
+}
+
+// A ball in front of a wall, with a second ball behind the wall
+fn system() -> System {
+    let mut wall = entity(
+        "Wall",
+        10.0,
+        0.0,
+        Shape::Cuboid(Cuboid {
+            half_extents: Vector3d::new(0.5, 5.0, 5.0) * si::M,
+        }),
+    );
+    wall.add_tag("static");
+    let ball = |identifier, x, y| {
+        entity(
+            identifier,
+            x,
+            y,
+            Shape::Sphere(Sphere {
+                radius: 1.0 * si::M,
+            }),
+        )
+    };
+    System::new(
+        vec![ball("Ball", 5.0, 3.0), wall, ball("Hidden", 15.0, 0.0)],
+        interactions(),
+        0.0 * si::S,
+    )
+}
+
+#[test]
+fn test_cast_ray() {
+    let system = system();
+    let origin = Vector3d::new(0.0, 0.0, 0.0) * si::M;
+    let forward = Ray::new(origin, Vector3d::new(1.0, 0.0, 0.0));
+    let hit = system
+        .cast_ray(&forward, 100.0 * si::M, &RayFilter::new())
+        .unwrap();
+    assert_eq!(hit.entity, "Wall");
+    assert_eq!(hit.distance, 9.5 * si::M);
+    assert_eq!(hit.normal, Vector3d::new(-1.0, 0.0, 0.0));
+    // Seeing through the static parts of the world
+    let dynamic = RayFilter::new().excluding_tag("static");
+    let hit = system.cast_ray(&forward, 100.0 * si::M, &dynamic).unwrap();
+    assert_eq!(hit.entity, "Hidden");
+    assert_eq!(hit.point, Vector3d::new(14.0, 0.0, 0.0) * si::M);
+    let towards_ball = Ray::new(origin, Vector3d::new(5.0, 3.0, 0.0));
+    let hit = system
+        .cast_ray(&towards_ball, 100.0 * si::M, &RayFilter::new())
+        .unwrap();
+    assert_eq!(hit.entity, "Ball");
+    assert!((hit.distance.value_unsafe - (34.0_f64.sqrt() - 1.0)).abs() < 1e-9);
+}
+
+#[test]
+fn test_cast_segment() {
+    let system = system();
+    let origin = Vector3d::new(0.0, 0.0, 0.0) * si::M;
+    let filter = RayFilter::new();
+    assert!(system
+        .cast_segment(origin, Vector3d::new(9.0, 0.0, 0.0) * si::M, &filter)
+        .is_none());
+    assert_eq!(
+        system
+            .cast_segment(origin, Vector3d::new(20.0, 0.0, 0.0) * si::M, &filter)
+            .unwrap()
+            .entity,
+        "Wall"
+    );
+    // Segments leaving an entity do not hit it
+    assert!(system
+        .cast_segment(
+            Vector3d::new(5.0, 3.0, 0.0) * si::M,
+            Vector3d::new(5.0, 10.0, 0.0) * si::M,
+            &filter
+        )
+        .is_none());
+}
+
+#[test]
+fn test_proximity_sensor_occlusion() {
+    // The large ball crosses the view of the sensor and hides the small one
+    let ball = |identifier, x, y, radius| {
+        entity(
+            identifier,
+            x,
+            y,
+            Shape::Sphere(Sphere {
+                radius: radius * si::M,
+            }),
+        )
+    };
+    let mut system = System::new(
+        vec![ball("Large", 5.0, 3.0, 3.5), ball("Hidden", 15.0, 0.0, 1.0)],
+        interactions(),
+        0.0 * si::S,
+    );
+    let pose = Pose {
+        direction: Vector3d::new(1.0, 0.0, 0.0),
+        ..Pose::new(Mounting::Location(Vector3d::new(0.0, 0.0, 0.0) * si::M))
+    };
+    system.add_sensor(Box::new(ProximitySensor::new(
+        "narrow",
+        pose,
+        100.0,
+        20.0 * si::M,
+        0.1,
+    )));
+    system.add_sensor(Box::new(ProximitySensor::new(
+        "wide",
+        pose,
+        100.0,
+        20.0 * si::M,
+        1.0,
+    )));
+    system.next_state(0.01 * si::S);
+    let distance = |sensor_id| match system.get_latest_measurement(sensor_id).unwrap().reading {
+        Reading::Distance(distance) => distance,
+        ref reading => panic!("Unexpected reading {:?}", reading),
+    };
+    // Distance along the edge of the narrow view towards the center of the large ball
+    let edge = 5.0 * 0.1_f64.cos() + 3.0 * 0.1_f64.sin();
+    let narrow = distance("narrow").unwrap();
+    assert!((narrow.value_unsafe - (edge - (edge * edge - 21.75).sqrt())).abs() < 1e-9);
+    let wide = distance("wide").unwrap();
+    assert!((wide.value_unsafe - (34.0_f64.sqrt() - 3.5)).abs() < 1e-9);
+}
+
+#[test]
+fn test_proximity_sensor_off_axis() {
+    // The closest point of the ball is outside of the view, but the ball reaches into it
+    let mut system = System::new(
+        vec![entity(
+            "Ball",
+            5.0,
+            4.0,
+            Shape::Sphere(Sphere {
+                radius: 2.0 * si::M,
+            }),
+        )],
+        interactions(),
+        0.0 * si::S,
+    );
+    let pose = Pose {
+        direction: Vector3d::new(1.0, 0.0, 0.0),
+        ..Pose::new(Mounting::Location(Vector3d::new(0.0, 0.0, 0.0) * si::M))
+    };
+    system.add_sensor(Box::new(ProximitySensor::new(
+        "sensor",
+        pose,
+        100.0,
+        20.0 * si::M,
+        0.5,
+    )));
+    system.next_state(0.01 * si::S);
+    let distance = match system.get_latest_measurement("sensor").unwrap().reading {
+        Reading::Distance(distance) => distance.unwrap(),
+        ref reading => panic!("Unexpected reading {:?}", reading),
+    };
+    let edge = 5.0 * 0.5_f64.cos() + 4.0 * 0.5_f64.sin();
+    assert!((distance.value_unsafe - (edge - (edge * edge - 37.0).sqrt())).abs() < 1e-9);
+}
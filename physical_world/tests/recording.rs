@@ -0,0 +1,120 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::recording::columns::{Columns, Quantity};
+use physical_machine::recording::csv_recorder::CsvRecorder;
+use physical_machine::recording::json_lines_recorder::JsonLinesRecorder;
+use physical_machine::recording::recorder::{Recorder, RecorderError};
+use physical_machine::recording::sampling::Sampling;
+
+mod common;
+
+use common::interactions::interactions;
+use common::simulation::Simulation;
+
+fn collision_simulation() -> Simulation {
+    let radius = 5.0 * si::M;
+    let e1 = Entity::new(
+        "A",
+        State::new(
+            Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            Vector3d::new(1.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            100.0 * si::KG,
+            Shape::Sphere(Sphere { radius }),
+        ),
+    );
+    let e2 = Entity::new(
+        "B",
+        State::new(
+            Vector3d::new(20.0, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            100.0 * si::KG,
+            Shape::Sphere(Sphere { radius }),
+        ),
+    );
+    Simulation {
+        simulation_time: 20.0 * si::S,
+        time_step: 0.1 * si::S,
+        system: System::new(vec![e1, e2], interactions(), 0.0 * si::S),
+    }
+}
+
+#[test]
+fn test_stream_recorders() {
+    let mut csv_recorder = CsvRecorder::new(
+        Vec::new(),
+        Sampling::Decimation(10),
+        Columns::new(Some(vec!["B"]), vec![Quantity::Location]),
+    )
+    .unwrap();
+    let mut json_recorder = JsonLinesRecorder::new(
+        Vec::new(),
+        Sampling::Interval(1.0 * si::S),
+        Columns::new(None, vec![Quantity::Velocity, Quantity::Mass]),
+    )
+    .unwrap();
+    let history = collision_simulation()
+        .run_recorded(false, &mut [&mut csv_recorder, &mut json_recorder])
+        .unwrap();
+
+    let csv_output = String::from_utf8(csv_recorder.into_inner().unwrap()).unwrap();
+    let csv_lines = csv_output.lines().collect::<Vec<&str>>();
+    assert_eq!(csv_lines[0], "time,B_location_x,B_location_y,B_location_z");
+    assert_eq!(csv_lines.len() - 1, history.len().div_ceil(10));
+
+    let json_output = String::from_utf8(json_recorder.into_inner().unwrap()).unwrap();
+    let rows = json_output
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .collect::<Vec<serde_json::Value>>();
+    assert_eq!(rows.len(), 21);
+    for (idx, row) in rows.iter().enumerate() {
+        let time = row["time"].as_f64().unwrap();
+        assert!((time - idx as f64).abs() < 0.1 + 1e-9);
+        assert_eq!(row["entities"][0]["identity"], "A");
+        assert_eq!(row["entities"][1]["mass"], 100.0);
+        assert!(row["entities"][1].get("location").is_none());
+    }
+    // After the collision, the momentum of A has been transferred to B
+    let last = rows.last().unwrap();
+    assert!(last["entities"][1]["velocity"][0].as_f64().unwrap() > 0.99);
+}
+
+#[test]
+fn test_unknown_entity() {
+    let mut recorder = CsvRecorder::new(
+        Vec::new(),
+        Sampling::EveryStep,
+        Columns::new(Some(vec!["missing"]), vec![Quantity::Location]),
+    )
+    .unwrap();
+    let system = collision_simulation().system;
+    match recorder.record(&system) {
+        Err(RecorderError::UnknownEntity(id)) => assert_eq!(id, "missing"),
+        _ => panic!("Expected unknown entity error."),
+    }
+}
+
+#[test]
+fn test_io_error() {
+    let result = CsvRecorder::create(
+        "csv/missing_directory/recording.csv",
+        Sampling::EveryStep,
+        Columns::all(),
+    );
+    assert!(matches!(result, Err(RecorderError::Io(_))));
+}
+
+#[test]
+fn test_invalid_sampling() {
+    let path = "csv/invalid_sampling.csv";
+    let result = CsvRecorder::create(path, Sampling::Decimation(0), Columns::all());
+    assert!(matches!(result, Err(RecorderError::InvalidSampling(_))));
+    assert!(!std::path::Path::new(path).exists());
+}
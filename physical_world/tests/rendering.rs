@@ -0,0 +1,116 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Cuboid, Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::plotting::animation::SceneRenderer;
+use physical_machine::plotting::chart::PlotError;
+use physical_machine::plotting::series::Projection;
+
+mod common;
+
+use common::interactions::interactions;
+use common::simulation::Simulation;
+
+fn history() -> Vec<System> {
+    let radius = 1.0 * si::M;
+    let e1 = Entity::new(
+        "A",
+        State::new(
+            Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            Vector3d::new(4.0, 1.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            10.0 * si::KG,
+            Shape::Sphere(Sphere { radius }),
+        ),
+    );
+    let e2 = Entity::new(
+        "B",
+        State::new(
+            Vector3d::new(5.0, 2.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            10.0 * si::KG,
+            Shape::Sphere(Sphere { radius }),
+        ),
+    );
+    let wall = Entity::new(
+        "Wall",
+        State::new(
+            Vector3d::new(12.0, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            1.0e6 * si::KG,
+            Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(1.0, 5.0, 5.0) * si::M,
+            }),
+        ),
+    );
+    let sim = Simulation {
+        simulation_time: 2.0 * si::S,
+        time_step: 0.01 * si::S,
+        system: System::new(vec![e1, e2, wall], interactions(), 0.0 * si::S),
+    };
+    sim.run(false).into_iter().map(|s| s.system).collect()
+}
+
+#[test]
+fn test_frame_selection() {
+    let history = history();
+    let renderer = SceneRenderer::new(Projection::XY, 4.0);
+    let frames = renderer
+        .select_frames(&history.iter().collect::<Vec<&System>>())
+        .unwrap();
+    assert_eq!(frames.len(), 9);
+    for (idx, frame) in frames.iter().enumerate() {
+        let frame_time = idx as f64 / 4.0;
+        let time = frame.get_current_time().value_unsafe;
+        assert!(time <= frame_time + 1e-9 && time > frame_time - 0.01);
+    }
+}
+
+#[test]
+fn test_render_svg_frames() {
+    let history = history();
+    let mut renderer = SceneRenderer::new(Projection::XY, 10.0);
+    renderer.influence_scale = Some(0.001);
+    let paths = renderer
+        .render_svg_frames(&history, "img/rendering_frames")
+        .unwrap();
+    assert_eq!(paths.len(), 21);
+    let frame = std::fs::read_to_string(&paths[5]).unwrap();
+    assert!(frame.contains("t = 0.500 s"));
+    assert!(frame.contains("<circle"));
+    assert!(frame.contains("<rect"));
+}
+
+#[test]
+fn test_render_gif() {
+    let history = history();
+    let mut renderer = SceneRenderer::new(Projection::XZ, 5.0);
+    renderer.size = (320, 240);
+    let frame_count = renderer.render_gif(&history, "img/rendering.gif").unwrap();
+    assert_eq!(frame_count, 11);
+    let gif = std::fs::read("img/rendering.gif").unwrap();
+    assert_eq!(&gif[0..6], b"GIF89a");
+}
+
+#[test]
+fn test_invalid_frame_rate() {
+    let history = history();
+    for frame_rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let mut renderer = SceneRenderer::new(Projection::XY, 1.0);
+        renderer.set_frame_rate(frame_rate);
+        assert!(matches!(
+            renderer.render_gif(&history, "img/rendering_invalid.gif"),
+            Err(PlotError::InvalidFrameRate(_))
+        ));
+        assert!(matches!(
+            renderer.render_svg_frames(&history, "img/rendering_invalid"),
+            Err(PlotError::InvalidFrameRate(_))
+        ));
+    }
+    assert!(!std::path::Path::new("img/rendering_invalid.gif").exists());
+}
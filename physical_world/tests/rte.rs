@@ -0,0 +1,221 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::actuators::thruster::Thruster;
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::rte::controller::Controller;
+use physical_machine::rte::observer::Observer;
+use physical_machine::rte::phenomenon::{Condition, PhenomenonDescription, StateInstance};
+use physical_machine::rte::runtime_environment::{RteError, RuntimeEnvironment};
+use physical_machine::sensors::position_sensor::PositionSensor;
+use physical_machine::sensors::sensor::Pose;
+use physical_machine::sensors::velocity_sensor::VelocitySensor;
+use physical_machine::space::region::Region;
+use physical_machine::virtualization::location::EntityLocation;
+use physical_machine::virtualization::velocity::EntityVelocity;
+use physical_machine::virtualization::virtual_actuator::{DesiredChange, VelocityActuator};
+use physical_machine::virtualization::virtual_sensor::{Observation, Value};
+
+mod common;
+
+use common::interactions::interactions;
+
+const STEP: f64 = 0.01;
+
+// A ball at rest with a thruster and sensors
+fn system() -> System {
+    let ball = Entity::new(
+        "Ball",
+        State::new(
+            Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            1.0 * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 1.0 * si::M,
+            }),
+        ),
+    );
+    let mut system = System::new(vec![ball], interactions(), 0.0 * si::S);
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("Ball"),
+        Vector3d::new(10.0, 0.0, 0.0) * si::N,
+    )));
+    let pose = Pose::new(Mounting::Entity("Ball"));
+    system.add_sensor(Box::new(PositionSensor::new("gps", pose, 100.0)));
+    system.add_sensor(Box::new(VelocitySensor::new("speedometer", pose, 100.0)));
+    system
+}
+
+fn observer() -> Observer {
+    Observer::new(vec![
+        Box::new(EntityLocation::new("location", "Ball", 0.05 * si::S)),
+        Box::new(EntityVelocity::new("velocity", "Ball", 0.05 * si::S)),
+    ])
+}
+
+fn controller() -> Controller {
+    Controller::new(vec![Box::new(VelocityActuator::new("velocity", 1e-6))])
+}
+
+fn system_region() -> Region {
+    Region::Sphere {
+        center: Vector3d::new(0.0, 0.0, 0.0) * si::M,
+        radius: 1000.0 * si::M,
+    }
+}
+
+fn rte(descriptions: Vec<Box<dyn PhenomenonDescription>>) -> RuntimeEnvironment {
+    RuntimeEnvironment::new(descriptions, observer(), controller(), system_region()).unwrap()
+}
+
+// Default phenomenon accelerating everything as fast as possible
+struct Push;
+
+impl PhenomenonDescription for Push {
+    fn get_identifier(&self) -> &'static str {
+        "push"
+    }
+
+    fn state(&self) -> Vec<Condition> {
+        Vec::new()
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn behavior(&self, state: &StateInstance) -> Vec<DesiredChange> {
+        vec![DesiredChange {
+            region: state.region.clone(),
+            value: Value::Velocity(Vector3d::new(0.1, 0.0, 0.0) * si::MPS),
+        }]
+    }
+}
+
+fn is_fast(observation: &Observation) -> bool {
+    match observation.value {
+        Value::Velocity(v) => v.x.value_unsafe > 1.0,
+        _ => false,
+    }
+}
+
+fn is_anywhere(_: &Observation) -> bool {
+    true
+}
+
+// Keeps the velocity of fast entities
+struct SpeedLimit;
+
+impl PhenomenonDescription for SpeedLimit {
+    fn get_identifier(&self) -> &'static str {
+        "speed limit"
+    }
+
+    fn state(&self) -> Vec<Condition> {
+        vec![
+            Condition {
+                sensor: "location",
+                rule: is_anywhere,
+            },
+            Condition {
+                sensor: "velocity",
+                rule: is_fast,
+            },
+        ]
+    }
+
+    fn priority(&self) -> i32 {
+        1
+    }
+
+    fn behavior(&self, state: &StateInstance) -> Vec<DesiredChange> {
+        let center = match state.observations[0].value {
+            Value::Location(location) => location,
+            _ => return Vec::new(),
+        };
+        vec![DesiredChange {
+            region: Region::Sphere {
+                center,
+                radius: 0.5 * si::M,
+            },
+            value: Value::Velocity(Vector3d::new(0.0, 0.0, 0.0) * si::MPS),
+        }]
+    }
+}
+
+#[test]
+fn test_default_phenomenon() {
+    let mut system = system();
+    let rte = rte(vec![Box::new(Push)]);
+    assert!(rte.run(&mut system, STEP * si::S, 100).is_empty());
+    let velocity = system.get_entities()[0].get_state().get_velocity();
+    assert!((velocity.x.value_unsafe - 10.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_priorities() {
+    let mut system = system();
+    let rte = rte(vec![Box::new(SpeedLimit), Box::new(Push)]);
+    assert_eq!(rte.get_ordered_descriptions()[0].get_identifier(), "push");
+    assert!(rte.run(&mut system, STEP * si::S, 300).is_empty());
+    let velocity = system.get_entities()[0].get_state().get_velocity();
+    assert!(velocity.x.value_unsafe > 1.0);
+    assert!(velocity.x.value_unsafe < 1.1 + 1e-6);
+}
+
+#[test]
+fn test_unobservable_state() {
+    let mut system = system();
+    let rte = rte(vec![Box::new(SpeedLimit)]);
+    system.set_input_signal("thruster", 1.0);
+    // Without readings the speed limit does not apply
+    let errors = rte.call_behaviors(&mut system, STEP * si::S);
+    assert!(errors.is_empty());
+    assert_eq!(system.get_actuators()[0].get_input_signal(), 1.0);
+    rte.run(&mut system, STEP * si::S, 200);
+    assert_eq!(system.get_actuators()[0].get_input_signal(), 0.0);
+}
+
+#[test]
+fn test_invalid_runtime_environments() {
+    let duplicate_sensors = Observer::new(vec![
+        Box::new(EntityLocation::new("location", "Ball", 0.05 * si::S)),
+        Box::new(EntityVelocity::new("location", "Ball", 0.05 * si::S)),
+    ]);
+    assert_eq!(
+        RuntimeEnvironment::new(Vec::new(), duplicate_sensors, controller(), system_region()).err(),
+        Some(RteError::DuplicateSensor("location"))
+    );
+    let duplicate_actuators = Controller::new(vec![
+        Box::new(VelocityActuator::new("velocity", 1e-6)),
+        Box::new(VelocityActuator::new("velocity", 1e-3)),
+    ]);
+    assert_eq!(
+        RuntimeEnvironment::new(Vec::new(), observer(), duplicate_actuators, system_region()).err(),
+        Some(RteError::DuplicateActuator("velocity"))
+    );
+    let location_only = Observer::new(vec![Box::new(EntityLocation::new(
+        "location",
+        "Ball",
+        0.05 * si::S,
+    ))]);
+    assert_eq!(
+        RuntimeEnvironment::new(
+            vec![Box::new(Push), Box::new(SpeedLimit)],
+            location_only,
+            controller(),
+            system_region()
+        )
+        .err(),
+        Some(RteError::UnknownSensor {
+            description: "speed limit",
+            sensor: "velocity"
+        })
+    );
+}
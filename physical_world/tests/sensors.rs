@@ -0,0 +1,220 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::actuators::thruster::Thruster;
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Cuboid, Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::sensors::accelerometer::Accelerometer;
+use physical_machine::sensors::contact_sensor::ContactSensor;
+use physical_machine::sensors::error_model::ErrorModel;
+use physical_machine::sensors::position_sensor::PositionSensor;
+use physical_machine::sensors::proximity_sensor::ProximitySensor;
+use physical_machine::sensors::sensor::{
+    is_valid_sampling_rate, Measurement, MeasurementRegion, Pose, Reading,
+};
+use physical_machine::sensors::velocity_sensor::VelocitySensor;
+
+mod common;
+
+use common::interactions::interactions;
+
+// A ball starting at rest is pushed towards a wall
+fn system() -> System {
+    let ball = Entity::new(
+        "Ball",
+        State::new(
+            Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            10.0 * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 1.0 * si::M,
+            }),
+        ),
+    );
+    let wall = Entity::new(
+        "Wall",
+        State::new(
+            Vector3d::new(10.0, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            1.0e6 * si::KG,
+            Shape::Cuboid(Cuboid {
+                half_extents: Vector3d::new(1.0, 5.0, 5.0) * si::M,
+            }),
+        ),
+    );
+    let mut system = System::new(vec![ball, wall], interactions(), 0.0 * si::S);
+    system.add_actuator(Box::new(Thruster::new(
+        "thruster",
+        Mounting::Entity("Ball"),
+        Vector3d::new(20.0, 0.0, 0.0) * si::N,
+    )));
+    system.set_input_signal("thruster", 1.0);
+    system
+}
+
+fn run(system: &mut System, steps: usize) -> Vec<Measurement> {
+    let mut measurements = Vec::new();
+    for _ in 0..steps {
+        system.next_state(0.01 * si::S);
+        measurements.extend(system.get_last_measurements().iter().cloned());
+    }
+    measurements
+}
+
+fn readings_of(measurements: &[Measurement], sensor_id: &str) -> Vec<Reading> {
+    measurements
+        .iter()
+        .filter(|m| m.sensor_id == sensor_id)
+        .map(|m| m.reading.clone())
+        .collect()
+}
+
+#[test]
+fn test_sampling_rate() {
+    let mut system = system();
+    let mut pose = Pose::new(Mounting::Entity("Ball"));
+    pose.offset = Vector3d::new(0.0, 1.0, 0.0) * si::M;
+    system.add_sensor(Box::new(PositionSensor::new("gps", pose, 10.0)));
+    system.add_sensor(Box::new(VelocitySensor::new("speedometer", pose, 100.0)));
+    let measurements = run(&mut system, 100);
+    let positions = readings_of(&measurements, "gps");
+    assert_eq!(positions.len(), 10);
+    assert_eq!(readings_of(&measurements, "speedometer").len(), 100);
+    match &positions[0] {
+        Reading::Position(location) => {
+            assert_eq!(location.y, 1.0 * si::M);
+        }
+        reading => panic!("Unexpected reading {:?}", reading),
+    }
+    let times = measurements
+        .iter()
+        .filter(|m| m.sensor_id == "gps")
+        .map(|m| m.time.value_unsafe)
+        .collect::<Vec<f64>>();
+    for (idx, time) in times.iter().enumerate() {
+        assert!((time - (0.01 + idx as f64 * 0.1)).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_invalid_sampling_rates() {
+    let pose = Pose::new(Mounting::Entity("Ball"));
+    for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(!is_valid_sampling_rate(rate));
+        let constructed = std::panic::catch_unwind(|| Accelerometer::new("imu", pose, rate));
+        assert!(constructed.is_err(), "{}", rate);
+    }
+    assert!(is_valid_sampling_rate(1e-3));
+}
+
+#[test]
+fn test_motion_sensors() {
+    let mut system = system();
+    let pose = Pose::new(Mounting::Entity("Ball"));
+    system.add_sensor(Box::new(VelocitySensor::new("speedometer", pose, 100.0)));
+    system.add_sensor(Box::new(Accelerometer::new("accelerometer", pose, 100.0)));
+    let measurements = run(&mut system, 100);
+    // 20 N on 10 kg
+    for reading in readings_of(&measurements, "accelerometer") {
+        assert_eq!(
+            reading,
+            Reading::Acceleration(Vector3d::new(2.0, 0.0, 0.0) * si::MPS2)
+        );
+    }
+    match readings_of(&measurements, "speedometer").last() {
+        Some(Reading::Velocity(velocity)) => {
+            assert!((velocity.x.value_unsafe - 2.0).abs() < 1e-9);
+        }
+        reading => panic!("Unexpected reading {:?}", reading),
+    }
+}
+
+#[test]
+fn test_proximity_sensor() {
+    let mut system = system();
+    let mut pose = Pose::new(Mounting::Entity("Ball"));
+    pose.direction = Vector3d::new(1.0, 0.0, 0.0);
+    let sensor = ProximitySensor::new("lidar", pose, 100.0, 8.5 * si::M, 0.1);
+    system.add_sensor(Box::new(sensor));
+    match system.get_sensors()[0].get_measurement_region(system.get_entities()) {
+        Some(MeasurementRegion::Cone { range, .. }) => assert_eq!(range, 8.5 * si::M),
+        region => panic!("Unexpected region {:?}", region),
+    }
+    let measurements = run(&mut system, 200);
+    let distances = readings_of(&measurements, "lidar")
+        .into_iter()
+        .map(|r| match r {
+            Reading::Distance(d) => d.map(|d| d.value_unsafe),
+            reading => panic!("Unexpected reading {:?}", reading),
+        })
+        .collect::<Vec<Option<f64>>>();
+    // The wall comes into range as the ball approaches it
+    assert_eq!(distances[0], None);
+    let last = distances.last().unwrap().unwrap();
+    // Surface of the wall at 9 m, ball after 2 s at 4 m
+    assert!((last - 5.0).abs() < 0.05, "{}", last);
+    assert!(distances.windows(2).all(|w| match (w[0], w[1]) {
+        (Some(d0), Some(d1)) => d1 <= d0,
+        (None, _) => true,
+        (Some(_), None) => false,
+    }));
+}
+
+#[test]
+fn test_contact_sensors() {
+    let mut system = system();
+    system.add_sensor(Box::new(ContactSensor::new(
+        "bumper",
+        Pose::new(Mounting::Entity("Wall")),
+        100.0,
+    )));
+    system.add_sensor(Box::new(ContactSensor::new(
+        "plate",
+        Pose::new(Mounting::Location(Vector3d::new(5.0, 0.0, 0.0) * si::M)),
+        100.0,
+    )));
+    let measurements = run(&mut system, 300);
+    let bumper = readings_of(&measurements, "bumper");
+    assert_eq!(bumper[0], Reading::Contacts(Vec::new()));
+    assert!(bumper.contains(&Reading::Contacts(vec!["Ball"])));
+    let plate = readings_of(&measurements, "plate");
+    assert_eq!(plate[0], Reading::Contacts(Vec::new()));
+    assert!(plate.contains(&Reading::Contacts(vec!["Ball"])));
+}
+
+fn noisy_run(seed: u64) -> Vec<Measurement> {
+    let mut system = system();
+    system.set_seed(seed);
+    let pose = Pose::new(Mounting::Entity("Ball"));
+    system.add_sensor(Box::new(PositionSensor::new("gps", pose, 100.0)));
+    system.set_error_models(
+        "gps",
+        vec![
+            ErrorModel::Gaussian {
+                standard_deviation: 0.1,
+            },
+            ErrorModel::Dropout { probability: 0.1 },
+        ],
+    );
+    run(&mut system, 100)
+}
+
+#[test]
+fn test_noise_is_reproducible() {
+    let first = noisy_run(3);
+    let second = noisy_run(3);
+    let other = noisy_run(4);
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert!(first.len() < 100 && first.len() > 70, "{}", first.len());
+    // Noise is added to the true location, which stays on the x axis
+    assert!(first.iter().any(|m| match &m.reading {
+        Reading::Position(location) => location.y != 0.0 * si::M,
+        _ => false,
+    }));
+}
@@ -0,0 +1,339 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use serde_json::{json, Value};
+
+use physical_machine::server::server::{handle_request, serve, serve_connection};
+use physical_machine::server::session::{
+    Session, INVALID_PARAMS, INVALID_REQUEST, MAX_STEPS, METHOD_NOT_FOUND, PARSE_ERROR,
+    SESSION_ERROR,
+};
+
+const SCENE: &str = r#"{
+    "name": "probe",
+    "duration": 1.0,
+    "time_step": 0.1,
+    "interactions": ["contact force", "elastic collision"],
+    "entities": [
+        {"identity": "Probe", "location": [0.0, 0.0, 0.0], "velocity": [0.0, 0.0, 0.0], "mass": 2.0,
+         "shape": {"sphere": {"radius": 0.5}}},
+        {"identity": "Wall", "location": [10.0, 0.0, 0.0], "velocity": [0.0, 0.0, 0.0], "mass": 1000.0,
+         "shape": {"sphere": {"radius": 1.0}}}
+    ],
+    "sensors": [
+        {"identity": "gps", "kind": "position", "mounting": {"entity": "Probe"}, "sampling_rate": 10.0}
+    ],
+    "actuators": [
+        {"identity": "thruster", "kind": "thruster", "max_force": [2.0, 0.0, 0.0],
+         "mounting": {"entity": "Probe"}}
+    ]
+}"#;
+
+fn call(session: &mut Session, id: u64, method: &str, params: Value) -> Value {
+    let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+    let response = handle_request(session, &request.to_string()).unwrap();
+    assert_eq!(response["id"], json!(id));
+    response
+}
+
+fn result(session: &mut Session, method: &str, params: Value) -> Value {
+    let response = call(session, 1, method, params);
+    assert!(response.get("error").is_none(), "{}", response);
+    response["result"].clone()
+}
+
+fn error_code(response: &Value) -> i64 {
+    response["error"]["code"].as_i64().unwrap()
+}
+
+fn loaded_session() -> Session {
+    let mut session = Session::new();
+    let scene: Value = serde_json::from_str(SCENE).unwrap();
+    let loaded = result(&mut session, "load_scene", json!({"scene": scene}));
+    assert_eq!(loaded["entities"], json!(["Probe", "Wall"]));
+    assert_eq!(loaded["sensors"], json!(["gps"]));
+    assert_eq!(loaded["actuators"], json!(["thruster"]));
+    session
+}
+
+fn location_x(entities: &Value, id: &str) -> f64 {
+    entities
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["identity"] == json!(id))
+        .unwrap()["location"][0]
+        .as_f64()
+        .unwrap()
+}
+
+#[test]
+fn test_load_example() {
+    let mut session = Session::new();
+    let loaded = result(
+        &mut session,
+        "load_scene",
+        json!({"example": "newton_pendulum_1"}),
+    );
+    assert_eq!(loaded["entities"].as_array().unwrap().len(), 5);
+    assert_eq!(result(&mut session, "get_time", json!({})), json!(0.0));
+    let error = call(&mut session, 2, "load_scene", json!({"example": "unknown"}));
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+    let error = call(&mut session, 3, "load_scene", json!({}));
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+}
+
+#[test]
+fn test_actuate_and_measure() {
+    let mut session = loaded_session();
+    result(
+        &mut session,
+        "set_input_signal",
+        json!({"actuator": "thruster", "signal": 1.0}),
+    );
+    assert_eq!(
+        result(&mut session, "get_actuators", json!({})),
+        json!([{"identity": "thruster", "signal": 1.0}])
+    );
+    let stepped = result(&mut session, "step", json!({"steps": 10}));
+    assert!((stepped["time"].as_f64().unwrap() - 1.0).abs() < 1e-9);
+    // 2 N on 2 kg for a second
+    let entities = result(&mut session, "get_entities", json!({}));
+    assert!((location_x(&entities, "Probe") - 0.5).abs() < 0.1);
+    assert_eq!(location_x(&entities, "Wall"), 10.0);
+    let measurements = result(
+        &mut session,
+        "get_measurements",
+        json!({"sensors": ["gps"]}),
+    );
+    let measurements = measurements.as_array().unwrap();
+    assert_eq!(measurements.len(), 1);
+    assert_eq!(measurements[0]["sensor"], json!("gps"));
+    assert!(measurements[0]["reading"]["position"].is_array());
+
+    let error = call(
+        &mut session,
+        2,
+        "set_input_signal",
+        json!({"actuator": "brake", "signal": 1.0}),
+    );
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+    let error = call(
+        &mut session,
+        3,
+        "get_measurements",
+        json!({"sensors": ["radar"]}),
+    );
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+}
+
+#[test]
+fn test_release_velocity_setter() {
+    let mut session = Session::new();
+    let mut scene: Value = serde_json::from_str(SCENE).unwrap();
+    scene["actuators"] = json!([{"identity": "setter", "kind": "velocity_setter",
+                                 "direction": [1.0, 0.0, 0.0], "mounting": {"entity": "Probe"}}]);
+    result(&mut session, "load_scene", json!({"scene": scene}));
+    result(
+        &mut session,
+        "set_input_signal",
+        json!({"actuator": "setter", "signal": 1.0}),
+    );
+    result(&mut session, "step", json!({}));
+    result(
+        &mut session,
+        "release_actuator",
+        json!({"actuator": "setter"}),
+    );
+    assert_eq!(
+        result(&mut session, "get_actuators", json!({})),
+        json!([{"identity": "setter", "signal": null}])
+    );
+    // The released probe keeps its velocity of 1 m/s
+    result(&mut session, "step", json!({"steps": 5}));
+    let entities = result(&mut session, "get_entities", json!({}));
+    let velocity = entities[0]["velocity"][0].as_f64().unwrap();
+    assert!((velocity - 1.0).abs() < 1e-9, "{}", entities);
+    let error = call(
+        &mut session,
+        2,
+        "release_actuator",
+        json!({"actuator": "thruster"}),
+    );
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+}
+
+#[test]
+fn test_predict_leaves_session_unchanged() {
+    let mut session = loaded_session();
+    let outcome = result(
+        &mut session,
+        "predict",
+        json!({
+            "horizon": 10.0,
+            "actions": [
+                {"kind": "change_velocity", "entity": "Probe", "velocity_change": [2.0, 0.0, 0.0], "at": 0.0}
+            ]
+        }),
+    );
+    assert!((outcome["end_time"].as_f64().unwrap() - 10.0).abs() < 1e-9);
+    let events = outcome["events"].as_array().unwrap();
+    assert!(events.iter().any(
+        |e| e["kind"] == json!("contact_started") && e["entities"] == json!(["Probe", "Wall"])
+    ));
+    // The prediction runs on a fork
+    assert_eq!(result(&mut session, "get_time", json!({})), json!(0.0));
+    let entities = result(&mut session, "get_entities", json!({}));
+    assert_eq!(location_x(&entities, "Probe"), 0.0);
+
+    let error = call(
+        &mut session,
+        2,
+        "predict",
+        json!({"horizon": 1.0, "actions": [
+            {"kind": "change_velocity", "entity": "Ghost", "velocity_change": [1.0, 0.0, 0.0], "at": 0.0}
+        ]}),
+    );
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+    let error = call(&mut session, 3, "predict", json!({"horizon": -1.0}));
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+    // Requests that would keep the server busy for every client
+    for params in [
+        json!({"horizon": 1e300}),
+        json!({"horizon": 1.0, "step_size": 1e-300}),
+    ] {
+        let error = call(&mut session, 4, "predict", params);
+        assert_eq!(error_code(&error), INVALID_PARAMS);
+    }
+    let error = call(&mut session, 5, "step", json!({"steps": MAX_STEPS + 1}));
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+}
+
+#[test]
+fn test_invalid_spawns() {
+    let mut session = loaded_session();
+    let drone = |identity: &str, mass: f64| {
+        json!({"kind": "spawn", "at": 0.0, "entity": {
+            "identity": identity, "location": [5.0, 0.0, 0.0], "mass": mass,
+            "shape": {"sphere": {"radius": 0.5}}
+        }})
+    };
+    let invalid = [
+        vec![drone("Drone", 1.0), drone("Drone", 1.0)],
+        vec![drone("Wall", 1.0)],
+        vec![drone("Drone", 0.0)],
+        vec![drone("Drone", f64::INFINITY)],
+        vec![json!({"kind": "spawn", "at": 0.0, "entity": {
+            "identity": "Drone", "location": [5.0, 0.0, 0.0], "mass": 1.0
+        }})],
+    ];
+    for actions in invalid {
+        let error = call(
+            &mut session,
+            2,
+            "predict",
+            json!({"horizon": 1.0, "actions": actions}),
+        );
+        assert_eq!(error_code(&error), INVALID_PARAMS, "{}", error);
+    }
+    let outcome = result(
+        &mut session,
+        "predict",
+        json!({"horizon": 1.0, "actions": [drone("Drone", 1.0)]}),
+    );
+    assert_eq!(outcome["entities"].as_array().unwrap().len(), 3);
+    // Later actions may act on spawned entities
+    let push = json!({"kind": "change_velocity", "entity": "Drone",
+                      "velocity_change": [1.0, 0.0, 0.0], "at": 0.5});
+    let outcome = result(
+        &mut session,
+        "predict",
+        json!({"horizon": 1.0, "actions": [drone("Drone", 1.0), push]}),
+    );
+    assert!(location_x(&outcome["entities"], "Drone") > 5.0);
+    let error = call(
+        &mut session,
+        3,
+        "predict",
+        json!({"horizon": 1.0, "actions": [push, drone("Drone", 1.0)]}),
+    );
+    assert_eq!(error_code(&error), INVALID_PARAMS);
+}
+
+#[test]
+fn test_protocol_errors() {
+    let mut session = Session::new();
+    let response = handle_request(&mut session, "{").unwrap();
+    assert_eq!(error_code(&response), PARSE_ERROR);
+    assert_eq!(response["id"], Value::Null);
+    let response = handle_request(&mut session, r#"{"id": 4, "method": "step"}"#).unwrap();
+    assert_eq!(error_code(&response), INVALID_REQUEST);
+    assert_eq!(response["id"], json!(4));
+    assert_eq!(
+        error_code(&call(&mut session, 5, "teleport", json!({}))),
+        METHOD_NOT_FOUND
+    );
+    assert_eq!(
+        error_code(&call(&mut session, 6, "step", json!({}))),
+        SESSION_ERROR
+    );
+    assert_eq!(
+        error_code(&call(&mut session, 7, "step", json!({"stride": 2}))),
+        INVALID_PARAMS
+    );
+    // Notifications are executed without a response
+    let notification = json!({"jsonrpc": "2.0", "method": "load_scene",
+                              "params": {"example": "newton_pendulum_1"}});
+    assert!(handle_request(&mut session, &notification.to_string()).is_none());
+    assert!(session.get_system().is_some());
+}
+
+#[test]
+fn test_serve_connection_line_by_line() {
+    let mut session = Session::new();
+    let input = concat!(
+        r#"{"jsonrpc": "2.0", "id": 1, "method": "load_scene", "params": {"example": "newton_pendulum_1"}}"#,
+        "\n\n",
+        r#"{"jsonrpc": "2.0", "id": 2, "method": "step", "params": {"steps": 2, "time_step": 0.5}}"#,
+        "\n",
+        r#"{"jsonrpc": "2.0", "id": 3, "method": "shutdown"}"#,
+        "\n",
+        r#"{"jsonrpc": "2.0", "id": 4, "method": "get_time"}"#,
+        "\n",
+    );
+    let mut output = Vec::new();
+    serve_connection(&mut session, input.as_bytes(), &mut output).unwrap();
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // Requests after the shutdown are not answered
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[1]["result"]["time"], json!(1.0));
+    assert!(session.is_shut_down());
+}
+
+#[test]
+fn test_serve_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || serve(listener).unwrap());
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request = |request: Value| -> Value {
+        writeln!(stream, "{}", request).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        serde_json::from_str(&line).unwrap()
+    };
+    let loaded = request(json!({"jsonrpc": "2.0", "id": 1, "method": "load_scene",
+                                "params": {"path": "scenes/wall_collisions.json"}}));
+    assert!(loaded.get("result").is_some(), "{}", loaded);
+    let stepped = request(json!({"jsonrpc": "2.0", "id": 2, "method": "step"}));
+    assert!(stepped["result"]["time"].as_f64().unwrap() > 0.0);
+    let stopped = request(json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}));
+    assert_eq!(stopped["result"], Value::Null);
+    server.join().unwrap();
+}
@@ -0,0 +1,99 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::interaction::contact_forces::ContactForces;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::physics::tolerance::{Tolerance, Tolerances};
+
+// Millimetre robot of a gram
+fn robot(id: &'static str, x: f64, force: f64) -> Entity {
+    Entity::new(
+        id,
+        State::new(
+            Vector3d::new(x, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(force, 0.0, 0.0) * si::N,
+            1e-3 * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 1e-3 * si::M,
+            }),
+        ),
+    )
+}
+
+fn pushed_velocity(system: &mut System) -> f64 {
+    system.next_state(1.0 * si::S);
+    system.get_entities()[1]
+        .get_state()
+        .get_velocity()
+        .x
+        .value_unsafe
+}
+
+#[test]
+fn test_tiny_forces_need_small_tolerances() {
+    // A pushes B with 5 pN
+    let robots = vec![robot("A", 0.0, 5e-12), robot("B", 1.999e-3, 0.0)];
+    let mut system = System::new(
+        robots.clone(),
+        vec![Box::new(ContactForces::default())],
+        0.0 * si::S,
+    );
+    assert_eq!(pushed_velocity(&mut system), 0.0);
+
+    let mut system = System::new(
+        robots.clone(),
+        vec![Box::new(ContactForces::default())],
+        0.0 * si::S,
+    );
+    system.set_tolerances(Tolerances::relative(1e-9));
+    assert!(pushed_velocity(&mut system) > 0.0);
+
+    // Interactions added later receive the tolerances of the system
+    let mut system = System::new(robots, Vec::new(), 0.0 * si::S);
+    system.set_tolerances(Tolerances::relative(1e-9));
+    system.add_interaction(Box::new(ContactForces::default()));
+    assert_eq!(system.get_tolerances(), Tolerances::relative(1e-9));
+    assert!(pushed_velocity(&mut system) > 0.0);
+}
+
+#[test]
+fn test_huge_states_compare_relatively() {
+    // A planet one astronomical unit from the sun, located with rounding errors
+    let planet = |x: f64| {
+        State::new(
+            Vector3d::new(x, 0.0, 0.0) * si::M,
+            Vector3d::new(0.0, 29.78e3, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            5.97e24 * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 6.371e6 * si::M,
+            }),
+        )
+    };
+    let state0 = planet(1.496e11);
+    let state1 = planet(1.496e11 + 1e-4);
+    assert!(state0 != state1);
+    assert!(!state0.approx_eq(&state1, &Tolerances::default()));
+    assert!(state0.approx_eq(&state1, &Tolerances::relative(1e-12)));
+    assert!(!state0.approx_eq(&planet(1.497e11), &Tolerances::relative(1e-12)));
+
+    // Systems compare states with their own tolerances
+    let mut system = System::new(Vec::new(), Vec::new(), 0.0 * si::S);
+    assert!(!system.are_close(&state0, &state1));
+    system.set_tolerances(Tolerances::relative(1e-12));
+    assert!(system.are_close(&state0, &state1));
+}
+
+#[test]
+fn test_tolerance() {
+    let tolerance = Tolerance::new(1e-3, 1e-6);
+    assert!(tolerance.is_negligible(1e-3 * si::N, 0.0 * si::N));
+    assert!(!tolerance.is_negligible(2e-3 * si::N, 1.0 * si::N));
+    assert!(tolerance.is_negligible(2e-3 * si::N, 1e4 * si::N));
+    assert!(tolerance.are_close(1e6 * si::KG, 1e6 * si::KG + 0.5 * si::KG));
+    assert!(!tolerance.are_close(1e6 * si::KG, 1e6 * si::KG + 2.0 * si::KG));
+}
@@ -0,0 +1,239 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::interaction::interaction::Interaction;
+use physical_machine::physics::state::shape::Shape;
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::state::state_influence::StateInfluence;
+use physical_machine::physics::system::System;
+use physical_machine::physics::validation::{Invariant, Phase, Validation, ValidationMode};
+use physical_machine::utils::identity::Identity;
+
+mod common;
+
+use common::entities::entity;
+use common::interactions::interactions;
+
+// Changes the state of its receiver once per step, on behalf of entity A
+#[derive(Clone, Copy)]
+struct Faulty {
+    identifier: &'static str,
+    receiver: &'static str,
+    force: f64,
+    mass: f64,
+}
+
+impl Interaction for Faulty {
+    fn get_identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn clone_box(&self) -> Box<dyn Interaction> {
+        Box::new(*self)
+    }
+
+    fn is_neighbor(&self, _transmitter: &Entity, _receiver: &Entity) -> bool {
+        false
+    }
+
+    fn init(
+        &self,
+        source: &Entity,
+        _neighbors: Vec<&Entity>,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        if source.get_identity() != "A" {
+            return Vec::new();
+        }
+        vec![StateInfluence::new(
+            self.receiver,
+            self.receiver,
+            self.receiver,
+            self.identifier,
+            State::new(
+                Vector3d::new(0.0, 0.0, 0.0) * si::M,
+                Vector3d::new(0.0, 0.0, 0.0) * si::MPS,
+                Vector3d::new(self.force, 0.0, 0.0) * si::N,
+                self.mass * si::KG,
+                Shape::None,
+            ),
+        )]
+    }
+
+    fn react(
+        &self,
+        _reactor: &Entity,
+        _neighbors: Vec<&Entity>,
+        _influence: StateInfluence,
+        _step_size: si::Second<f64>,
+    ) -> Vec<StateInfluence> {
+        Vec::new()
+    }
+}
+
+const GHOST: Faulty = Faulty {
+    identifier: "ghost",
+    receiver: "Ghost",
+    force: 1.0,
+    mass: 0.0,
+};
+const INFINITE: Faulty = Faulty {
+    identifier: "infinite",
+    receiver: "A",
+    force: f64::INFINITY,
+    mass: 0.0,
+};
+const DRAIN: Faulty = Faulty {
+    identifier: "drain",
+    receiver: "A",
+    force: 0.0,
+    mass: -2.0,
+};
+
+fn system(interaction: Faulty, mode: ValidationMode) -> System {
+    let mut system = System::new(
+        vec![entity("A", 0.0).build(), entity("B", 5.0).build()],
+        vec![Box::new(interaction)],
+        0.0 * si::S,
+    );
+    system.set_validation(Validation::new(mode));
+    system
+}
+
+#[test]
+fn test_validation_is_off_by_default() {
+    let mut system = System::new(
+        vec![entity("A", 0.0).build(), entity("B", 5.0).build()],
+        vec![Box::new(GHOST)],
+        0.0 * si::S,
+    );
+    assert_eq!(system.get_validation().mode, ValidationMode::Off);
+    system.next_state(0.1 * si::S);
+    assert!(system.get_violations().is_empty());
+}
+
+#[test]
+fn test_unknown_receiver() {
+    let mut system = system(GHOST, ValidationMode::Report);
+    system.next_state(0.1 * si::S);
+    let violations = system.get_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].entity, "Ghost");
+    assert_eq!(violations[0].interactions, vec!["ghost"]);
+    assert_eq!(violations[0].phase, Phase::Influences);
+    assert_eq!(violations[0].invariant, Invariant::UnknownReceiver);
+}
+
+#[test]
+fn test_unknown_receiver_of_external_influences() {
+    let mut system = System::new(
+        vec![entity("A", 0.0).build(), entity("B", 5.0).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.set_validation(Validation::new(ValidationMode::Report));
+    let push = |interaction| {
+        StateInfluence::force_influence(
+            "A",
+            "A",
+            "Ghost",
+            interaction,
+            Vector3d::new(1.0, 0.0, 0.0) * si::N,
+        )
+    };
+    // Propagated by an interaction of the system and applied directly
+    system.apply_influence(push("contact force"));
+    system.apply_influence(push("thruster"));
+    system.next_state(0.1 * si::S);
+    let violations = system.get_violations();
+    assert_eq!(violations.len(), 2);
+    for (violation, interaction) in violations.iter().zip(["contact force", "thruster"]) {
+        assert_eq!(violation.entity, "Ghost");
+        assert_eq!(violation.interactions, vec![interaction]);
+        assert_eq!(violation.time, 0.0 * si::S);
+        assert_eq!(violation.invariant, Invariant::UnknownReceiver);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Receiver of influence not found.")]
+fn test_unknown_receiver_without_validation() {
+    let mut system = system(GHOST, ValidationMode::Off);
+    system.apply_influence(StateInfluence::force_influence(
+        "A",
+        "A",
+        "Ghost",
+        "ghost",
+        Vector3d::new(1.0, 0.0, 0.0) * si::N,
+    ));
+}
+
+#[test]
+fn test_non_finite_influence_and_state() {
+    let mut system = system(INFINITE, ValidationMode::Report);
+    system.next_state(0.1 * si::S);
+    let invariants: Vec<(Phase, Invariant)> = system
+        .get_violations()
+        .iter()
+        .map(|v| (v.phase, v.invariant))
+        .collect();
+    assert_eq!(
+        invariants,
+        vec![
+            (Phase::Influences, Invariant::NonFiniteInfluence),
+            (Phase::Evolution, Invariant::NonFiniteState)
+        ]
+    );
+    assert_eq!(system.get_violations()[1].interactions, vec!["infinite"]);
+}
+
+#[test]
+fn test_non_positive_mass() {
+    let mut system = system(DRAIN, ValidationMode::Report);
+    system.next_state(1.0 * si::S);
+    let violations = system.get_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].entity, "A");
+    assert_eq!(violations[0].interactions, vec!["drain"]);
+    assert_eq!(violations[0].time, 0.0 * si::S);
+    assert_eq!(
+        violations[0].invariant,
+        Invariant::NonPositiveMass(-1.0 * si::KG)
+    );
+    // Violations are kept until the next step
+    system.set_validation(Validation::default());
+    system.next_state(1.0 * si::S);
+    assert!(system.get_violations().is_empty());
+}
+
+#[test]
+fn test_interpenetration() {
+    let mut system = System::new(
+        vec![entity("A", 0.0).build(), entity("B", 0.8).build()],
+        interactions(),
+        0.0 * si::S,
+    );
+    system.set_validation(Validation {
+        mode: ValidationMode::Report,
+        penetration_tolerance: 0.1 * si::M,
+    });
+    system.next_state(0.01 * si::S);
+    let violations = system.get_violations();
+    assert_eq!(violations.len(), 1);
+    match violations[0].invariant {
+        Invariant::Interpenetration { other, depth } => {
+            assert_eq!(violations[0].entity, "A");
+            assert_eq!(other, "B");
+            assert!((depth - 0.2 * si::M).value_unsafe.abs() < 0.01);
+        }
+        invariant => panic!("Unexpected {:?}", invariant),
+    }
+    assert!(violations[0].to_string().contains("overlaps B"));
+}
+
+#[test]
+#[should_panic(expected = "entity Ghost (influenced by ghost) receives an influence")]
+fn test_panic_mode() {
+    system(GHOST, ValidationMode::Panic).next_state(0.1 * si::S);
+}
@@ -0,0 +1,189 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::actuators::brake::Brake;
+use physical_machine::actuators::thruster::Thruster;
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::space::region::Region;
+use physical_machine::virtualization::virtual_actuator::{
+    ActuationError, DesiredChange, VelocityActuator, VirtualActuator,
+};
+use physical_machine::virtualization::virtual_sensor::Value;
+
+mod common;
+
+use common::interactions::interactions;
+
+const STEP: f64 = 0.1;
+
+// A ball of one kilogram, far away from a second one
+fn system(velocity: Vector3d<si::MeterPerSecond<f64>>) -> System {
+    let ball = |identifier, x| {
+        Entity::new(
+            identifier,
+            State::new(
+                Vector3d::new(x, 0.0, 0.0) * si::M,
+                velocity,
+                Vector3d::new(0.0, 0.0, 0.0) * si::N,
+                1.0 * si::KG,
+                Shape::Sphere(Sphere {
+                    radius: 1.0 * si::M,
+                }),
+            ),
+        )
+    };
+    System::new(
+        vec![ball("Ball", 0.0), ball("Other", 100.0)],
+        interactions(),
+        0.0 * si::S,
+    )
+}
+
+fn thruster(identifier: &'static str, x: f64, y: f64) -> Box<Thruster> {
+    Box::new(Thruster::new(
+        identifier,
+        Mounting::Entity("Ball"),
+        Vector3d::new(x, y, 0.0) * si::N,
+    ))
+}
+
+fn change(x: f64, y: f64) -> DesiredChange {
+    DesiredChange {
+        region: Region::Sphere {
+            center: Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            radius: 1.0 * si::M,
+        },
+        value: Value::Velocity(Vector3d::new(x, y, 0.0) * si::MPS),
+    }
+}
+
+#[test]
+fn test_single_actuator() {
+    let mut system = system(Vector3d::new(0.0, 0.0, 0.0) * si::MPS);
+    system.add_actuator(thruster("weak", 2.0, 0.0));
+    system.add_actuator(thruster("strong", 10.0, 0.0));
+    system.add_actuator(thruster("side", 0.0, 10.0));
+    let actuator = VelocityActuator::new("velocity", 1e-6);
+    let plan = actuator
+        .plan(&system, &change(0.5, 0.0), STEP * si::S)
+        .unwrap();
+    assert_eq!(plan.signals.len(), 1);
+    assert_eq!(plan.signals[0].0, "strong");
+    assert!((plan.signals[0].1 - 0.5).abs() < 1e-9);
+    plan.apply(&mut system);
+    system.next_state(STEP * si::S);
+    let velocity = system.get_entities()[0].get_state().get_velocity();
+    assert!((velocity.x.value_unsafe - 0.5).abs() < 1e-9);
+    assert_eq!(
+        system.get_entities()[1]
+            .get_state()
+            .get_velocity()
+            .x
+            .value_unsafe,
+        0.0
+    );
+}
+
+#[test]
+fn test_combined_actuators() {
+    let mut system = system(Vector3d::new(0.0, 0.0, 0.0) * si::MPS);
+    system.add_actuator(thruster("weak", 2.0, 0.0));
+    system.add_actuator(thruster("strong", 10.0, 0.0));
+    system.add_actuator(thruster("side", 0.0, 10.0));
+    let actuator = VelocityActuator::new("velocity", 1e-6);
+    let mut plan = actuator
+        .plan(&system, &change(1.1, -0.5), STEP * si::S)
+        .unwrap();
+    plan.signals.sort_by_key(|(actuator_id, _)| *actuator_id);
+    assert_eq!(plan.signals.len(), 3);
+    assert_eq!(plan.signals[0].0, "side");
+    assert!((plan.signals[0].1 + 0.5).abs() < 1e-9);
+    plan.apply(&mut system);
+    system.next_state(STEP * si::S);
+    let velocity = system.get_entities()[0].get_state().get_velocity();
+    assert!((velocity.x.value_unsafe - 1.1).abs() < 1e-6);
+    assert!((velocity.y.value_unsafe + 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_current_signals_are_taken_into_account() {
+    let mut system = system(Vector3d::new(2.0, 0.0, 0.0) * si::MPS);
+    system.add_actuator(thruster("thruster", 10.0, 0.0));
+    system.set_input_signal("thruster", 1.0);
+    system.add_actuator(Box::new(Brake::new(
+        "brake",
+        Mounting::Entity("Ball"),
+        100.0 * si::N,
+    )));
+    let actuator = VelocityActuator::new("velocity", 1e-6);
+    // Keeping the signals speeds the ball up, turning the thruster off keeps the velocity
+    let plan = actuator
+        .plan(&system, &change(1.0, 0.0), STEP * si::S)
+        .unwrap();
+    assert!(plan.signals.is_empty());
+    let plan = actuator
+        .plan(&system, &change(0.0, 0.0), STEP * si::S)
+        .unwrap();
+    assert_eq!(plan.signals, vec![("thruster", 0.0)]);
+    let plan = actuator
+        .plan(&system, &change(-1.0, 0.0), STEP * si::S)
+        .unwrap();
+    assert_eq!(plan.signals.len(), 1);
+    assert_eq!(plan.signals[0].0, "thruster");
+    assert!((plan.signals[0].1 + 1.0).abs() < 1e-9);
+    // Only the brake can stop the ball, it cannot reverse it
+    let plan = actuator
+        .plan(&system, &change(-3.0, 0.0), STEP * si::S)
+        .unwrap();
+    assert_eq!(plan.signals.len(), 2);
+    match actuator.plan(&system, &change(-4.0, 0.0), STEP * si::S) {
+        Err(ActuationError::Unachievable { residual }) => assert!((residual - 1.0).abs() < 1e-6),
+        result => panic!("Unexpected plan {:?}", result),
+    }
+}
+
+#[test]
+fn test_many_actuators() {
+    let mut system = system(Vector3d::new(0.0, 0.0, 0.0) * si::MPS);
+    let identifiers: Vec<&'static str> = (0..40)
+        .map(|idx| &*Box::leak(format!("thruster {}", idx).into_boxed_str()))
+        .collect();
+    for identifier in &identifiers {
+        system.add_actuator(thruster(identifier, 1.0, 0.0));
+    }
+    let actuator = VelocityActuator::new("velocity", 1e-6);
+    let plan = actuator
+        .plan(&system, &change(2.55, 0.0), STEP * si::S)
+        .unwrap();
+    assert_eq!(plan.signals.len(), 26);
+}
+
+#[test]
+fn test_errors() {
+    let mut system = system(Vector3d::new(0.0, 0.0, 0.0) * si::MPS);
+    system.add_actuator(thruster("thruster", 1.0, 0.0));
+    let actuator = VelocityActuator::new("velocity", 1e-6);
+    let mut location_change = change(1.0, 0.0);
+    location_change.value = Value::Location(Vector3d::new(1.0, 0.0, 0.0) * si::M);
+    assert!(matches!(
+        actuator.plan(&system, &location_change, STEP * si::S),
+        Err(ActuationError::UnsupportedProperty(_))
+    ));
+    let mut empty_region = change(1.0, 0.0);
+    empty_region.region = Region::cuboid(
+        Vector3d::new(50.0, 0.0, 0.0) * si::M,
+        Vector3d::new(1.0, 1.0, 1.0) * si::M,
+    );
+    assert!(matches!(
+        actuator.plan(&system, &empty_region, STEP * si::S),
+        Err(ActuationError::NoTargets)
+    ));
+    assert!(matches!(
+        actuator.plan(&system, &change(0.0, 1.0), STEP * si::S),
+        Err(ActuationError::Unachievable { .. })
+    ));
+}
@@ -0,0 +1,168 @@
+use dimensioned::si;
+use vector3d::Vector3d;
+
+use physical_machine::actuators::actuator::Mounting;
+use physical_machine::physics::entity::Entity;
+use physical_machine::physics::state::shape::{Shape, Sphere};
+use physical_machine::physics::state::state::State;
+use physical_machine::physics::system::System;
+use physical_machine::sensors::contact_sensor::ContactSensor;
+use physical_machine::sensors::error_model::ErrorModel;
+use physical_machine::sensors::position_sensor::PositionSensor;
+use physical_machine::sensors::sensor::{MeasurementRegion, Pose};
+use physical_machine::sensors::velocity_sensor::VelocitySensor;
+use physical_machine::virtualization::location::EntityLocation;
+use physical_machine::virtualization::velocity::EntityVelocity;
+use physical_machine::virtualization::virtual_sensor::{Value, VirtualSensor};
+
+mod common;
+
+use common::interactions::interactions;
+
+// A ball rolling along the x axis at one meter per second
+fn system() -> System {
+    let ball = Entity::new(
+        "Ball",
+        State::new(
+            Vector3d::new(0.0, 0.0, 0.0) * si::M,
+            Vector3d::new(1.0, 0.0, 0.0) * si::MPS,
+            Vector3d::new(0.0, 0.0, 0.0) * si::N,
+            1.0 * si::KG,
+            Shape::Sphere(Sphere {
+                radius: 1.0 * si::M,
+            }),
+        ),
+    );
+    System::new(vec![ball], interactions(), 0.0 * si::S)
+}
+
+fn run(system: &mut System, steps: usize) {
+    for _ in 0..steps {
+        system.next_state(0.01 * si::S);
+    }
+}
+
+fn add_gps(system: &mut System, identifier: &'static str, models: Vec<ErrorModel>) {
+    let mut pose = Pose::new(Mounting::Entity("Ball"));
+    pose.offset = Vector3d::new(0.0, 0.5, 0.0) * si::M;
+    system.add_sensor(Box::new(PositionSensor::new(identifier, pose, 100.0)));
+    system.set_error_models(identifier, models);
+}
+
+fn get_location(value: Value) -> Vector3d<si::Meter<f64>> {
+    match value {
+        Value::Location(location) => location,
+        _ => panic!("Value {:?} is not a location.", value),
+    }
+}
+
+#[test]
+fn test_no_sensors() {
+    let mut system = system();
+    run(&mut system, 10);
+    let location = EntityLocation::new("location", "Ball", 0.1 * si::S);
+    assert_eq!(location.observe(&system), None);
+}
+
+#[test]
+fn test_fuses_noisy_position_sensors() {
+    let mut system = system();
+    add_gps(
+        &mut system,
+        "gps 1",
+        vec![ErrorModel::Gaussian {
+            standard_deviation: 0.1,
+        }],
+    );
+    add_gps(
+        &mut system,
+        "gps 2",
+        vec![ErrorModel::Gaussian {
+            standard_deviation: 0.1,
+        }],
+    );
+    run(&mut system, 100);
+    let location = EntityLocation::new("location", "Ball", 0.1 * si::S);
+    let observation = location.observe(&system).unwrap();
+    let value = get_location(observation.value);
+    assert!((value.x.value_unsafe - 1.0).abs() < 0.5);
+    assert!(value.y.value_unsafe.abs() < 0.5);
+    assert!((observation.uncertainty - 0.1 / 2.0_f64.sqrt()).abs() < 1e-9);
+    assert_eq!(observation.confidence, 1.0);
+    assert_eq!(observation.sensors, vec!["gps 1", "gps 2"]);
+    assert_eq!(
+        observation.region,
+        MeasurementRegion::Sphere {
+            center: value,
+            radius: 2.0 * observation.uncertainty * si::M,
+        }
+    );
+}
+
+#[test]
+fn test_ideal_sensor_outweighs_noisy_sensor() {
+    let mut system = system();
+    add_gps(&mut system, "gps 1", vec![]);
+    add_gps(&mut system, "gps 2", vec![ErrorModel::Bias(10.0)]);
+    run(&mut system, 100);
+    let location = EntityLocation::new("location", "Ball", 0.1 * si::S);
+    let value = get_location(location.observe(&system).unwrap().value);
+    let expected = system.get_entities()[0].get_state().get_location();
+    assert!((value - expected).norm2().value_unsafe < 1e-12);
+}
+
+#[test]
+fn test_contact_plate() {
+    let mut system = system();
+    system.add_sensor(Box::new(ContactSensor::new(
+        "plate",
+        Pose::new(Mounting::Location(Vector3d::new(1.5, 0.0, 0.0) * si::M)),
+        100.0,
+    )));
+    let location = EntityLocation::new("location", "Ball", 0.1 * si::S);
+    run(&mut system, 100);
+    let observation = location.observe(&system).unwrap();
+    assert_eq!(
+        observation.value,
+        Value::Location(Vector3d::new(1.5, 0.0, 0.0) * si::M)
+    );
+    assert_eq!(observation.uncertainty, 0.5);
+    // The ball has rolled off the plate
+    run(&mut system, 200);
+    assert_eq!(location.observe(&system), None);
+}
+
+#[test]
+fn test_dropouts_reduce_confidence() {
+    let mut system = system();
+    add_gps(&mut system, "gps 1", vec![]);
+    add_gps(
+        &mut system,
+        "gps 2",
+        vec![ErrorModel::Dropout { probability: 1.0 }],
+    );
+    run(&mut system, 100);
+    let location = EntityLocation::new("location", "Ball", 0.1 * si::S);
+    let observation = location.observe(&system).unwrap();
+    assert_eq!(observation.confidence, 0.5);
+    assert_eq!(observation.sensors, vec!["gps 1"]);
+}
+
+#[test]
+fn test_stale_readings() {
+    let mut system = system();
+    let pose = Pose::new(Mounting::Entity("Ball"));
+    system.add_sensor(Box::new(VelocitySensor::new("speedometer", pose, 1.0)));
+    let velocity = EntityVelocity::new("velocity", "Ball", 0.5 * si::S);
+    run(&mut system, 105);
+    let fresh = velocity.observe(&system).unwrap();
+    assert_eq!(
+        fresh.value,
+        Value::Velocity(Vector3d::new(1.0, 0.0, 0.0) * si::MPS)
+    );
+    run(&mut system, 20);
+    let older = velocity.observe(&system).unwrap();
+    assert!(older.confidence < fresh.confidence);
+    run(&mut system, 40);
+    assert_eq!(velocity.observe(&system), None);
+}